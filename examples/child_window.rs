@@ -1,4 +1,7 @@
-#[cfg(all(feature = "rwh_06", any(x11_platform, macos_platform, windows_platform)))]
+#[cfg(all(
+    feature = "rwh_06",
+    any(x11_platform, wayland_platform, macos_platform, windows_platform)
+))]
 #[allow(deprecated)]
 fn main() -> Result<(), impl std::error::Error> {
     use std::collections::HashMap;
@@ -89,10 +92,13 @@ fn main() -> Result<(), impl std::error::Error> {
     event_loop.run_app(Application::default())
 }
 
-#[cfg(all(feature = "rwh_06", not(any(x11_platform, macos_platform, windows_platform))))]
+#[cfg(all(
+    feature = "rwh_06",
+    not(any(x11_platform, wayland_platform, macos_platform, windows_platform))
+))]
 fn main() {
     panic!(
-        "This example is supported only on x11, macOS, and Windows, with the `rwh_06` feature \
-         enabled."
+        "This example is supported only on X11, Wayland, macOS, and Windows, with the `rwh_06` \
+         feature enabled."
     );
 }
@@ -17,7 +17,9 @@ use rwh_06::{DisplayHandle, HasDisplayHandle};
 use softbuffer::{Context, Surface};
 use winit::application::ApplicationHandler;
 use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize};
-use winit::event::{DeviceEvent, DeviceId, Ime, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::event::{
+    Compose, DeviceEvent, DeviceId, Ime, MouseButton, MouseScrollDelta, WindowEvent,
+};
 use winit::event_loop::{ActiveEventLoop, EventLoop};
 use winit::keyboard::{Key, ModifiersState};
 #[cfg(macos_platform)]
@@ -383,6 +385,15 @@ impl ApplicationHandler for Application {
                 info!("Theme changed to {theme:?}");
                 window.set_draw_theme(theme);
             },
+            WindowEvent::DecorationModeChanged(mode) => {
+                info!("Window={window_id:?} decoration mode changed to {mode:?}");
+            },
+            WindowEvent::StateChanged(state) => {
+                info!("Window={window_id:?} state changed to {state:?}");
+            },
+            WindowEvent::WmCapabilitiesChanged(capabilities) => {
+                info!("Window={window_id:?} WM capabilities changed to {capabilities:?}");
+            },
             WindowEvent::RedrawRequested => {
                 if let Err(err) = window.draw() {
                     error!("Error drawing window: {err}");
@@ -399,6 +410,12 @@ impl ApplicationHandler for Application {
                 window.modifiers = modifiers.state();
                 info!("Modifiers changed to {:?}", window.modifiers);
             },
+            WindowEvent::KeyboardLayoutChanged => {
+                info!("Keyboard layout changed");
+            },
+            WindowEvent::KeyboardShortcutsInhibited(inhibited) => {
+                info!("Window={window_id:?} keyboard shortcuts inhibited: {inhibited}");
+            },
             WindowEvent::MouseWheel { delta, .. } => match delta {
                 MouseScrollDelta::LineDelta(x, y) => {
                     info!("Mouse wheel Line Delta: ({x},{y})");
@@ -448,6 +465,10 @@ impl ApplicationHandler for Application {
                     }
                 }
             },
+            WindowEvent::Compose(event) => match event {
+                Compose::Preedit(text) => info!("Compose preedit: {text}"),
+                Compose::End => info!("Compose sequence ended"),
+            },
             WindowEvent::Ime(event) => match event {
                 Ime::Enabled => info!("IME enabled for Window={window_id:?}"),
                 Ime::Preedit(text, caret_pos) => {
@@ -492,6 +513,7 @@ impl ApplicationHandler for Application {
             | WindowEvent::DroppedFile(_)
             | WindowEvent::HoveredFile(_)
             | WindowEvent::Destroyed
+            | WindowEvent::PopupDismissed
             | WindowEvent::Touch(_)
             | WindowEvent::Moved(_) => (),
         }
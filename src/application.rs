@@ -1,11 +1,13 @@
 //! End user application handling.
 
-use crate::event::{DeviceEvent, DeviceId, StartCause, WindowEvent};
-use crate::event_loop::ActiveEventLoop;
+use crate::event::{DeviceEvent, DeviceId, ElementState, StartCause, WindowEvent};
+use crate::event_loop::{
+    ActiveEventLoop, FdId, FdReadiness, GlobalShortcutId, IdleTimeoutId, TimerId,
+};
 use crate::window::WindowId;
 
 /// The handler of the application events.
-pub trait ApplicationHandler {
+pub trait ApplicationHandler<T: 'static = ()> {
     /// Emitted when new events arrive from the OS to be processed.
     ///
     /// This is a useful place to put code that should be done before you start processing
@@ -184,6 +186,18 @@ pub trait ApplicationHandler {
         let _ = event_loop;
     }
 
+    /// Emitted when the event was sent with [`EventLoopProxy::send()`].
+    ///
+    /// The events are delivered in the order they were sent, right before
+    /// [`proxy_wake_up`][Self::proxy_wake_up] is called, since sending the event wakes up the
+    /// event loop as well. The events sent while handling this one are delivered on the next
+    /// wake up.
+    ///
+    /// [`EventLoopProxy::send()`]: crate::event_loop::EventLoopProxy::send
+    fn user_event(&mut self, event_loop: &ActiveEventLoop, event: T) {
+        let _ = (event_loop, event);
+    }
+
     /// Emitted when the OS sends an event to a winit window.
     fn window_event(
         &mut self,
@@ -325,10 +339,76 @@ pub trait ApplicationHandler {
     fn memory_warning(&mut self, event_loop: &ActiveEventLoop) {
         let _ = event_loop;
     }
+
+    /// Emitted when the file descriptor registered with [`EventLoopExtUnix::register_fd`] is
+    /// ready.
+    ///
+    /// The file descriptor is polled in the level-triggered mode, thus it'll be reported again on
+    /// the next iteration of the event loop unless it was drained.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    ///
+    /// [`EventLoopExtUnix::register_fd`]: crate::platform::unix::EventLoopExtUnix::register_fd
+    fn fd_ready(&mut self, event_loop: &ActiveEventLoop, fd_id: FdId, readiness: FdReadiness) {
+        let _ = (event_loop, fd_id, readiness);
+    }
+
+    /// Emitted when the timer set with [`EventLoopExtUnix::set_timeout`] or
+    /// [`EventLoopExtUnix::set_interval`] has expired.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    ///
+    /// [`EventLoopExtUnix::set_timeout`]: crate::platform::unix::EventLoopExtUnix::set_timeout
+    /// [`EventLoopExtUnix::set_interval`]: crate::platform::unix::EventLoopExtUnix::set_interval
+    fn timer_expired(&mut self, event_loop: &ActiveEventLoop, timer_id: TimerId) {
+        let _ = (event_loop, timer_id);
+    }
+
+    /// Emitted when the user has been idle for the timeout registered with
+    /// [`EventLoopExtUnix::add_idle_timeout`], with `idle` set to `true`, and once the user is
+    /// active again, with `idle` set to `false`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    ///
+    /// [`EventLoopExtUnix::add_idle_timeout`]: crate::platform::unix::EventLoopExtUnix::add_idle_timeout
+    fn idle_changed(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        timeout_id: IdleTimeoutId,
+        idle: bool,
+    ) {
+        let _ = (event_loop, timeout_id, idle);
+    }
+
+    /// Emitted when the global shortcut registered with
+    /// [`EventLoopExtUnix::register_global_shortcut`] is activated, with `state` set to
+    /// [`ElementState::Pressed`], and once it's deactivated, with [`ElementState::Released`].
+    ///
+    /// The shortcut is reported regardless of the focused window.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    ///
+    /// [`EventLoopExtUnix::register_global_shortcut`]: crate::platform::unix::EventLoopExtUnix::register_global_shortcut
+    fn global_shortcut(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        shortcut_id: GlobalShortcutId,
+        state: ElementState,
+    ) {
+        let _ = (event_loop, shortcut_id, state);
+    }
 }
 
 #[deny(clippy::missing_trait_methods)]
-impl<A: ?Sized + ApplicationHandler> ApplicationHandler for &mut A {
+impl<T: 'static, A: ?Sized + ApplicationHandler<T>> ApplicationHandler<T> for &mut A {
     #[inline]
     fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
         (**self).new_events(event_loop, cause);
@@ -349,6 +429,11 @@ impl<A: ?Sized + ApplicationHandler> ApplicationHandler for &mut A {
         (**self).proxy_wake_up(event_loop);
     }
 
+    #[inline]
+    fn user_event(&mut self, event_loop: &ActiveEventLoop, event: T) {
+        (**self).user_event(event_loop, event);
+    }
+
     #[inline]
     fn window_event(
         &mut self,
@@ -393,10 +478,40 @@ impl<A: ?Sized + ApplicationHandler> ApplicationHandler for &mut A {
     fn memory_warning(&mut self, event_loop: &ActiveEventLoop) {
         (**self).memory_warning(event_loop);
     }
+
+    #[inline]
+    fn fd_ready(&mut self, event_loop: &ActiveEventLoop, fd_id: FdId, readiness: FdReadiness) {
+        (**self).fd_ready(event_loop, fd_id, readiness);
+    }
+
+    #[inline]
+    fn timer_expired(&mut self, event_loop: &ActiveEventLoop, timer_id: TimerId) {
+        (**self).timer_expired(event_loop, timer_id);
+    }
+
+    #[inline]
+    fn idle_changed(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        timeout_id: IdleTimeoutId,
+        idle: bool,
+    ) {
+        (**self).idle_changed(event_loop, timeout_id, idle);
+    }
+
+    #[inline]
+    fn global_shortcut(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        shortcut_id: GlobalShortcutId,
+        state: ElementState,
+    ) {
+        (**self).global_shortcut(event_loop, shortcut_id, state);
+    }
 }
 
 #[deny(clippy::missing_trait_methods)]
-impl<A: ?Sized + ApplicationHandler> ApplicationHandler for Box<A> {
+impl<T: 'static, A: ?Sized + ApplicationHandler<T>> ApplicationHandler<T> for Box<A> {
     #[inline]
     fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
         (**self).new_events(event_loop, cause);
@@ -417,6 +532,11 @@ impl<A: ?Sized + ApplicationHandler> ApplicationHandler for Box<A> {
         (**self).proxy_wake_up(event_loop);
     }
 
+    #[inline]
+    fn user_event(&mut self, event_loop: &ActiveEventLoop, event: T) {
+        (**self).user_event(event_loop, event);
+    }
+
     #[inline]
     fn window_event(
         &mut self,
@@ -461,4 +581,34 @@ impl<A: ?Sized + ApplicationHandler> ApplicationHandler for Box<A> {
     fn memory_warning(&mut self, event_loop: &ActiveEventLoop) {
         (**self).memory_warning(event_loop);
     }
+
+    #[inline]
+    fn fd_ready(&mut self, event_loop: &ActiveEventLoop, fd_id: FdId, readiness: FdReadiness) {
+        (**self).fd_ready(event_loop, fd_id, readiness);
+    }
+
+    #[inline]
+    fn timer_expired(&mut self, event_loop: &ActiveEventLoop, timer_id: TimerId) {
+        (**self).timer_expired(event_loop, timer_id);
+    }
+
+    #[inline]
+    fn idle_changed(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        timeout_id: IdleTimeoutId,
+        idle: bool,
+    ) {
+        (**self).idle_changed(event_loop, timeout_id, idle);
+    }
+
+    #[inline]
+    fn global_shortcut(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        shortcut_id: GlobalShortcutId,
+        state: ElementState,
+    ) {
+        (**self).global_shortcut(event_loop, shortcut_id, state);
+    }
 }
@@ -48,11 +48,13 @@ use web_time::Instant;
 use crate::dpi::{PhysicalPosition, PhysicalSize};
 use crate::error::ExternalError;
 use crate::event_loop::AsyncRequestSerial;
-use crate::keyboard::{self, ModifiersKeyState, ModifiersKeys, ModifiersState};
+use crate::keyboard::{self, LockState, ModifiersKeyState, ModifiersKeys, ModifiersState};
 use crate::platform_impl;
 #[cfg(doc)]
 use crate::window::Window;
-use crate::window::{ActivationToken, Theme, WindowId};
+use crate::window::{
+    ActivationToken, DecorationMode, Theme, WindowId, WindowState, WmCapabilities,
+};
 
 // TODO: Remove once the backends can call `ApplicationHandler` methods directly. For now backends
 // like Windows and Web require `Event` to wire user events, otherwise each backend will have to
@@ -164,6 +166,18 @@ pub enum WindowEvent {
     /// The window has been destroyed.
     Destroyed,
 
+    /// The popup window was dismissed by the system and should be dropped.
+    ///
+    /// This happens, for example, when the user clicks outside of a popup created with
+    /// [`PopupAttributes::grab`] set.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Windows / macOS / iOS / Android / Web / Orbital:** Unsupported.
+    ///
+    /// [`PopupAttributes::grab`]: crate::window::PopupAttributes::grab
+    PopupDismissed,
+
     /// A file has been dropped into the window.
     ///
     /// When the user drops multiple files at once, this event will be emitted for each file
@@ -212,6 +226,29 @@ pub enum WindowEvent {
     /// The keyboard modifiers have changed.
     ModifiersChanged(Modifiers),
 
+    /// The active keyboard layout or the keymap itself has changed.
+    ///
+    /// Sent to the focused window. The new keymap could be queried with
+    /// [`EventLoopExtUnix::keymap`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **iOS / Android / Web / Windows / macOS / Orbital:** Unsupported.
+    ///
+    /// [`EventLoopExtUnix::keymap`]: crate::platform::unix::EventLoopExtUnix::keymap
+    KeyboardLayoutChanged,
+
+    /// The dead-key or Compose key sequence has progressed.
+    ///
+    /// Unlike [`WindowEvent::Ime`], it's sent regardless of [`Window::set_ime_allowed`], since the
+    /// keys of the sequence are still delivered with [`WindowEvent::KeyboardInput`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Only sent when the input isn't handled by the XIM server.
+    /// - **iOS / Android / Web / Windows / macOS / Orbital:** Unsupported.
+    Compose(Compose),
+
     /// An event from an input method.
     ///
     /// **Note:** You have to explicitly enable this event using [`Window::set_ime_allowed`].
@@ -263,7 +300,33 @@ pub enum WindowEvent {
     CursorLeft { device_id: DeviceId },
 
     /// A mouse wheel movement or touchpad scroll occurred.
-    MouseWheel { device_id: DeviceId, delta: MouseScrollDelta, phase: TouchPhase },
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Wheel deltas are in whole detents and `inverted` is always `false` when the
+    ///   compositor doesn't support `wl_seat` version 8 and 9 respectively.
+    /// - **X11:** The end of a touchpad scroll isn't reported, so `phase` is always
+    ///   [`TouchPhase::Moved`].
+    /// - **Orbital / Web / Windows:** `source` is always [`ScrollSource::Unknown`] and `inverted`
+    ///   is always `false`.
+    MouseWheel {
+        device_id: DeviceId,
+        /// The scrolled amount.
+        ///
+        /// High-resolution wheels report fractions of a detent in [`MouseScrollDelta::LineDelta`].
+        delta: MouseScrollDelta,
+        /// [`TouchPhase::Ended`] signals the end of a continuous scroll, e.g. the fingers were
+        /// lifted from the touchpad. The application may start kinetic scrolling at this point.
+        phase: TouchPhase,
+        /// The kind of device that produced the scroll.
+        source: ScrollSource,
+        /// Whether the user enabled natural scrolling, so the deltas move opposite to the
+        /// physical motion of the fingers or wheel.
+        ///
+        /// The deltas are already adjusted, this is meant for widgets mapping the physical
+        /// motion directly, like sliders.
+        inverted: bool,
+    },
 
     /// An mouse button press has been received.
     MouseInput { device_id: DeviceId, state: ElementState, button: MouseButton },
@@ -387,6 +450,47 @@ pub enum WindowEvent {
     /// - **iOS / Android / X11 / Wayland / Orbital:** Unsupported.
     ThemeChanged(Theme),
 
+    /// The negotiated decoration mode of the window has changed.
+    ///
+    /// Emitted once the initial mode is known and whenever the compositor switches it afterwards,
+    /// for example when a tiling compositor takes over drawing the decorations.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Reflects the mode negotiated through `zxdg_decoration_manager_v1`.
+    /// - **iOS / Android / Web / Windows / macOS / X11 / Orbital:** Unsupported.
+    DecorationModeChanged(DecorationMode),
+
+    /// The states of the window set by the window manager have changed.
+    ///
+    /// See [`Window::state`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **iOS / Android / Web / Windows / macOS / Orbital:** Unsupported.
+    ///
+    /// [`Window::state`]: crate::window::Window::state
+    StateChanged(WindowState),
+
+    /// The window management actions supported by the window manager have changed.
+    ///
+    /// Emitted once the capabilities are known and whenever they change afterwards.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **iOS / Android / Web / Windows / macOS / Orbital:** Unsupported.
+    WmCapabilitiesChanged(WmCapabilities),
+
+    /// The keyboard shortcuts inhibition requested with
+    /// [`Window::set_keyboard_shortcuts_inhibited`] has been granted or revoked.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **iOS / Android / Web / Windows / macOS / Orbital:** Unsupported.
+    ///
+    /// [`Window::set_keyboard_shortcuts_inhibited`]: crate::window::Window::set_keyboard_shortcuts_inhibited
+    KeyboardShortcutsInhibited(bool),
+
     /// The window has been occluded (completely hidden from view).
     ///
     /// This is different to window visibility as it depends on whether the window is closed,
@@ -450,6 +554,65 @@ impl DeviceId {
     }
 }
 
+/// Describes an input device, see [`ActiveEventLoop::devices`].
+///
+/// [`ActiveEventLoop::devices`]: crate::event_loop::ActiveEventLoop::devices
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceInfo {
+    /// The identifier used in the events of the device.
+    pub id: DeviceId,
+
+    /// The name of the device, as reported by the system.
+    pub name: String,
+
+    /// The kind of the device.
+    pub kind: DeviceKind,
+
+    /// The USB or Bluetooth vendor ID, if known.
+    pub vendor_id: Option<u16>,
+
+    /// The USB or Bluetooth product ID, if known.
+    pub product_id: Option<u16>,
+
+    /// The analog axes of the device, as reported by [`WindowEvent::AxisMotion`] and
+    /// [`DeviceEvent::Motion`].
+    pub axes: Vec<DeviceAxis>,
+}
+
+/// Describes the kind of an input device.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DeviceKind {
+    Keyboard,
+    /// A mouse, trackball or any other relative pointing device.
+    Pointer,
+    Touchpad,
+    Touchscreen,
+    /// A drawing tablet or pen.
+    Tablet,
+    /// A gamepad or joystick.
+    Gamepad,
+}
+
+/// Describes an analog axis of an input device.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceAxis {
+    /// The identifier of the axis in the events of the device.
+    pub id: AxisId,
+
+    /// The label of the axis, e.g. `Rel X` or `Abs Pressure`.
+    pub name: Option<String>,
+
+    /// The minimum value of the axis, equal to `max` when the axis isn't bounded.
+    pub min: f64,
+
+    /// The maximum value of the axis, equal to `min` when the axis isn't bounded.
+    pub max: f64,
+
+    /// The resolution of the axis in units per meter, `0` if unknown.
+    pub resolution: u32,
+}
+
 /// Represents raw hardware events that are not associated with any particular window.
 ///
 /// Useful for interactions that diverge significantly from a conventional 2D GUI, such as 3D camera
@@ -460,6 +623,9 @@ impl DeviceId {
 /// Note that these events are delivered regardless of input focus.
 #[derive(Clone, Debug, PartialEq)]
 pub enum DeviceEvent {
+    /// The device was added, its description is available with [`ActiveEventLoop::devices`].
+    ///
+    /// [`ActiveEventLoop::devices`]: crate::event_loop::ActiveEventLoop::devices
     Added,
     Removed,
 
@@ -509,6 +675,29 @@ pub enum DeviceEvent {
     },
 
     Key(RawKeyEvent),
+
+    /// A gamepad button was pressed or released.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only available on **Linux** when enabled with `EventLoopBuilderExtUnix::with_gamepads`.
+    GamepadButton {
+        button: GamepadButton,
+        state: ElementState,
+    },
+
+    /// A gamepad axis moved.
+    ///
+    /// The sticks are in the `-1.0..=1.0` range, with the positive values pointing right and
+    /// down, and the triggers are in the `0.0..=1.0` range.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only available on **Linux** when enabled with `EventLoopBuilderExtUnix::with_gamepads`.
+    GamepadAxis {
+        axis: GamepadAxis,
+        value: f64,
+    },
 }
 
 /// Describes a keyboard input as a raw device event.
@@ -671,6 +860,8 @@ pub struct Modifiers {
     //
     // The field providing a metadata, it shouldn't be used as a source of truth.
     pub(crate) pressed_mods: ModifiersKeys,
+
+    pub(crate) lock_state: LockState,
 }
 
 impl Modifiers {
@@ -679,6 +870,16 @@ impl Modifiers {
         self.state
     }
 
+    /// The state of the lock keys.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11 / Wayland:** The Scroll Lock is only reported when the keymap maps it to a modifier.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    pub fn lock_state(&self) -> LockState {
+        self.lock_state
+    }
+
     /// The state of the left shift key.
     pub fn lshift_state(&self) -> ModifiersKeyState {
         self.mod_state(ModifiersKeys::LSHIFT)
@@ -730,7 +931,7 @@ impl Modifiers {
 
 impl From<ModifiersState> for Modifiers {
     fn from(value: ModifiersState) -> Self {
-        Self { state: value, pressed_mods: Default::default() }
+        Self { state: value, pressed_mods: Default::default(), lock_state: Default::default() }
     }
 }
 
@@ -809,6 +1010,33 @@ pub enum Ime {
     Disabled,
 }
 
+/// Describes the progress of the dead-key or Compose key sequence.
+///
+/// This is used to show the pending sequence the way the native text widgets do. E.g. on a layout
+/// with the dead acute accent the following event sequence could be obtained:
+///
+/// ```ignore
+/// // Press the dead acute accent key
+/// Compose::Preedit("´")
+/// // Press "E" key, the "é" text is delivered with its `KeyEvent`
+/// Compose::End
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Compose {
+    /// The sequence is in progress, with the text to show for the keys pressed so far.
+    ///
+    /// The dead keys are represented with their non-combining variant, like `´`, and the
+    /// Compose key with `·`.
+    Preedit(String),
+
+    /// The sequence has been completed or cancelled.
+    ///
+    /// The composed text, if any, is delivered with the [`KeyEvent`] completing the sequence
+    /// right after this event.
+    End,
+}
+
 /// Describes touch-screen input state.
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -834,7 +1062,7 @@ pub enum TouchPhase {
 ///
 /// A [`TouchPhase::Cancelled`] event is emitted when the system has canceled tracking this
 /// touch, such as when the window loses focus, or on iOS if the user moves the
-/// device against their face.
+/// device against their face. The touch is over then, and its effects should be reverted.
 ///
 /// ## Platform-specific
 ///
@@ -859,6 +1087,22 @@ pub struct Touch {
     ///   sensitivity, force will either be 0.0 or 1.0. Also see the
     ///   [android documentation](https://developer.android.com/reference/android/view/MotionEvent#AXIS_PRESSURE).
     pub force: Option<Force>,
+    /// The size of the contact area approximated by an ellipse, with the major axis as the
+    /// `width` and the minor axis as the `height`. `None` if the device doesn't report it.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only available on **Wayland** and **X11**.
+    /// - **X11:** The size is converted from the device units assuming the touchscreen spans the
+    ///   whole screen.
+    pub contact_size: Option<PhysicalSize<f64>>,
+    /// The clockwise angle in degrees from the vertical axis of the window to the major axis of
+    /// the contact area, in the `-180.0..=180.0` range. `None` if the device doesn't report it.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only available on **Wayland** and **X11**.
+    pub orientation: Option<f64>,
     /// Unique identifier of a finger.
     pub id: u64,
 }
@@ -954,6 +1198,53 @@ pub enum MouseButton {
     Other(u16),
 }
 
+/// Describes a button of a gamepad in the standard layout.
+///
+/// The face buttons are named after their position, e.g. [`GamepadButton::South`] is `A` on the
+/// Xbox controller and `Cross` on the PlayStation one.
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GamepadButton {
+    South,
+    East,
+    West,
+    North,
+    LeftShoulder,
+    RightShoulder,
+    /// The left trigger, reported as a button as well when it's pressed past the half.
+    LeftTrigger,
+    /// The right trigger, reported as a button as well when it's pressed past the half.
+    RightTrigger,
+    /// The left stick pressed in.
+    LeftStick,
+    /// The right stick pressed in.
+    RightStick,
+    Select,
+    Start,
+    /// The button in the center, like the Xbox or PlayStation logo.
+    Mode,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    /// The button which isn't part of the standard layout, with the platform-specific code.
+    Other(u16),
+}
+
+/// Describes an axis of a gamepad in the standard layout.
+#[derive(Debug, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+    /// The axis which isn't part of the standard layout, with the platform-specific code.
+    Other(u16),
+}
+
 /// Describes a difference in the mouse scroll wheel state.
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -981,6 +1272,29 @@ pub enum MouseScrollDelta {
     PixelDelta(PhysicalPosition<f64>),
 }
 
+/// Describes the kind of device which produced a [`WindowEvent::MouseWheel`].
+#[derive(Debug, Default, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ScrollSource {
+    /// The source couldn't be determined.
+    #[default]
+    Unknown,
+
+    /// A mouse wheel, moving in discrete detents which may be split into high-resolution
+    /// fractions.
+    Wheel,
+
+    /// Fingers on a touchpad or touch surface, usually followed by a [`TouchPhase::Ended`] when
+    /// they are lifted.
+    Finger,
+
+    /// Continuous motion without a physical end, e.g. button scrolling with a trackpoint.
+    Continuous,
+
+    /// The side movement of a tilting mouse wheel.
+    WheelTilt,
+}
+
 /// Handle to synchronously change the size of the window from the
 /// [`WindowEvent`].
 #[derive(Debug, Clone)]
@@ -1018,7 +1332,7 @@ impl PartialEq for InnerSizeWriter {
 mod tests {
     use std::collections::{BTreeSet, HashSet};
 
-    use crate::dpi::PhysicalPosition;
+    use crate::dpi::{PhysicalPosition, PhysicalSize};
     use crate::event;
 
     macro_rules! foreach_event {
@@ -1047,6 +1361,7 @@ mod tests {
 
                 with_window_event(CloseRequested);
                 with_window_event(Destroyed);
+                with_window_event(PopupDismissed);
                 with_window_event(Focused(true));
                 with_window_event(Moved((0, 0).into()));
                 with_window_event(Resized((0, 0).into()));
@@ -1054,14 +1369,20 @@ mod tests {
                 with_window_event(HoveredFile("x.txt".into()));
                 with_window_event(HoveredFileCancelled);
                 with_window_event(Ime(Enabled));
+                with_window_event(Compose(event::Compose::Preedit("´".into())));
+                with_window_event(Compose(event::Compose::End));
                 with_window_event(CursorMoved { device_id: did, position: (0, 0).into() });
                 with_window_event(ModifiersChanged(event::Modifiers::default()));
+                with_window_event(KeyboardLayoutChanged);
+                with_window_event(KeyboardShortcutsInhibited(true));
                 with_window_event(CursorEntered { device_id: did });
                 with_window_event(CursorLeft { device_id: did });
                 with_window_event(MouseWheel {
                     device_id: did,
                     delta: event::MouseScrollDelta::LineDelta(0.0, 0.0),
                     phase: event::TouchPhase::Started,
+                    source: event::ScrollSource::Finger,
+                    inverted: false,
                 });
                 with_window_event(MouseInput {
                     device_id: did,
@@ -1092,8 +1413,13 @@ mod tests {
                     location: (0.0, 0.0).into(),
                     id: 0,
                     force: Some(event::Force::Normalized(0.0)),
+                    contact_size: Some(PhysicalSize::new(0.0, 0.0)),
+                    orientation: Some(0.0),
                 }));
                 with_window_event(ThemeChanged(crate::window::Theme::Light));
+                with_window_event(DecorationModeChanged(crate::window::DecorationMode::Server));
+                with_window_event(StateChanged(crate::window::WindowState::SUSPENDED));
+                with_window_event(WmCapabilitiesChanged(crate::window::WmCapabilities::all()));
                 with_window_event(Occluded(true));
             }
 
@@ -1112,6 +1438,11 @@ mod tests {
                 });
                 with_device_event(Motion { axis: 0, value: 0.0 });
                 with_device_event(Button { button: 0, state: event::ElementState::Pressed });
+                with_device_event(GamepadButton {
+                    button: event::GamepadButton::South,
+                    state: event::ElementState::Pressed,
+                });
+                with_device_event(GamepadAxis { axis: event::GamepadAxis::LeftStickX, value: 0.0 });
             }
         }};
     }
@@ -1168,6 +1499,8 @@ mod tests {
             location: (0.0, 0.0).into(),
             id: 0,
             force: Some(event::Force::Normalized(0.0)),
+            contact_size: None,
+            orientation: None,
         }
         .clone();
         let _ =
@@ -1,28 +1,35 @@
 //! The [`EventLoop`] struct and assorted supporting types, including
 //! [`ControlFlow`].
 //!
-//! If you want to send custom events to the event loop, use
-//! [`EventLoop::create_proxy`] to acquire an [`EventLoopProxy`] and call its
-//! [`wake_up`][EventLoopProxy::wake_up] method. Then during handling the wake up
-//! you can poll your event sources.
+//! If you want to send custom events to the event loop, create it with
+//! [`EventLoop::with_user_event`], use [`EventLoop::create_proxy`] to acquire an
+//! [`EventLoopProxy`] and call its [`send`][EventLoopProxy::send] method. The events are
+//! delivered via [`ApplicationHandler::user_event`]. To only wake up the event loop, call
+//! [`wake_up`][EventLoopProxy::wake_up] instead, and then during handling the wake up you can
+//! poll your event sources.
 //!
 //! See the root-level documentation for information on how to create and use an event loop to
 //! handle events.
-use std::fmt;
 use std::marker::PhantomData;
 #[cfg(any(x11_platform, wayland_platform))]
 use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+#[cfg(not(any(x11_platform, wayland_platform)))]
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 #[cfg(not(web_platform))]
 use std::time::{Duration, Instant};
+use std::{error, fmt};
 
 #[cfg(web_platform)]
 use web_time::{Duration, Instant};
 
 use crate::application::ApplicationHandler;
 use crate::error::{EventLoopError, OsError};
+use crate::event::DeviceInfo;
 use crate::monitor::MonitorHandle;
 use crate::platform_impl;
+use crate::user_events::{self, UserEventHandler, UserEventQueue, UserEventSender};
 use crate::window::{CustomCursor, CustomCursorSource, Window, WindowAttributes};
 
 /// Provides a way to retrieve events from the system and from the windows that were registered to
@@ -39,9 +46,13 @@ use crate::window::{CustomCursor, CustomCursorSource, Window, WindowAttributes};
 /// the [`Window`] created from this _can_ be sent to an other thread, and the
 /// [`EventLoopProxy`] allows you to wake up an `EventLoop` from another thread.
 ///
+/// The events of type `T` sent through the [`EventLoopProxy`] are delivered via
+/// [`ApplicationHandler::user_event`].
+///
 /// [`Window`]: crate::window::Window
-pub struct EventLoop {
+pub struct EventLoop<T = ()> {
     pub(crate) event_loop: platform_impl::EventLoop,
+    pub(crate) user_events: Arc<UserEventQueue<T>>,
     pub(crate) _marker: PhantomData<*mut ()>, // Not Send nor Sync
 }
 
@@ -57,17 +68,44 @@ pub struct ActiveEventLoop {
 /// Object that allows building the event loop.
 ///
 /// This is used to make specifying options that affect the whole application
-/// easier. But note that constructing multiple event loops is not supported.
+/// easier. But note that constructing multiple event loops is not supported, except on X11 and
+/// Wayland, where one event loop per thread could be created.
 ///
-/// This can be created using [`EventLoop::builder`].
-#[derive(Default)]
-pub struct EventLoopBuilder {
+/// This can be created using [`EventLoop::builder`] or [`EventLoop::with_user_event`].
+pub struct EventLoopBuilder<T = ()> {
     pub(crate) platform_specific: platform_impl::PlatformSpecificEventLoopAttributes,
+    user_event_capacity: usize,
+    _p: PhantomData<T>,
 }
 
+#[cfg(not(any(x11_platform, wayland_platform)))]
 static EVENT_LOOP_CREATED: AtomicBool = AtomicBool::new(false);
 
-impl EventLoopBuilder {
+// NOTE: The event loops on X11 and Wayland are independent, thus one per thread is allowed.
+#[cfg(any(x11_platform, wayland_platform))]
+thread_local! {
+    static EVENT_LOOP_CREATED: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Mark the event loop as created, returning whether it was created already.
+fn mark_event_loop_created() -> bool {
+    #[cfg(not(any(x11_platform, wayland_platform)))]
+    return EVENT_LOOP_CREATED.swap(true, Ordering::Relaxed);
+    #[cfg(any(x11_platform, wayland_platform))]
+    return EVENT_LOOP_CREATED.with(|created| created.replace(true));
+}
+
+impl<T: 'static> EventLoopBuilder<T> {
+    /// The maximum amount of the user events waiting to be delivered.
+    ///
+    /// Once it's reached, [`EventLoopProxy::send`] returns [`SendError::Full`]. The default is
+    /// `1024`.
+    #[inline]
+    pub fn with_user_event_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.user_event_capacity = capacity;
+        self
+    }
+
     /// Builds a new event loop.
     ///
     /// ***For cross-platform compatibility, the [`EventLoop`] must be created on the main thread,
@@ -88,6 +126,10 @@ impl EventLoopBuilder {
     ///
     /// - **Wayland/X11:** to prevent running under `Wayland` or `X11` unset `WAYLAND_DISPLAY` or
     ///   `DISPLAY` respectively when building the event loop.
+    /// - **Wayland/X11:** one event loop could be created per thread, the threads other than the
+    ///   main one require `with_any_thread`. Every event loop has its own connection to the display
+    ///   server, thus the windows, custom cursors, monitors and proxies obtained from the event
+    ///   loop could only be used with that event loop.
     /// - **Android:** must be configured with an `AndroidApp` from `android_main()` by calling
     ///   [`.with_android_app(app)`] before calling `.build()`, otherwise it'll panic.
     ///
@@ -102,10 +144,10 @@ impl EventLoopBuilder {
         doc = "[`.with_android_app(app)`]: #only-available-on-android"
     )]
     #[inline]
-    pub fn build(&mut self) -> Result<EventLoop, EventLoopError> {
+    pub fn build(&mut self) -> Result<EventLoop<T>, EventLoopError> {
         let _span = tracing::debug_span!("winit::EventLoopBuilder::build").entered();
 
-        if EVENT_LOOP_CREATED.swap(true, Ordering::Relaxed) {
+        if mark_event_loop_created() {
             return Err(EventLoopError::RecreationAttempt);
         }
 
@@ -113,6 +155,7 @@ impl EventLoopBuilder {
         #[allow(clippy::unnecessary_mut_passed)]
         Ok(EventLoop {
             event_loop: platform_impl::EventLoop::new(&mut self.platform_specific)?,
+            user_events: Arc::new(UserEventQueue::new(self.user_event_capacity)),
             _marker: PhantomData,
         })
     }
@@ -123,7 +166,7 @@ impl EventLoopBuilder {
     }
 }
 
-impl fmt::Debug for EventLoop {
+impl<T> fmt::Debug for EventLoop<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.pad("EventLoop { .. }")
     }
@@ -196,11 +239,24 @@ impl EventLoop {
     /// To get the actual event loop, call [`build`][EventLoopBuilder::build] on that.
     #[inline]
     pub fn builder() -> EventLoopBuilder {
-        EventLoopBuilder { platform_specific: Default::default() }
+        Self::with_user_event()
     }
 }
 
-impl EventLoop {
+impl<T: 'static> EventLoop<T> {
+    /// Start building a new event loop, with the given type of the user events.
+    ///
+    /// The events are sent with [`EventLoopProxy::send`] and delivered via
+    /// [`ApplicationHandler::user_event`].
+    #[inline]
+    pub fn with_user_event() -> EventLoopBuilder<T> {
+        EventLoopBuilder {
+            platform_specific: Default::default(),
+            user_event_capacity: user_events::DEFAULT_CAPACITY,
+            _p: PhantomData,
+        }
+    }
+
     /// Run the application with the event loop on the calling thread.
     ///
     /// See the [`set_control_flow()`] docs on how to change the event loop's behavior.
@@ -232,14 +288,17 @@ impl EventLoop {
     /// [`run_app()`]: Self::run_app()
     #[inline]
     #[cfg(not(all(web_platform, target_feature = "exception-handling")))]
-    pub fn run_app<A: ApplicationHandler>(self, app: A) -> Result<(), EventLoopError> {
-        self.event_loop.run_app(app)
+    pub fn run_app<A: ApplicationHandler<T>>(self, app: A) -> Result<(), EventLoopError> {
+        self.event_loop.run_app(UserEventHandler::new(app, self.user_events))
     }
 
     /// Creates an [`EventLoopProxy`] that can be used to dispatch user events
     /// to the main event loop, possibly from another thread.
-    pub fn create_proxy(&self) -> EventLoopProxy {
-        EventLoopProxy { event_loop_proxy: self.event_loop.window_target().p.create_proxy() }
+    pub fn create_proxy(&self) -> EventLoopProxy<T> {
+        EventLoopProxy {
+            event_loop_proxy: self.event_loop.window_target().p.create_proxy(),
+            user_events: Some(self.user_events.sender()),
+        }
     }
 
     /// Gets a persistent reference to the underlying platform display.
@@ -276,14 +335,14 @@ impl EventLoop {
 }
 
 #[cfg(feature = "rwh_06")]
-impl rwh_06::HasDisplayHandle for EventLoop {
+impl<T> rwh_06::HasDisplayHandle for EventLoop<T> {
     fn display_handle(&self) -> Result<rwh_06::DisplayHandle<'_>, rwh_06::HandleError> {
         rwh_06::HasDisplayHandle::display_handle(self.event_loop.window_target())
     }
 }
 
 #[cfg(feature = "rwh_05")]
-unsafe impl rwh_05::HasRawDisplayHandle for EventLoop {
+unsafe impl<T> rwh_05::HasRawDisplayHandle for EventLoop<T> {
     /// Returns a [`rwh_05::RawDisplayHandle`] for the event loop.
     fn raw_display_handle(&self) -> rwh_05::RawDisplayHandle {
         rwh_05::HasRawDisplayHandle::raw_display_handle(self.event_loop.window_target())
@@ -291,38 +350,46 @@ unsafe impl rwh_05::HasRawDisplayHandle for EventLoop {
 }
 
 #[cfg(any(x11_platform, wayland_platform))]
-impl AsFd for EventLoop {
+impl<T> AsFd for EventLoop<T> {
     /// Get the underlying [EventLoop]'s `fd` which you can register
     /// into other event loop, like [`calloop`] or [`mio`]. When doing so, the
-    /// loop must be polled with the [`pump_app_events`] API.
+    /// loop must be polled with the [`pump_app_events`] API, at the latest by the
+    /// [`next_deadline`].
     ///
     /// [`calloop`]: https://crates.io/crates/calloop
     /// [`mio`]: https://crates.io/crates/mio
     /// [`pump_app_events`]: crate::platform::pump_events::EventLoopExtPumpEvents::pump_app_events
+    /// [`next_deadline`]: crate::platform::unix::EventLoopExtUnix::next_deadline
     fn as_fd(&self) -> BorrowedFd<'_> {
         self.event_loop.as_fd()
     }
 }
 
 #[cfg(any(x11_platform, wayland_platform))]
-impl AsRawFd for EventLoop {
+impl<T> AsRawFd for EventLoop<T> {
     /// Get the underlying [EventLoop]'s raw `fd` which you can register
     /// into other event loop, like [`calloop`] or [`mio`]. When doing so, the
-    /// loop must be polled with the [`pump_app_events`] API.
+    /// loop must be polled with the [`pump_app_events`] API, at the latest by the
+    /// [`next_deadline`].
     ///
     /// [`calloop`]: https://crates.io/crates/calloop
     /// [`mio`]: https://crates.io/crates/mio
     /// [`pump_app_events`]: crate::platform::pump_events::EventLoopExtPumpEvents::pump_app_events
+    /// [`next_deadline`]: crate::platform::unix::EventLoopExtUnix::next_deadline
     fn as_raw_fd(&self) -> RawFd {
         self.event_loop.as_raw_fd()
     }
 }
 
 impl ActiveEventLoop {
-    /// Creates an [`EventLoopProxy`] that can be used to dispatch user events
-    /// to the main event loop, possibly from another thread.
+    /// Creates an [`EventLoopProxy`] that can be used to wake up the main event loop, possibly
+    /// from another thread.
+    ///
+    /// The proxy isn't connected to the user event queue, thus [`EventLoopProxy::send`] always
+    /// returns [`SendError::Unsupported`]. Use [`EventLoop::create_proxy`] to send the user
+    /// events.
     pub fn create_proxy(&self) -> EventLoopProxy {
-        EventLoopProxy { event_loop_proxy: self.p.create_proxy() }
+        EventLoopProxy { event_loop_proxy: self.p.create_proxy(), user_events: None }
     }
 
     /// Create the window.
@@ -341,6 +408,19 @@ impl ActiveEventLoop {
         )
         .entered();
 
+        #[cfg(feature = "serde")]
+        let window_attributes = {
+            let mut window_attributes = window_attributes;
+            if let Some(placement) = window_attributes.placement.take() {
+                placement.apply(
+                    &mut window_attributes,
+                    self.available_monitors().collect(),
+                    self.primary_monitor(),
+                );
+            }
+            window_attributes
+        };
+
         let window = platform_impl::Window::new(&self.p, window_attributes)?;
         Ok(Window { window })
     }
@@ -390,6 +470,23 @@ impl ActiveEventLoop {
         self.p.primary_monitor().map(|inner| MonitorHandle { inner })
     }
 
+    /// Returns the description of the input devices currently connected.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** The physical devices aren't exposed, a device is returned for every
+    ///   capability of the seats with the name of the seat. All the devices share the same
+    ///   [`DeviceId`] and no axes are reported.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, returns no device.
+    ///
+    /// [`DeviceId`]: crate::event::DeviceId
+    #[inline]
+    pub fn devices(&self) -> impl Iterator<Item = DeviceInfo> {
+        let _span = tracing::debug_span!("winit::ActiveEventLoop::devices",).entered();
+
+        self.p.devices().into_iter()
+    }
+
     /// Change if or when [`DeviceEvent`]s are captured.
     ///
     /// Since the [`DeviceEvent`] capture can lead to high CPU usage for unfocused windows, winit
@@ -509,12 +606,35 @@ unsafe impl rwh_05::HasRawDisplayHandle for OwnedDisplayHandle {
 }
 
 /// Control the [`EventLoop`], possibly from a different thread, without referencing it directly.
-#[derive(Clone)]
-pub struct EventLoopProxy {
+pub struct EventLoopProxy<T = ()> {
     event_loop_proxy: platform_impl::EventLoopProxy,
+    user_events: Option<UserEventSender<T>>,
 }
 
-impl EventLoopProxy {
+impl<T: 'static> EventLoopProxy<T> {
+    /// Send the event to the [`EventLoop`], waking it up.
+    ///
+    /// The event is delivered via [`ApplicationHandler::user_event()`] in the order the events
+    /// were sent from all the proxies. The event is given back in the error when the queue is
+    /// full, the event loop has exited, or the proxy was created by
+    /// [`ActiveEventLoop::create_proxy`].
+    ///
+    /// # Platform-specific
+    ///
+    /// - **Windows**: The wake-up may be ignored under high contention, see [#3687], delaying the
+    ///   event until the next wake-up.
+    ///
+    /// [#3687]: https://github.com/rust-windowing/winit/pull/3687
+    pub fn send(&self, event: T) -> Result<(), SendError<T>> {
+        match &self.user_events {
+            Some(user_events) => user_events.send(event)?,
+            None => return Err(SendError::Unsupported(event)),
+        }
+
+        self.event_loop_proxy.wake_up();
+        Ok(())
+    }
+
     /// Wake up the [`EventLoop`], resulting in [`ApplicationHandler::proxy_wake_up()`] being
     /// called.
     ///
@@ -535,12 +655,66 @@ impl EventLoopProxy {
     }
 }
 
-impl fmt::Debug for EventLoopProxy {
+impl<T> Clone for EventLoopProxy<T> {
+    fn clone(&self) -> Self {
+        Self {
+            event_loop_proxy: self.event_loop_proxy.clone(),
+            user_events: self.user_events.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for EventLoopProxy<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.pad("EventLoopProxy { .. }")
     }
 }
 
+/// The error returned by [`EventLoopProxy::send`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SendError<T> {
+    /// The queue of the user events is full.
+    ///
+    /// See [`EventLoopBuilder::with_user_event_capacity`].
+    Full(T),
+    /// The event loop has exited.
+    Closed(T),
+    /// The proxy can't send the user events, since it was created by
+    /// [`ActiveEventLoop::create_proxy`].
+    Unsupported(T),
+}
+
+impl<T> SendError<T> {
+    /// Get the event which wasn't sent back.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(event) | Self::Closed(event) | Self::Unsupported(event) => event,
+        }
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => f.pad("Full(..)"),
+            Self::Closed(_) => f.pad("Closed(..)"),
+            Self::Unsupported(_) => f.pad("Unsupported(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => f.pad("the user event queue is full"),
+            Self::Closed(_) => f.pad("the event loop has exited"),
+            Self::Unsupported(_) => f.pad("the proxy isn't connected to the user event queue"),
+        }
+    }
+}
+
+impl<T> error::Error for SendError<T> {}
+
 /// Control when device events are captured.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
 pub enum DeviceEvents {
@@ -562,7 +736,7 @@ pub enum DeviceEvents {
 /// containing [`AsyncRequestSerial`] and some closure associated with it.
 /// Then once event is arriving the working list is being traversed and a job
 /// executed and removed from the list.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct AsyncRequestSerial {
     serial: usize,
 }
@@ -578,3 +752,50 @@ impl AsyncRequestSerial {
         Self { serial }
     }
 }
+
+/// Identifier of the file descriptor registered with the event loop.
+///
+/// See [`EventLoopExtUnix::register_fd`].
+///
+/// [`EventLoopExtUnix::register_fd`]: crate::platform::unix::EventLoopExtUnix::register_fd
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FdId(pub(crate) u64);
+
+/// Identifier of the timer registered with the event loop.
+///
+/// See [`EventLoopExtUnix::set_timeout`].
+///
+/// [`EventLoopExtUnix::set_timeout`]: crate::platform::unix::EventLoopExtUnix::set_timeout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerId(pub(crate) u64);
+
+/// Identifier of the idle timeout registered with the event loop.
+///
+/// See [`EventLoopExtUnix::add_idle_timeout`].
+///
+/// [`EventLoopExtUnix::add_idle_timeout`]: crate::platform::unix::EventLoopExtUnix::add_idle_timeout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IdleTimeoutId(pub(crate) u64);
+
+/// Identifier of the global shortcut registered with the event loop.
+///
+/// See [`EventLoopExtUnix::register_global_shortcut`].
+///
+/// [`EventLoopExtUnix::register_global_shortcut`]: crate::platform::unix::EventLoopExtUnix::register_global_shortcut
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GlobalShortcutId(pub(crate) u64);
+
+/// The readiness of the registered file descriptor.
+///
+/// See [`ApplicationHandler::fd_ready`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FdReadiness {
+    /// The file descriptor is readable.
+    pub readable: bool,
+
+    /// The file descriptor is writable.
+    pub writable: bool,
+
+    /// The file descriptor is in an error state.
+    pub error: bool,
+}
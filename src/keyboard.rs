@@ -1709,6 +1709,18 @@ bitflags! {
         const ALT = 0b100 << 6;
         /// This is the "windows" key on PC and "command" key on Mac.
         const SUPER = 0b100 << 9;
+        /// The "AltGr" key, also known as the level 3 shift.
+        const ALT_GRAPH = 0b100 << 12;
+        /// The level 5 shift, used by some layouts to type the additional characters.
+        const LEVEL5 = 0b100 << 15;
+        /// The "hyper" key.
+        ///
+        /// Only reported when it's distinct from [`ModifiersState::SUPER`].
+        const HYPER = 0b100 << 18;
+        /// The "meta" key.
+        ///
+        /// Only reported when it's distinct from [`ModifiersState::ALT`].
+        const META = 0b100 << 21;
     }
 }
 
@@ -1732,6 +1744,41 @@ impl ModifiersState {
     pub fn super_key(&self) -> bool {
         self.intersects(Self::SUPER)
     }
+
+    /// Returns `true` if the AltGr key is pressed.
+    pub fn alt_graph_key(&self) -> bool {
+        self.intersects(Self::ALT_GRAPH)
+    }
+
+    /// Returns `true` if the level 5 shift is pressed.
+    pub fn level5_key(&self) -> bool {
+        self.intersects(Self::LEVEL5)
+    }
+
+    /// Returns `true` if the hyper key is pressed.
+    pub fn hyper_key(&self) -> bool {
+        self.intersects(Self::HYPER)
+    }
+
+    /// Returns `true` if the meta key is pressed.
+    pub fn meta_key(&self) -> bool {
+        self.intersects(Self::META)
+    }
+}
+
+bitflags! {
+    /// The state of the lock keys.
+    ///
+    /// Each flag is set if the lock is engaged.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct LockState: u8 {
+        /// The "Caps Lock" key.
+        const CAPS_LOCK = 0b001;
+        /// The "Num Lock" key.
+        const NUM_LOCK = 0b010;
+        /// The "Scroll Lock" key.
+        const SCROLL_LOCK = 0b100;
+    }
 }
 
 /// The state of the particular modifiers key.
@@ -1780,6 +1827,10 @@ mod modifiers_serde {
         pub control_key: bool,
         pub alt_key: bool,
         pub super_key: bool,
+        pub alt_graph_key: bool,
+        pub level5_key: bool,
+        pub hyper_key: bool,
+        pub meta_key: bool,
     }
 
     impl Serialize for ModifiersState {
@@ -1792,6 +1843,10 @@ mod modifiers_serde {
                 control_key: self.control_key(),
                 alt_key: self.alt_key(),
                 super_key: self.super_key(),
+                alt_graph_key: self.alt_graph_key(),
+                level5_key: self.level5_key(),
+                hyper_key: self.hyper_key(),
+                meta_key: self.meta_key(),
             };
             s.serialize(serializer)
         }
@@ -1802,13 +1857,25 @@ mod modifiers_serde {
         where
             D: Deserializer<'de>,
         {
-            let ModifiersStateSerialize { shift_key, control_key, alt_key, super_key } =
-                ModifiersStateSerialize::deserialize(deserializer)?;
+            let ModifiersStateSerialize {
+                shift_key,
+                control_key,
+                alt_key,
+                super_key,
+                alt_graph_key,
+                level5_key,
+                hyper_key,
+                meta_key,
+            } = ModifiersStateSerialize::deserialize(deserializer)?;
             let mut m = ModifiersState::empty();
             m.set(ModifiersState::SHIFT, shift_key);
             m.set(ModifiersState::CONTROL, control_key);
             m.set(ModifiersState::ALT, alt_key);
             m.set(ModifiersState::SUPER, super_key);
+            m.set(ModifiersState::ALT_GRAPH, alt_graph_key);
+            m.set(ModifiersState::LEVEL5, level5_key);
+            m.set(ModifiersState::HYPER, hyper_key);
+            m.set(ModifiersState::META, meta_key);
             Ok(m)
         }
     }
@@ -140,6 +140,8 @@
 //!
 //! * `x11` (enabled by default): On Unix platforms, enables the X11 backend.
 //! * `wayland` (enabled by default): On Unix platforms, enables the Wayland backend.
+//! * `wayland-global-shortcuts`: On Wayland, enables the global shortcuts through the
+//!   `org.freedesktop.portal.GlobalShortcuts` portal.
 //! * `rwh_04`: Implement `raw-window-handle v0.4` traits.
 //! * `rwh_05`: Implement `raw-window-handle v0.5` traits.
 //! * `rwh_06`: Implement `raw-window-handle v0.6` traits.
@@ -198,7 +200,10 @@ pub mod event_loop;
 mod icon;
 pub mod keyboard;
 pub mod monitor;
+#[cfg(feature = "serde")]
+mod placement;
 mod platform_impl;
+mod user_events;
 mod utils;
 pub mod window;
 
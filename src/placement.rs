@@ -0,0 +1,274 @@
+//! The serializable snapshot of the window placement.
+use serde::{Deserialize, Serialize};
+
+use crate::dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize};
+use crate::monitor::MonitorHandle;
+use crate::window::{Fullscreen, Window, WindowAttributes};
+
+/// The placement of the window which could be saved and restored on the next run.
+///
+/// The snapshot is obtained with [`Window::placement`] and applied to the new window with
+/// [`WindowAttributes::with_placement`]. The geometry is stored in the logical units of the
+/// monitor the window was on, so it's restored properly when the scale factor changes.
+///
+/// When the window is restored, the monitor is looked up by its [`MonitorIdentity`], falling back
+/// to the primary monitor, or to any available one, when it's gone. The geometry is then clamped
+/// to that monitor, so the window never ends up off-screen.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowPlacement {
+    /// The outer position of the window relative to the top-left corner of its monitor.
+    ///
+    /// `None` when the position isn't available, e.g. on Wayland.
+    pub position: Option<LogicalPosition<f64>>,
+    /// The inner size of the window.
+    pub size: LogicalSize<f64>,
+    /// The outer size of the window, used to keep the decorations on the monitor.
+    pub outer_size: LogicalSize<f64>,
+    /// Whether the window is maximized.
+    pub maximized: bool,
+    /// Whether the window is fullscreen.
+    ///
+    /// The window is always restored in the [`Fullscreen::Borderless`] mode.
+    pub fullscreen: bool,
+    /// The monitor the window is on.
+    pub monitor: Option<MonitorIdentity>,
+}
+
+/// The data identifying the monitor across the runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorIdentity {
+    /// See [`MonitorHandle::name`].
+    pub name: Option<String>,
+    /// See [`MonitorHandle::position`].
+    pub position: PhysicalPosition<i32>,
+    /// See [`MonitorHandle::size`].
+    pub size: PhysicalSize<u32>,
+}
+
+impl MonitorIdentity {
+    fn new(monitor: &MonitorHandle) -> Self {
+        Self { name: monitor.name(), position: monitor.position(), size: monitor.size() }
+    }
+
+    /// Whether both identities have the same monitor name.
+    fn matches_name(&self, other: &MonitorIdentity) -> bool {
+        self.name.is_some() && self.name == other.name
+    }
+
+    /// Whether both identities have the same monitor geometry.
+    fn matches_geometry(&self, other: &MonitorIdentity) -> bool {
+        self.position == other.position && self.size == other.size
+    }
+}
+
+impl WindowPlacement {
+    pub(crate) fn from_window(window: &Window) -> Self {
+        let scale_factor = window.scale_factor();
+        let monitor = window.current_monitor();
+        let maximized = window.is_maximized();
+        let fullscreen = window.fullscreen().is_some();
+
+        // NOTE: Store the geometry the window is restored to, and not the maximized one.
+        let (position, size, outer_size) = match window.normal_bounds() {
+            Some(bounds) if maximized || fullscreen => {
+                (bounds.position, bounds.inner_size, bounds.outer_size)
+            },
+            _ => (window.outer_position().ok(), window.inner_size(), window.outer_size()),
+        };
+
+        let position = position.map(|position| {
+            let origin = monitor.as_ref().map(MonitorHandle::position).unwrap_or_default();
+            PhysicalPosition::new(position.x - origin.x, position.y - origin.y)
+                .to_logical(scale_factor)
+        });
+
+        Self {
+            position,
+            size: size.to_logical(scale_factor),
+            outer_size: outer_size.to_logical(scale_factor),
+            maximized,
+            fullscreen,
+            monitor: monitor.as_ref().map(MonitorIdentity::new),
+        }
+    }
+
+    /// Apply the placement to the attributes against the currently available monitors.
+    pub(crate) fn apply(
+        &self,
+        attributes: &mut WindowAttributes,
+        mut monitors: Vec<MonitorHandle>,
+        primary: Option<MonitorHandle>,
+    ) {
+        let identities: Vec<_> = monitors.iter().map(MonitorIdentity::new).collect();
+        let primary = primary.as_ref().map(MonitorIdentity::new);
+        let monitor = select_monitor(self.monitor.as_ref(), &identities, primary.as_ref())
+            .map(|index| monitors.swap_remove(index));
+
+        attributes.inner_size = Some(self.size.into());
+        attributes.maximized = self.maximized;
+
+        let monitor = match monitor {
+            Some(monitor) => monitor,
+            None => return,
+        };
+
+        // NOTE: The monitor size isn't always known, e.g. on Web without the permissions, so
+        // don't clamp to it.
+        let monitor_size = monitor.size();
+        if monitor_size.width != 0 && monitor_size.height != 0 {
+            let scale_factor = monitor.scale_factor();
+            let (size, position) = clamp_to_monitor(
+                monitor_size.to_logical(scale_factor),
+                self.position,
+                self.size,
+                self.outer_size,
+            );
+            attributes.inner_size = Some(size.into());
+
+            if let Some(position) = position {
+                let position = position.to_physical::<i32>(scale_factor);
+                let origin = monitor.position();
+                attributes.position = Some(
+                    PhysicalPosition::new(origin.x + position.x, origin.y + position.y).into(),
+                );
+            }
+        }
+
+        if self.fullscreen {
+            attributes.fullscreen = Some(Fullscreen::Borderless(Some(monitor)));
+        }
+    }
+}
+
+/// Pick the index of the monitor to restore the window on.
+///
+/// The saved monitor is looked up by name first, and by its geometry next. When it's gone, the
+/// primary monitor is used, falling back to the first available one.
+fn select_monitor(
+    saved: Option<&MonitorIdentity>,
+    monitors: &[MonitorIdentity],
+    primary: Option<&MonitorIdentity>,
+) -> Option<usize> {
+    let find = |identity: &MonitorIdentity| {
+        monitors
+            .iter()
+            .position(|monitor| monitor.matches_name(identity))
+            .or_else(|| monitors.iter().position(|monitor| monitor.matches_geometry(identity)))
+    };
+
+    saved
+        .and_then(find)
+        .or_else(|| primary.and_then(|primary| monitors.iter().position(|m| m == primary)))
+        .or_else(|| (!monitors.is_empty()).then_some(0))
+}
+
+/// Clamp the inner size and the outer position relative to the monitor to the monitor bounds,
+/// so the whole window, including its decorations, stays on the monitor.
+fn clamp_to_monitor(
+    bounds: LogicalSize<f64>,
+    position: Option<LogicalPosition<f64>>,
+    size: LogicalSize<f64>,
+    outer_size: LogicalSize<f64>,
+) -> (LogicalSize<f64>, Option<LogicalPosition<f64>>) {
+    let frame_width = (outer_size.width - size.width).max(0.);
+    let frame_height = (outer_size.height - size.height).max(0.);
+
+    let size = LogicalSize::new(
+        size.width.min(bounds.width - frame_width).max(0.),
+        size.height.min(bounds.height - frame_height).max(0.),
+    );
+
+    let position = position.map(|position| {
+        let max_x = (bounds.width - size.width - frame_width).max(0.);
+        let max_y = (bounds.height - size.height - frame_height).max(0.);
+        LogicalPosition::new(position.x.clamp(0., max_x), position.y.clamp(0., max_y))
+    });
+
+    (size, position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(name: Option<&str>, x: i32, width: u32) -> MonitorIdentity {
+        MonitorIdentity {
+            name: name.map(ToOwned::to_owned),
+            position: PhysicalPosition::new(x, 0),
+            size: PhysicalSize::new(width, 1080),
+        }
+    }
+
+    #[test]
+    fn select_saved_monitor() {
+        let monitors = [identity(Some("DP-1"), 0, 1920), identity(Some("DP-2"), 1920, 1920)];
+        let primary = monitors[0].clone();
+
+        // The monitor moved, but kept its name.
+        let saved = identity(Some("DP-2"), 0, 2560);
+        assert_eq!(select_monitor(Some(&saved), &monitors, Some(&primary)), Some(1));
+
+        // The monitor name isn't available, so it's matched by the geometry.
+        let monitors = [identity(None, 0, 1920), identity(None, 1920, 1920)];
+        let saved = identity(None, 1920, 1920);
+        assert_eq!(select_monitor(Some(&saved), &monitors, Some(&primary)), Some(1));
+    }
+
+    #[test]
+    fn select_missing_monitor() {
+        let monitors = [identity(Some("DP-1"), 0, 1920), identity(Some("DP-2"), 1920, 1920)];
+        let saved = identity(Some("HDMI-1"), 3840, 1920);
+
+        assert_eq!(select_monitor(Some(&saved), &monitors, Some(&monitors[1])), Some(1));
+        assert_eq!(select_monitor(Some(&saved), &monitors, None), Some(0));
+        assert_eq!(select_monitor(None, &monitors, None), Some(0));
+        assert_eq!(select_monitor(Some(&saved), &[], None), None);
+    }
+
+    #[test]
+    fn clamp_on_monitor() {
+        let bounds = LogicalSize::new(1920., 1080.);
+        let size = LogicalSize::new(800., 600.);
+        let outer_size = LogicalSize::new(810., 640.);
+        let position = LogicalPosition::new(100., 100.);
+
+        assert_eq!(
+            clamp_to_monitor(bounds, Some(position), size, outer_size),
+            (size, Some(position))
+        );
+        assert_eq!(clamp_to_monitor(bounds, None, size, outer_size), (size, None));
+    }
+
+    #[test]
+    fn clamp_off_monitor() {
+        let bounds = LogicalSize::new(1920., 1080.);
+        let size = LogicalSize::new(800., 600.);
+        let outer_size = LogicalSize::new(810., 640.);
+
+        // The decorations are kept on the monitor as well.
+        let position = LogicalPosition::new(1500., 900.);
+        assert_eq!(
+            clamp_to_monitor(bounds, Some(position), size, outer_size),
+            (size, Some(LogicalPosition::new(1110., 440.)))
+        );
+
+        let position = LogicalPosition::new(-100., -50.);
+        assert_eq!(
+            clamp_to_monitor(bounds, Some(position), size, outer_size),
+            (size, Some(LogicalPosition::new(0., 0.)))
+        );
+    }
+
+    #[test]
+    fn clamp_oversized() {
+        let bounds = LogicalSize::new(1920., 1080.);
+        let size = LogicalSize::new(2000., 1200.);
+        let outer_size = LogicalSize::new(2010., 1240.);
+        let position = LogicalPosition::new(300., 200.);
+
+        assert_eq!(
+            clamp_to_monitor(bounds, Some(position), size, outer_size),
+            (LogicalSize::new(1910., 1040.), Some(LogicalPosition::new(0., 0.)))
+        );
+    }
+}
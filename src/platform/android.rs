@@ -80,7 +80,7 @@ pub trait EventLoopExtAndroid {
     fn android_app(&self) -> &AndroidApp;
 }
 
-impl EventLoopExtAndroid for EventLoop {
+impl<T> EventLoopExtAndroid for EventLoop<T> {
     fn android_app(&self) -> &AndroidApp {
         &self.event_loop.android_app
     }
@@ -132,7 +132,7 @@ pub trait EventLoopBuilderExtAndroid {
     fn handle_volume_keys(&mut self) -> &mut Self;
 }
 
-impl EventLoopBuilderExtAndroid for EventLoopBuilder {
+impl<T> EventLoopBuilderExtAndroid for EventLoopBuilder<T> {
     fn with_android_app(&mut self, app: AndroidApp) -> &mut Self {
         self.platform_specific.android_app = Some(app);
         self
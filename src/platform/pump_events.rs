@@ -2,9 +2,14 @@ use std::time::Duration;
 
 use crate::application::ApplicationHandler;
 use crate::event_loop::EventLoop;
+use crate::user_events::UserEventHandler;
 
 /// Additional methods on [`EventLoop`] for pumping events within an external event loop
 pub trait EventLoopExtPumpEvents {
+    /// The type of the user events delivered via
+    /// [`ApplicationHandler::user_event`].
+    type UserEvent: 'static;
+
     /// Pump the `EventLoop` to check for and dispatch pending events.
     ///
     /// This API is designed to enable applications to integrate Winit into an
@@ -99,19 +104,22 @@ pub trait EventLoopExtPumpEvents {
     ///   If you render outside of Winit you are likely to see window resizing artifacts
     ///   since MacOS expects applications to render synchronously during any `drawRect`
     ///   callback.
-    fn pump_app_events<A: ApplicationHandler>(
+    fn pump_app_events<A: ApplicationHandler<Self::UserEvent>>(
         &mut self,
         timeout: Option<Duration>,
         app: A,
     ) -> PumpStatus;
 }
 
-impl EventLoopExtPumpEvents for EventLoop {
-    fn pump_app_events<A: ApplicationHandler>(
+impl<T: 'static> EventLoopExtPumpEvents for EventLoop<T> {
+    type UserEvent = T;
+
+    fn pump_app_events<A: ApplicationHandler<T>>(
         &mut self,
         timeout: Option<Duration>,
         app: A,
     ) -> PumpStatus {
+        let app = UserEventHandler::new(app, self.user_events.clone());
         self.event_loop.pump_app_events(timeout, app)
     }
 }
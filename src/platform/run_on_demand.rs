@@ -1,6 +1,7 @@
 use crate::application::ApplicationHandler;
 use crate::error::EventLoopError;
 use crate::event_loop::EventLoop;
+use crate::user_events::UserEventHandler;
 #[cfg(doc)]
 use crate::{
     event_loop::ActiveEventLoop, platform::pump_events::EventLoopExtPumpEvents, window::Window,
@@ -8,6 +9,10 @@ use crate::{
 
 /// Additional methods on [`EventLoop`] to return control flow to the caller.
 pub trait EventLoopExtRunOnDemand {
+    /// The type of the user events delivered via
+    /// [`ApplicationHandler::user_event`].
+    type UserEvent: 'static;
+
     /// Run the application with the event loop on the calling thread.
     ///
     /// Unlike [`EventLoop::run_app`], this function accepts non-`'static` (i.e. non-`move`)
@@ -60,11 +65,20 @@ pub trait EventLoopExtRunOnDemand {
     ///
     /// [`exit()`]: ActiveEventLoop::exit()
     /// [`set_control_flow()`]: ActiveEventLoop::set_control_flow()
-    fn run_app_on_demand<A: ApplicationHandler>(&mut self, app: A) -> Result<(), EventLoopError>;
+    fn run_app_on_demand<A: ApplicationHandler<Self::UserEvent>>(
+        &mut self,
+        app: A,
+    ) -> Result<(), EventLoopError>;
 }
 
-impl EventLoopExtRunOnDemand for EventLoop {
-    fn run_app_on_demand<A: ApplicationHandler>(&mut self, app: A) -> Result<(), EventLoopError> {
+impl<T: 'static> EventLoopExtRunOnDemand for EventLoop<T> {
+    type UserEvent = T;
+
+    fn run_app_on_demand<A: ApplicationHandler<T>>(
+        &mut self,
+        app: A,
+    ) -> Result<(), EventLoopError> {
+        let app = UserEventHandler::new(app, self.user_events.clone());
         self.event_loop.run_app_on_demand(app)
     }
 }
@@ -14,17 +14,25 @@
 //! [`WindowExtStartupNotify::request_activation_token`] the [`set_activation_token_env`]
 //! must be used to propagate it to the child
 //!
+//! The token could also be awaited with [`WindowExtStartupNotify::request_activation_token_async`]
+//! from the futures spawned with [`EventLoopExtUnix::spawn_local`].
+//!
 //! To ensure the delivery of such token by other processes to you, the user should
 //! set `StartupNotify=true` inside the `.desktop` file of their application.
 //!
 //! The specification could be found [`here`].
 //!
 //! [`here`]: https://specifications.freedesktop.org/startup-notification-spec/startup-notification-latest.txt
+//! [`EventLoopExtUnix::spawn_local`]: crate::platform::unix::EventLoopExtUnix::spawn_local
 
 use std::env;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
-use crate::error::NotSupportedError;
+use crate::error::{ExternalError, NotSupportedError};
 use crate::event_loop::{ActiveEventLoop, AsyncRequestSerial};
+use crate::platform_impl::common::executor::{self, AsyncRequest};
 use crate::window::{ActivationToken, Window, WindowAttributes};
 
 /// The variable which is used mostly on X11.
@@ -45,6 +53,14 @@ pub trait WindowExtStartupNotify {
     ///
     /// The token will be delivered inside
     fn request_activation_token(&self) -> Result<AsyncRequestSerial, NotSupportedError>;
+
+    /// Request a new activation token and wait for it asynchronously.
+    ///
+    /// The [`WindowEvent::ActivationTokenDone`] is still delivered for such request. The
+    /// future never resolves if the window is destroyed before the token is obtained.
+    ///
+    /// [`WindowEvent::ActivationTokenDone`]: crate::event::WindowEvent::ActivationTokenDone
+    fn request_activation_token_async(&self) -> ActivationTokenFuture;
 }
 
 pub trait WindowAttributesExtStartupNotify {
@@ -72,6 +88,29 @@ impl WindowExtStartupNotify for Window {
     fn request_activation_token(&self) -> Result<AsyncRequestSerial, NotSupportedError> {
         self.window.request_activation_token()
     }
+
+    fn request_activation_token_async(&self) -> ActivationTokenFuture {
+        ActivationTokenFuture(executor::request_activation_token(|| {
+            self.window.request_activation_token().map_err(ExternalError::NotSupported)
+        }))
+    }
+}
+
+/// The future returned by [`WindowExtStartupNotify::request_activation_token_async`].
+pub struct ActivationTokenFuture(AsyncRequest<Result<ActivationToken, ExternalError>>);
+
+impl Future for ActivationTokenFuture {
+    type Output = Result<ActivationToken, ExternalError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+impl std::fmt::Debug for ActivationTokenFuture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ActivationTokenFuture").finish_non_exhaustive()
+    }
 }
 
 impl WindowAttributesExtStartupNotify for WindowAttributes {
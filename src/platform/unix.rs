@@ -0,0 +1,406 @@
+//! # Unix event loop integration
+//!
+//! The X11 and Wayland backends run a [`calloop`] event loop internally, which lets you
+//! watch your own file descriptors and timers on the event loop thread without spawning
+//! a helper thread waking up the loop.
+//!
+//! The file descriptor readiness is delivered via [`ApplicationHandler::fd_ready`] and the
+//! timer expiration via [`ApplicationHandler::timer_expired`]. The user idle state could be
+//! watched as well with [`EventLoopExtUnix::add_idle_timeout`].
+//!
+//! The system-wide shortcuts could be registered with
+//! [`EventLoopExtUnix::register_global_shortcut`] and are delivered via
+//! [`ApplicationHandler::global_shortcut`].
+//!
+//! The gamepads could be enabled with [`EventLoopBuilderExtUnix::with_gamepads`] and the rumble
+//! effects played with [`EventLoopExtUnix::rumble_gamepad`].
+//!
+//! The keyboard keymap could be queried with [`EventLoopExtUnix::keymap`], e.g. to show the
+//! shortcut hints matching the current layout.
+//!
+//! The futures could be run on the event loop thread as well with
+//! [`EventLoopExtUnix::spawn_local`], without the need for a separate executor.
+//!
+//! When embedding the [`EventLoop`] into another event loop, register its file descriptor
+//! obtained with [`AsFd`] into that event loop and call [`pump_app_events`] once it becomes
+//! readable or once [`EventLoopExtUnix::next_deadline`] is reached, whatever comes first.
+//!
+//! [`calloop`]: https://docs.rs/calloop
+//! [`pump_app_events`]: crate::platform::pump_events::EventLoopExtPumpEvents::pump_app_events
+//! [`ApplicationHandler::fd_ready`]: crate::application::ApplicationHandler::fd_ready
+//! [`ApplicationHandler::timer_expired`]: crate::application::ApplicationHandler::timer_expired
+//! [`ApplicationHandler::global_shortcut`]: crate::application::ApplicationHandler::global_shortcut
+
+use std::future::Future;
+use std::io;
+use std::os::unix::io::AsFd;
+use std::time::{Duration, Instant};
+
+use crate::error::{ExternalError, NotSupportedError};
+use crate::event::DeviceId;
+use crate::event_loop::{
+    ActiveEventLoop, EventLoop, EventLoopBuilder, FdId, GlobalShortcutId, IdleTimeoutId, TimerId,
+};
+use crate::keyboard::{Key, KeyCode, ModifiersState, PhysicalKey};
+use crate::platform_impl::common::xkb::KeymapSnapshot;
+
+/// The readiness the file descriptor is watched for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FdInterest {
+    pub readable: bool,
+    pub writable: bool,
+}
+
+impl FdInterest {
+    /// Watch for the file descriptor being readable.
+    pub const READ: Self = Self { readable: true, writable: false };
+    /// Watch for the file descriptor being writable.
+    pub const WRITE: Self = Self { readable: false, writable: true };
+    /// Watch for the file descriptor being either readable or writable.
+    pub const BOTH: Self = Self { readable: true, writable: true };
+}
+
+/// Additional methods on [`EventLoop`] and [`ActiveEventLoop`] to integrate the user
+/// sources into the event loop.
+pub trait EventLoopExtUnix {
+    /// Watch the file descriptor for the given `interest`.
+    ///
+    /// The file descriptor is watched in level-triggered mode, thus
+    /// [`ApplicationHandler::fd_ready`] will be called on every event loop iteration
+    /// until the file descriptor is no longer ready. The `fd` is owned by the event loop
+    /// until [`EventLoopExtUnix::unregister_fd`] is called, so pass a reference counted
+    /// handle if you need to use it elsewhere.
+    ///
+    /// [`ApplicationHandler::fd_ready`]: crate::application::ApplicationHandler::fd_ready
+    fn register_fd<F: AsFd + 'static>(&self, fd: F, interest: FdInterest) -> io::Result<FdId>;
+
+    /// Stop watching the file descriptor and drop it.
+    ///
+    /// The readiness which wasn't delivered yet is discarded.
+    fn unregister_fd(&self, fd_id: FdId);
+
+    /// Schedule the one-shot timer expiring at `deadline`.
+    ///
+    /// The timer wakes up the event loop regardless of the current
+    /// [`ControlFlow`], and [`ApplicationHandler::timer_expired`] is called once it expires.
+    ///
+    /// [`ControlFlow`]: crate::event_loop::ControlFlow
+    /// [`ApplicationHandler::timer_expired`]: crate::application::ApplicationHandler::timer_expired
+    fn set_timeout(&self, deadline: Instant) -> TimerId;
+
+    /// Schedule the repeating timer first expiring after `interval` from now.
+    ///
+    /// See [`EventLoopExtUnix::set_timeout`] for details.
+    fn set_interval(&self, interval: Duration) -> TimerId;
+
+    /// Cancel the timer.
+    ///
+    /// The expiration which wasn't delivered yet is discarded. Canceling the already
+    /// expired one-shot timer is a no-op.
+    fn cancel_timer(&self, timer_id: TimerId);
+
+    /// Watch for the user being idle, i.e. not interacting with any input device, for at least
+    /// `timeout`.
+    ///
+    /// [`ApplicationHandler::idle_changed`] is called with `idle` set to `true` once the timeout
+    /// passes, and with `false` once the user is active again. Any number of the idle timeouts
+    /// could be registered at once.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Requires the `ext_idle_notifier_v1` protocol and a seat. The compositor may
+    ///   not report the idle state while the idle is inhibited.
+    /// - **X11:** Requires the `MIT-SCREEN-SAVER` extension. The idle time is polled, thus the user
+    ///   activity is reported with up to a second of delay.
+    ///
+    /// [`ApplicationHandler::idle_changed`]: crate::application::ApplicationHandler::idle_changed
+    fn add_idle_timeout(&self, timeout: Duration) -> Result<IdleTimeoutId, NotSupportedError>;
+
+    /// Stop watching for the user being idle.
+    ///
+    /// The idle state changes which weren't delivered yet are discarded.
+    fn remove_idle_timeout(&self, timeout_id: IdleTimeoutId);
+
+    /// The snapshot of the current keyboard keymap.
+    ///
+    /// `None` when there's no keyboard. The snapshot isn't updated, thus query it again on
+    /// [`WindowEvent::KeyboardLayoutChanged`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** The keymap of the first seat with a keyboard.
+    ///
+    /// [`WindowEvent::KeyboardLayoutChanged`]: crate::event::WindowEvent::KeyboardLayoutChanged
+    fn keymap(&self) -> Option<Keymap>;
+
+    /// Register the system-wide shortcut of the `key` pressed along with exactly the
+    /// `modifiers`.
+    ///
+    /// [`ApplicationHandler::global_shortcut`] is called once the shortcut is pressed, even
+    /// when none of the windows is focused, and once the `key` is released. The shortcut isn't
+    /// delivered to the focused window, and the lock keys, like Caps Lock, are ignored when
+    /// matching it.
+    ///
+    /// Returns [`ExternalError::Os`] when the shortcut is already taken by another client.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The key is grabbed on the root window. Only the Shift, Control, Alt and Super
+    ///   modifiers are matched.
+    /// - **Wayland:** The shortcut is bound through the `org.freedesktop.portal.GlobalShortcuts`
+    ///   portal, which usually asks the user to confirm or to change it, thus it's bound
+    ///   asynchronously and the binding failures are only logged. The `modifiers` and the `key`
+    ///   are just the preferred trigger. Requires the `wayland-global-shortcuts` feature, and
+    ///   returns [`ExternalError::NotSupported`] without it or once the portal is found to be
+    ///   unavailable.
+    ///
+    /// [`ApplicationHandler::global_shortcut`]: crate::application::ApplicationHandler::global_shortcut
+    fn register_global_shortcut(
+        &self,
+        modifiers: ModifiersState,
+        key: KeyCode,
+    ) -> Result<GlobalShortcutId, ExternalError>;
+
+    /// Unregister the system-wide shortcut.
+    ///
+    /// The shortcut state changes which weren't delivered yet are discarded.
+    fn unregister_global_shortcut(&self, shortcut_id: GlobalShortcutId);
+
+    /// Play the rumble effect on the gamepad.
+    ///
+    /// The `strong` and `weak` magnitudes are in the `0.0..=1.0` range and drive the low and
+    /// high frequency motors respectively. The effect replaces the one which is still playing.
+    ///
+    /// Returns [`ExternalError::NotSupported`] when the device isn't a gamepad enabled with
+    /// [`EventLoopBuilderExtUnix::with_gamepads`] or it has no force feedback.
+    fn rumble_gamepad(
+        &self,
+        device_id: DeviceId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<(), ExternalError>;
+
+    /// Spawn the future on the event loop thread.
+    ///
+    /// The future is polled during the event loop iteration, right before
+    /// [`ApplicationHandler::about_to_wait`], and waking it up wakes up the event loop.
+    /// The future is dropped along with the event loop if it isn't complete by then.
+    ///
+    /// [`ApplicationHandler::about_to_wait`]: crate::application::ApplicationHandler::about_to_wait
+    fn spawn_local<F: Future<Output = ()> + 'static>(&self, future: F);
+
+    /// The deadline by which the event loop must be pumped again, even if its file
+    /// descriptor didn't become readable.
+    ///
+    /// The deadline accounts for the current [`ControlFlow`] and the registered timers,
+    /// and is the current time when there's already work the file descriptor won't signal.
+    /// `None` means that it's fine to wait for the file descriptor indefinitely.
+    ///
+    /// This flushes the pending requests to the display server, thus it must be called
+    /// right before going to sleep, after all the drawing is done.
+    ///
+    /// [`ControlFlow`]: crate::event_loop::ControlFlow
+    fn next_deadline(&self) -> Option<Instant>;
+}
+
+impl EventLoopExtUnix for ActiveEventLoop {
+    fn register_fd<F: AsFd + 'static>(&self, fd: F, interest: FdInterest) -> io::Result<FdId> {
+        self.p.register_fd(fd, interest.readable, interest.writable)
+    }
+
+    fn unregister_fd(&self, fd_id: FdId) {
+        self.p.unregister_fd(fd_id)
+    }
+
+    fn set_timeout(&self, deadline: Instant) -> TimerId {
+        self.p.set_timer(deadline, None)
+    }
+
+    fn set_interval(&self, interval: Duration) -> TimerId {
+        self.p.set_timer(Instant::now() + interval, Some(interval))
+    }
+
+    fn cancel_timer(&self, timer_id: TimerId) {
+        self.p.cancel_timer(timer_id)
+    }
+
+    fn add_idle_timeout(&self, timeout: Duration) -> Result<IdleTimeoutId, NotSupportedError> {
+        self.p.add_idle_timeout(timeout)
+    }
+
+    fn remove_idle_timeout(&self, timeout_id: IdleTimeoutId) {
+        self.p.remove_idle_timeout(timeout_id)
+    }
+
+    fn keymap(&self) -> Option<Keymap> {
+        self.p.keymap().map(|inner| Keymap { inner })
+    }
+
+    fn register_global_shortcut(
+        &self,
+        modifiers: ModifiersState,
+        key: KeyCode,
+    ) -> Result<GlobalShortcutId, ExternalError> {
+        self.p.register_global_shortcut(modifiers, key)
+    }
+
+    fn unregister_global_shortcut(&self, shortcut_id: GlobalShortcutId) {
+        self.p.unregister_global_shortcut(shortcut_id)
+    }
+
+    fn rumble_gamepad(
+        &self,
+        device_id: DeviceId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<(), ExternalError> {
+        self.p.rumble_gamepad(device_id, strong, weak, duration)
+    }
+
+    fn spawn_local<F: Future<Output = ()> + 'static>(&self, future: F) {
+        self.p.spawn_local(future)
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        self.p.next_deadline()
+    }
+}
+
+impl<T> EventLoopExtUnix for EventLoop<T> {
+    fn register_fd<F: AsFd + 'static>(&self, fd: F, interest: FdInterest) -> io::Result<FdId> {
+        self.event_loop.window_target().register_fd(fd, interest)
+    }
+
+    fn unregister_fd(&self, fd_id: FdId) {
+        self.event_loop.window_target().unregister_fd(fd_id)
+    }
+
+    fn set_timeout(&self, deadline: Instant) -> TimerId {
+        self.event_loop.window_target().set_timeout(deadline)
+    }
+
+    fn set_interval(&self, interval: Duration) -> TimerId {
+        self.event_loop.window_target().set_interval(interval)
+    }
+
+    fn cancel_timer(&self, timer_id: TimerId) {
+        self.event_loop.window_target().cancel_timer(timer_id)
+    }
+
+    fn add_idle_timeout(&self, timeout: Duration) -> Result<IdleTimeoutId, NotSupportedError> {
+        self.event_loop.window_target().add_idle_timeout(timeout)
+    }
+
+    fn remove_idle_timeout(&self, timeout_id: IdleTimeoutId) {
+        self.event_loop.window_target().remove_idle_timeout(timeout_id)
+    }
+
+    fn keymap(&self) -> Option<Keymap> {
+        self.event_loop.window_target().keymap()
+    }
+
+    fn register_global_shortcut(
+        &self,
+        modifiers: ModifiersState,
+        key: KeyCode,
+    ) -> Result<GlobalShortcutId, ExternalError> {
+        self.event_loop.window_target().register_global_shortcut(modifiers, key)
+    }
+
+    fn unregister_global_shortcut(&self, shortcut_id: GlobalShortcutId) {
+        self.event_loop.window_target().unregister_global_shortcut(shortcut_id)
+    }
+
+    fn rumble_gamepad(
+        &self,
+        device_id: DeviceId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<(), ExternalError> {
+        self.event_loop.window_target().rumble_gamepad(device_id, strong, weak, duration)
+    }
+
+    fn spawn_local<F: Future<Output = ()> + 'static>(&self, future: F) {
+        self.event_loop.window_target().spawn_local(future)
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        self.event_loop.window_target().next_deadline()
+    }
+}
+
+/// The keyboard keymap along with its active layout.
+///
+/// See [`EventLoopExtUnix::keymap`].
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    inner: KeymapSnapshot,
+}
+
+impl Keymap {
+    /// The names of the layouts in the keymap, e.g. `"English (US)"`.
+    ///
+    /// The name is empty when the layout has none.
+    pub fn layouts(&self) -> Vec<String> {
+        self.inner.layout_names()
+    }
+
+    /// The index of the active layout in [`Keymap::layouts`].
+    pub fn active_layout(&self) -> usize {
+        self.inner.active_layout() as usize
+    }
+
+    /// The logical key the physical key produces under the active layout with the `modifiers`
+    /// held.
+    ///
+    /// The compose sequences and dead keys aren't resolved, e.g. the dead key produces
+    /// [`Key::Dead`]. [`ModifiersState::HYPER`] and [`ModifiersState::META`] are ignored.
+    pub fn key(&self, physical_key: PhysicalKey, modifiers: ModifiersState) -> Key {
+        self.inner.key(physical_key, modifiers)
+    }
+}
+
+/// Additional methods on [`EventLoopBuilder`] to configure the gamepads.
+pub trait EventLoopBuilderExtUnix {
+    /// Whether to watch the gamepads in `/dev/input`.
+    ///
+    /// The gamepads are reported via [`ApplicationHandler::device_event`] with
+    /// [`DeviceEvent::GamepadButton`] and [`DeviceEvent::GamepadAxis`], and are listed by
+    /// [`ActiveEventLoop::devices`]. The event devices must be readable by the user, and
+    /// writable to play the rumble effects. The default is `false`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// Only supported on **Linux**.
+    ///
+    /// [`ApplicationHandler::device_event`]: crate::application::ApplicationHandler::device_event
+    /// [`DeviceEvent::GamepadButton`]: crate::event::DeviceEvent::GamepadButton
+    /// [`DeviceEvent::GamepadAxis`]: crate::event::DeviceEvent::GamepadAxis
+    fn with_gamepads(&mut self, gamepads: bool) -> &mut Self;
+
+    /// Add the gamepad mappings in the SDL `gamecontrollerdb.txt` format, one per line.
+    ///
+    /// The mappings take precedence over the ones from the `SDL_GAMECONTROLLERCONFIG`
+    /// environment variable, and the gamepads without a mapping use the Linux gamepad
+    /// button and axis codes.
+    fn with_gamepad_mappings(&mut self, mappings: &str) -> &mut Self;
+}
+
+impl<T> EventLoopBuilderExtUnix for EventLoopBuilder<T> {
+    #[inline]
+    fn with_gamepads(&mut self, gamepads: bool) -> &mut Self {
+        self.platform_specific.gamepads = gamepads;
+        self
+    }
+
+    #[inline]
+    fn with_gamepad_mappings(&mut self, mappings: &str) -> &mut Self {
+        let gamepad_mappings = &mut self.platform_specific.gamepad_mappings;
+        gamepad_mappings.push_str(mappings);
+        gamepad_mappings.push('\n');
+        self
+    }
+}
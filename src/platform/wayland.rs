@@ -13,10 +13,34 @@
 //! * `wayland-csd-adwaita` (default).
 //! * `wayland-csd-adwaita-crossfont`.
 //! * `wayland-csd-adwaita-notitle`.
+//!
+//! Applications can draw the frame themselves by implementing [`CsdFrame`] and passing it to
+//! [`WindowAttributesExtWayland::with_csd_frame`].
+//!
+//! Winit prefers server-side decorations when the compositor supports `zxdg_decoration_manager_v1`
+//! and falls back to drawing the frame itself otherwise. Use
+//! [`WindowAttributesExtWayland::with_decoration_preference`] to change that, and
+//! [`WindowExtWayland::decoration_mode`] together with [`WindowEvent::DecorationModeChanged`] to
+//! learn which side ended up drawing the decorations.
+//!
+//! ## Child windows
+//!
+//! Windows created with [`WindowAttributes::with_parent_window`] are embedded into their parent as
+//! a `wl_subsurface`. The parent must be a window created by the same event loop.
+//! [`WindowAttributes::position`] and [`Window::set_outer_position`] place the child relative to
+//! the parent, however the new position only takes effect on the next commit of the parent. Pointer
+//! and touch input on the child is delivered with the child's [`WindowId`], while keyboard focus
+//! stays with the top-level window.
+//!
+//! [`WindowId`]: crate::window::WindowId
+//! [`WindowEvent::DecorationModeChanged`]: crate::event::WindowEvent::DecorationModeChanged
+use std::sync::Arc;
+
+use crate::dpi::{LogicalPosition, LogicalSize};
 use crate::event_loop::{ActiveEventLoop, EventLoop, EventLoopBuilder};
 use crate::monitor::MonitorHandle;
 pub use crate::window::Theme;
-use crate::window::{Window, WindowAttributes};
+use crate::window::{DecorationMode, ResizeDirection, Window, WindowAttributes};
 
 /// Additional methods on [`ActiveEventLoop`] that are specific to Wayland.
 pub trait ActiveEventLoopExtWayland {
@@ -37,7 +61,7 @@ pub trait EventLoopExtWayland {
     fn is_wayland(&self) -> bool;
 }
 
-impl EventLoopExtWayland for EventLoop {
+impl<T> EventLoopExtWayland for EventLoop<T> {
     #[inline]
     fn is_wayland(&self) -> bool {
         self.event_loop.is_wayland()
@@ -56,7 +80,7 @@ pub trait EventLoopBuilderExtWayland {
     fn with_any_thread(&mut self, any_thread: bool) -> &mut Self;
 }
 
-impl EventLoopBuilderExtWayland for EventLoopBuilder {
+impl<T> EventLoopBuilderExtWayland for EventLoopBuilder<T> {
     #[inline]
     fn with_wayland(&mut self) -> &mut Self {
         self.platform_specific.forced_backend = Some(crate::platform_impl::Backend::Wayland);
@@ -71,9 +95,59 @@ impl EventLoopBuilderExtWayland for EventLoopBuilder {
 }
 
 /// Additional methods on [`Window`] that are specific to Wayland.
-pub trait WindowExtWayland {}
+pub trait WindowExtWayland {
+    /// Set whether the commits of the child window are synchronized with its parent.
+    ///
+    /// In the synchronized mode the content of the child is updated atomically together with the
+    /// next commit of the parent, otherwise the child is updated on its own.
+    ///
+    /// Does nothing if the window is not a child window.
+    fn set_subsurface_sync(&self, sync: bool);
+
+    /// Place the child window right above the `sibling`.
+    ///
+    /// The `sibling` must be either the parent of the child window or another child of the same
+    /// parent. The new stacking order takes effect on the next commit of the parent.
+    ///
+    /// Does nothing if the window is not a child window.
+    fn place_subsurface_above(&self, sibling: &Window);
+
+    /// Place the child window right below the `sibling`.
+    ///
+    /// See [`WindowExtWayland::place_subsurface_above`] for the details.
+    fn place_subsurface_below(&self, sibling: &Window);
+
+    /// The decoration mode negotiated with the compositor.
+    ///
+    /// Returns `None` until the window is configured, and for windows which never have
+    /// decorations, like popups and child windows.
+    ///
+    /// Note that [`DecorationMode::Client`] doesn't imply that the frame is visible, see
+    /// [`Window::is_decorated`].
+    fn decoration_mode(&self) -> Option<DecorationMode>;
+}
+
+impl WindowExtWayland for Window {
+    #[inline]
+    fn set_subsurface_sync(&self, sync: bool) {
+        self.window.set_subsurface_sync(sync)
+    }
+
+    #[inline]
+    fn place_subsurface_above(&self, sibling: &Window) {
+        self.window.place_subsurface_above(&sibling.window)
+    }
+
+    #[inline]
+    fn place_subsurface_below(&self, sibling: &Window) {
+        self.window.place_subsurface_below(&sibling.window)
+    }
 
-impl WindowExtWayland for Window {}
+    #[inline]
+    fn decoration_mode(&self) -> Option<DecorationMode> {
+        self.window.decoration_mode()
+    }
+}
 
 /// Additional methods on [`WindowAttributes`] that are specific to Wayland.
 pub trait WindowAttributesExtWayland {
@@ -85,6 +159,31 @@ pub trait WindowAttributesExtWayland {
     /// For details about application ID conventions, see the
     /// [Desktop Entry Spec](https://specifications.freedesktop.org/desktop-entry-spec/desktop-entry-spec-latest.html#desktop-file-id)
     fn with_name(self, general: impl Into<String>, instance: impl Into<String>) -> Self;
+
+    /// Build the child window with its commits synchronized with the parent.
+    ///
+    /// Only used for windows created with [`WindowAttributes::with_parent_window`], see
+    /// [`WindowExtWayland::set_subsurface_sync`].
+    ///
+    /// The default is `false`.
+    fn with_subsurface_sync(self, sync: bool) -> Self;
+
+    /// Build window with the given decorations preference.
+    ///
+    /// The compositor has the final word, thus it may still pick the other mode, in which case
+    /// the preference decides whether winit draws its own frame. Only used for top-level windows.
+    ///
+    /// The default is [`DecorationPreference::Auto`].
+    fn with_decoration_preference(self, preference: DecorationPreference) -> Self;
+
+    /// Build window with the client-side decorations frame drawn by the application.
+    ///
+    /// The `builder` is called every time winit needs a new frame, for example when the
+    /// compositor switches from server-side to client-side decorations. Without it winit uses
+    /// the frame picked by the cargo features.
+    fn with_csd_frame<F>(self, builder: F) -> Self
+    where
+        F: Fn() -> Box<dyn CsdFrame> + Send + Sync + 'static;
 }
 
 impl WindowAttributesExtWayland for WindowAttributes {
@@ -94,6 +193,201 @@ impl WindowAttributesExtWayland for WindowAttributes {
             Some(crate::platform_impl::ApplicationName::new(general.into(), instance.into()));
         self
     }
+
+    #[inline]
+    fn with_subsurface_sync(mut self, sync: bool) -> Self {
+        self.platform_specific.wayland.subsurface_sync = sync;
+        self
+    }
+
+    #[inline]
+    fn with_decoration_preference(mut self, preference: DecorationPreference) -> Self {
+        self.platform_specific.wayland.decoration_preference = preference;
+        self
+    }
+
+    #[inline]
+    fn with_csd_frame<F>(mut self, builder: F) -> Self
+    where
+        F: Fn() -> Box<dyn CsdFrame> + Send + Sync + 'static,
+    {
+        self.platform_specific.wayland.csd_frame =
+            Some(crate::platform_impl::wayland::CsdFrameBuilder::new(Arc::new(builder)));
+        self
+    }
+}
+
+/// The preferred way of decorating the window.
+///
+/// All the variants respect [`WindowAttributes::with_decorations`], thus a window without
+/// decorations stays undecorated regardless of the preference.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecorationPreference {
+    /// Prefer server-side decorations, falling back to the client-side frame when the
+    /// compositor refuses to draw them.
+    #[default]
+    Auto,
+
+    /// Only use server-side decorations.
+    ///
+    /// The window is left without decorations when the compositor picks the client-side mode.
+    ServerOnly,
+
+    /// Only use the client-side frame drawn by winit.
+    ///
+    /// The frame is dropped when the compositor enforces server-side decorations.
+    ClientOnly,
+
+    /// Ask for the client-side mode, but never draw the frame.
+    ///
+    /// Useful for applications drawing their own decorations.
+    None,
+}
+
+/// The client-side decorations frame drawn by the application.
+///
+/// The frame is drawn on a surface placed right below the window, which covers both the window
+/// and the borders around it. Winit takes care of the surface, the buffers, and of turning the
+/// pointer input into the move, resize, and window menu requests, while the frame decides how it
+/// looks and which of its parts is under the pointer.
+///
+/// Every method gets the latest [`CsdFrameState`]. The frame is hidden while the window is
+/// fullscreen.
+pub trait CsdFrame: Send {
+    /// The size of the borders around the window content in logical pixels.
+    fn borders(&self, state: &CsdFrameState) -> CsdFrameBorders;
+
+    /// Find the part of the frame at the `position`.
+    ///
+    /// The `position` is in logical pixels relative to the top-left corner of the frame. Return
+    /// `None` for the parts which shouldn't react to the pointer.
+    fn hit_test(
+        &self,
+        state: &CsdFrameState,
+        position: LogicalPosition<f64>,
+    ) -> Option<CsdFrameHit>;
+
+    /// Draw the frame into the `buffer`.
+    ///
+    /// The buffer is cleared before the call and the area under the window content is cleared
+    /// again afterwards, thus it's never visible.
+    fn draw(&mut self, state: &CsdFrameState, buffer: &mut CsdFrameBuffer<'_>);
+
+    /// The state of the frame has changed.
+    ///
+    /// Return whether the frame must be redrawn. The default always redraws.
+    fn state_changed(&mut self, state: &CsdFrameState) -> bool {
+        let _ = state;
+        true
+    }
+}
+
+/// The state of the window the [`CsdFrame`] is drawn for.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsdFrameState {
+    /// The title of the window.
+    pub title: String,
+
+    /// The size of the window content, without the borders.
+    pub inner_size: LogicalSize<u32>,
+
+    /// The scale factor of the window.
+    pub scale_factor: f64,
+
+    /// The theme requested by [`Window::set_theme`].
+    pub theme: Option<Theme>,
+
+    /// Whether the window is activated.
+    pub activated: bool,
+
+    /// Whether the window is maximized.
+    pub maximized: bool,
+
+    /// Whether the window is tiled.
+    pub tiled: bool,
+
+    /// Whether the window can be resized by the user.
+    pub resizable: bool,
+
+    /// Whether the compositor supports minimizing the window.
+    pub minimizable: bool,
+
+    /// Whether the compositor supports maximizing the window.
+    pub maximizable: bool,
+
+    /// The part of the frame under the pointer.
+    pub hovered: Option<CsdFrameHit>,
+
+    /// The button being pressed.
+    pub pressed: Option<CsdFrameButton>,
+}
+
+/// The size of the [`CsdFrame`] borders in logical pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CsdFrameBorders {
+    pub top: u32,
+    pub left: u32,
+    pub right: u32,
+    pub bottom: u32,
+}
+
+/// The part of the [`CsdFrame`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CsdFrameHit {
+    /// The border or the corner to resize the window with.
+    Resize(ResizeDirection),
+
+    /// The title bar to move the window with.
+    Titlebar,
+
+    /// One of the title bar buttons.
+    Button(CsdFrameButton),
+}
+
+/// The button on the [`CsdFrame`] title bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CsdFrameButton {
+    Close,
+    Maximize,
+    Minimize,
+}
+
+/// The buffer to draw the [`CsdFrame`] into.
+#[derive(Debug)]
+pub struct CsdFrameBuffer<'a> {
+    pub(crate) pixels: &'a mut [u8],
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) scale: u32,
+}
+
+impl CsdFrameBuffer<'_> {
+    /// The pixels of the buffer, row by row.
+    ///
+    /// Every pixel is premultiplied ARGB stored as the little endian `u32`, as in `[B, G, R, A]`.
+    #[inline]
+    pub fn pixels_mut(&mut self) -> &mut [u8] {
+        self.pixels
+    }
+
+    /// The width of the buffer in physical pixels.
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of the buffer in physical pixels.
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The integer scale of the buffer, as in the number of physical pixels per logical one.
+    #[inline]
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
 }
 
 /// Additional methods on `MonitorHandle` that are specific to Wayland.
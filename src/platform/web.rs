@@ -66,6 +66,7 @@ use crate::platform_impl::{
     MonitorPermissionFuture as PlatformMonitorPermissionFuture,
     OrientationLockFuture as PlatformOrientationLockFuture,
 };
+use crate::user_events::UserEventHandler;
 use crate::window::{CustomCursor, Window, WindowAttributes};
 
 #[cfg(not(web_platform))]
@@ -174,6 +175,9 @@ impl WindowAttributesExtWeb for WindowAttributes {
 
 /// Additional methods on `EventLoop` that are specific to the Web.
 pub trait EventLoopExtWeb {
+    /// The type of the user events delivered via [`ApplicationHandler::user_event`].
+    type UserEvent: 'static;
+
     /// Initializes the winit event loop.
     ///
     /// Unlike
@@ -196,7 +200,7 @@ pub trait EventLoopExtWeb {
         doc = "[`run_app()`]: EventLoop::run_app()"
     )]
     /// [^1]: `run_app()` is _not_ available on Wasm when the target supports `exception-handling`.
-    fn spawn_app<A: ApplicationHandler + 'static>(self, app: A);
+    fn spawn_app<A: ApplicationHandler<Self::UserEvent> + 'static>(self, app: A);
 
     /// Sets the strategy for [`ControlFlow::Poll`].
     ///
@@ -242,9 +246,11 @@ pub trait EventLoopExtWeb {
     fn has_detailed_monitor_permission(&self) -> HasMonitorPermissionFuture;
 }
 
-impl EventLoopExtWeb for EventLoop {
-    fn spawn_app<A: ApplicationHandler + 'static>(self, app: A) {
-        self.event_loop.spawn_app(app);
+impl<T: 'static> EventLoopExtWeb for EventLoop<T> {
+    type UserEvent = T;
+
+    fn spawn_app<A: ApplicationHandler<T> + 'static>(self, app: A) {
+        self.event_loop.spawn_app(UserEventHandler::new(app, self.user_events));
     }
 
     fn set_poll_strategy(&self, strategy: PollStrategy) {
@@ -17,13 +17,13 @@ use crate::cursor::Cursor;
 use crate::dpi::{PhysicalPosition, PhysicalSize, Position, Size};
 use crate::error;
 use crate::error::EventLoopError;
-use crate::event::{self, Force, InnerSizeWriter, StartCause};
+use crate::event::{self, DeviceInfo, Force, InnerSizeWriter, StartCause};
 use crate::event_loop::{self, ControlFlow, DeviceEvents};
 use crate::platform::pump_events::PumpStatus;
 use crate::platform_impl::Fullscreen;
 use crate::window::{
-    self, CursorGrabMode, CustomCursor, CustomCursorSource, ImePurpose, ResizeDirection, Theme,
-    WindowButtons, WindowLevel,
+    self, CursorGrabMode, CustomCursor, CustomCursorSource, ImePurpose, KeyRepeat, KeyRepeatRate,
+    ResizeDirection, Theme, WindowButtons, WindowLevel,
 };
 
 mod keycodes;
@@ -360,6 +360,8 @@ impl EventLoop {
                             location,
                             id: pointer.pointer_id() as u64,
                             force: Some(Force::Normalized(pointer.pressure() as f64)),
+                            contact_size: None,
+                            orientation: None,
                         });
 
                         app.window_event(self.window_target(), window_id, event);
@@ -602,6 +604,11 @@ impl ActiveEventLoop {
         v
     }
 
+    #[inline]
+    pub fn devices(&self) -> Vec<DeviceInfo> {
+        Vec::new()
+    }
+
     #[inline]
     pub fn listen_device_events(&self, _allowed: DeviceEvents) {}
 
@@ -787,6 +794,16 @@ impl Window {
 
     pub fn set_blur(&self, _blur: bool) {}
 
+    pub fn set_idle_inhibit(&self, _inhibit: bool) {}
+
+    pub fn set_keyboard_shortcuts_inhibited(&self, _inhibited: bool) {}
+
+    pub fn set_key_repeat(&self, _repeat: KeyRepeat) {}
+
+    pub fn key_repeat_rate(&self) -> Option<KeyRepeatRate> {
+        None
+    }
+
     pub fn set_visible(&self, _visibility: bool) {}
 
     pub fn is_visible(&self) -> Option<bool> {
@@ -817,6 +834,20 @@ impl Window {
         false
     }
 
+    pub fn normal_bounds(&self) -> Option<window::WindowBounds> {
+        None
+    }
+
+    pub fn set_normal_bounds(&self, _position: Option<Position>, _inner_size: Size) {}
+
+    pub fn state(&self) -> window::WindowState {
+        window::WindowState::empty()
+    }
+
+    pub fn wm_capabilities(&self) -> Option<window::WmCapabilities> {
+        None
+    }
+
     pub fn set_fullscreen(&self, _monitor: Option<Fullscreen>) {
         warn!("Cannot set fullscreen on Android");
     }
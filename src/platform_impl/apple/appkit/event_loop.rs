@@ -29,6 +29,7 @@ use super::monitor::{self, MonitorHandle};
 use super::observer::setup_control_flow_observers;
 use crate::application::ApplicationHandler;
 use crate::error::EventLoopError;
+use crate::event::DeviceInfo;
 use crate::event_loop::{ActiveEventLoop as RootWindowTarget, ControlFlow, DeviceEvents};
 use crate::platform::macos::ActivationPolicy;
 use crate::platform::pump_events::PumpStatus;
@@ -100,6 +101,11 @@ impl ActiveEventLoop {
         Some(monitor)
     }
 
+    #[inline]
+    pub fn devices(&self) -> Vec<DeviceInfo> {
+        Vec::new()
+    }
+
     #[inline]
     pub fn listen_device_events(&self, _allowed: DeviceEvents) {}
 
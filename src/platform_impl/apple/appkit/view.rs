@@ -26,8 +26,8 @@ use super::window::WinitWindow;
 use super::DEVICE_ID;
 use crate::dpi::{LogicalPosition, LogicalSize};
 use crate::event::{
-    DeviceEvent, ElementState, Ime, Modifiers, MouseButton, MouseScrollDelta, TouchPhase,
-    WindowEvent,
+    DeviceEvent, ElementState, Ime, Modifiers, MouseButton, MouseScrollDelta, ScrollSource,
+    TouchPhase, WindowEvent,
 };
 use crate::keyboard::{Key, KeyCode, KeyLocation, ModifiersState, NamedKey};
 use crate::platform::macos::OptionAsAlt;
@@ -660,15 +660,16 @@ declare_class!(
 
             self.mouse_motion(event);
 
-            let delta = {
+            let (delta, source) = {
                 let (x, y) = unsafe { (event.scrollingDeltaX(), event.scrollingDeltaY()) };
                 if unsafe { event.hasPreciseScrollingDeltas() } {
                     let delta = LogicalPosition::new(x, y).to_physical(self.scale_factor());
-                    MouseScrollDelta::PixelDelta(delta)
+                    (MouseScrollDelta::PixelDelta(delta), ScrollSource::Finger)
                 } else {
-                    MouseScrollDelta::LineDelta(x as f32, y as f32)
+                    (MouseScrollDelta::LineDelta(x as f32, y as f32), ScrollSource::Wheel)
                 }
             };
+            let inverted = unsafe { event.isDirectionInvertedFromDevice() };
 
             // The "momentum phase," if any, has higher priority than touch phase (the two should
             // be mutually exclusive anyhow, which is why the API is rather incoherent). If no momentum
@@ -694,6 +695,8 @@ declare_class!(
                 device_id: DEVICE_ID,
                 delta,
                 phase,
+                source,
+                inverted,
             });
         }
 
@@ -38,8 +38,9 @@ use crate::error::{ExternalError, NotSupportedError, OsError as RootOsError};
 use crate::event::{InnerSizeWriter, WindowEvent};
 use crate::platform::macos::{OptionAsAlt, WindowExtMacOS};
 use crate::window::{
-    Cursor, CursorGrabMode, Icon, ImePurpose, ResizeDirection, Theme, UserAttentionType,
-    WindowAttributes, WindowButtons, WindowId as RootWindowId, WindowLevel,
+    Cursor, CursorGrabMode, Icon, ImePurpose, KeyRepeat, KeyRepeatRate, ResizeDirection, Theme,
+    UserAttentionType, WindowAttributes, WindowBounds, WindowButtons, WindowId as RootWindowId,
+    WindowLevel, WindowState, WmCapabilities,
 };
 
 #[derive(Clone, Debug)]
@@ -894,6 +895,16 @@ impl WindowDelegate {
         }
     }
 
+    pub fn set_idle_inhibit(&self, _inhibit: bool) {}
+
+    pub fn set_keyboard_shortcuts_inhibited(&self, _inhibited: bool) {}
+
+    pub fn set_key_repeat(&self, _repeat: KeyRepeat) {}
+
+    pub fn key_repeat_rate(&self) -> Option<KeyRepeatRate> {
+        None
+    }
+
     pub fn set_visible(&self, visible: bool) {
         match visible {
             true => self.window().makeKeyAndOrderFront(None),
@@ -1289,6 +1300,24 @@ impl WindowDelegate {
         self.is_zoomed()
     }
 
+    #[inline]
+    pub fn normal_bounds(&self) -> Option<WindowBounds> {
+        None
+    }
+
+    #[inline]
+    pub fn set_normal_bounds(&self, _position: Option<Position>, _inner_size: Size) {}
+
+    #[inline]
+    pub fn state(&self) -> WindowState {
+        WindowState::empty()
+    }
+
+    #[inline]
+    pub fn wm_capabilities(&self) -> Option<WmCapabilities> {
+        None
+    }
+
     #[inline]
     pub(crate) fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
         let mtm = MainThreadMarker::from(self);
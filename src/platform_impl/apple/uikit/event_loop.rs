@@ -22,7 +22,7 @@ use super::app_state::{AppState, EventLoopHandler};
 use super::{app_state, monitor, MonitorHandle};
 use crate::application::ApplicationHandler;
 use crate::error::EventLoopError;
-use crate::event::Event;
+use crate::event::{DeviceInfo, Event};
 use crate::event_loop::{ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents};
 use crate::window::{CustomCursor, CustomCursorSource};
 
@@ -50,6 +50,11 @@ impl ActiveEventLoop {
         Some(MonitorHandle::new(UIScreen::mainScreen(self.mtm)))
     }
 
+    #[inline]
+    pub fn devices(&self) -> Vec<DeviceInfo> {
+        Vec::new()
+    }
+
     #[inline]
     pub fn listen_device_events(&self, _allowed: DeviceEvents) {}
 
@@ -505,6 +505,8 @@ impl WinitView {
                     id: touch_id,
                     location: physical_location,
                     force,
+                    contact_size: None,
+                    orientation: None,
                     phase,
                 }),
             }));
@@ -25,8 +25,9 @@ use crate::event::{Event, WindowEvent};
 use crate::icon::Icon;
 use crate::platform::ios::{ScreenEdge, StatusBarStyle, ValidOrientations};
 use crate::window::{
-    CursorGrabMode, ImePurpose, ResizeDirection, Theme, UserAttentionType, WindowAttributes,
-    WindowButtons, WindowId as RootWindowId, WindowLevel,
+    CursorGrabMode, ImePurpose, KeyRepeat, KeyRepeatRate, ResizeDirection, Theme,
+    UserAttentionType, WindowAttributes, WindowBounds, WindowButtons, WindowId as RootWindowId,
+    WindowLevel, WindowState, WmCapabilities,
 };
 
 declare_class!(
@@ -124,6 +125,22 @@ impl Inner {
         debug!("`Window::set_blur` is ignored on iOS")
     }
 
+    pub fn set_idle_inhibit(&self, _inhibit: bool) {
+        debug!("`Window::set_idle_inhibit` is ignored on iOS")
+    }
+
+    pub fn set_keyboard_shortcuts_inhibited(&self, _inhibited: bool) {
+        debug!("`Window::set_keyboard_shortcuts_inhibited` is ignored on iOS")
+    }
+
+    pub fn set_key_repeat(&self, _repeat: KeyRepeat) {
+        debug!("`Window::set_key_repeat` is ignored on iOS")
+    }
+
+    pub fn key_repeat_rate(&self) -> Option<KeyRepeatRate> {
+        None
+    }
+
     pub fn set_visible(&self, visible: bool) {
         self.window.setHidden(!visible)
     }
@@ -295,6 +312,20 @@ impl Inner {
         false
     }
 
+    pub fn normal_bounds(&self) -> Option<WindowBounds> {
+        None
+    }
+
+    pub fn set_normal_bounds(&self, _position: Option<Position>, _inner_size: Size) {}
+
+    pub fn state(&self) -> WindowState {
+        WindowState::empty()
+    }
+
+    pub fn wm_capabilities(&self) -> Option<WmCapabilities> {
+        None
+    }
+
     pub(crate) fn set_fullscreen(&self, monitor: Option<Fullscreen>) {
         let mtm = MainThreadMarker::new().unwrap();
         let uiscreen = match &monitor {
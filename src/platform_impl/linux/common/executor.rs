@@ -0,0 +1,190 @@
+//! The local executor running the user futures on the event loop thread.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+use calloop::ping::{self, Ping};
+use calloop::LoopHandle;
+
+use crate::error::ExternalError;
+use crate::event_loop::AsyncRequestSerial;
+use crate::window::ActivationToken;
+
+/// The executor polling the futures spawned with `EventLoopExtUnix::spawn_local`.
+///
+/// The tasks are polled once per event loop iteration right before `about_to_wait`, and waking
+/// them up pings the event loop.
+pub struct LocalExecutor {
+    tasks: RefCell<HashMap<u64, Task>>,
+    shared: Arc<Shared>,
+    next_id: Cell<u64>,
+}
+
+struct Task {
+    future: Pin<Box<dyn Future<Output = ()>>>,
+    waker: Arc<TaskWaker>,
+}
+
+/// The state shared with the wakers, which could be used from any thread.
+struct Shared {
+    ready: Mutex<VecDeque<u64>>,
+    ping: Ping,
+}
+
+struct TaskWaker {
+    id: u64,
+    /// Whether the task is already in the ready queue.
+    queued: AtomicBool,
+    shared: Arc<Shared>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        if !self.queued.swap(true, Ordering::AcqRel) {
+            self.shared.ready.lock().unwrap().push_back(self.id);
+            self.shared.ping.ping();
+        }
+    }
+}
+
+impl LocalExecutor {
+    pub fn new<D: 'static>(handle: &LoopHandle<'static, D>) -> Self {
+        let (ping, ping_source) = ping::make_ping().expect("Failed to create the executor waker");
+        handle
+            .insert_source(ping_source, |_, _, _| {
+                // No extra handling is required, we just need to wake-up.
+            })
+            .expect("Failed to register the executor waker source");
+
+        Self {
+            tasks: Default::default(),
+            shared: Arc::new(Shared { ready: Default::default(), ping }),
+            next_id: Cell::new(0),
+        }
+    }
+
+    pub fn spawn<F: Future<Output = ()> + 'static>(&self, future: F) {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+
+        let waker =
+            Arc::new(TaskWaker { id, queued: AtomicBool::new(false), shared: self.shared.clone() });
+        waker.wake_by_ref();
+        self.tasks.borrow_mut().insert(id, Task { future: Box::pin(future), waker });
+    }
+
+    /// Whether there're tasks waiting to be polled.
+    pub fn has_pending(&self) -> bool {
+        !self.shared.ready.lock().unwrap().is_empty()
+    }
+
+    /// Poll the tasks woken up so far.
+    ///
+    /// The tasks woken up while polling are polled on the next iteration.
+    pub fn run(&self) {
+        let ready = mem::take(&mut *self.shared.ready.lock().unwrap());
+        for id in ready {
+            // NOTE: Take the task out, since polling it could spawn the new ones.
+            let mut task = match self.tasks.borrow_mut().remove(&id) {
+                Some(task) => task,
+                None => continue,
+            };
+
+            task.waker.queued.store(false, Ordering::Release);
+            let waker = Waker::from(task.waker.clone());
+            if task.future.as_mut().poll(&mut Context::from_waker(&waker)).is_pending() {
+                self.tasks.borrow_mut().insert(id, task);
+            }
+        }
+    }
+}
+
+/// The result of the async request shared between the event loop and the future.
+struct RequestSlot<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+}
+
+/// The future resolving once the event loop completes the request.
+pub struct AsyncRequest<T>(Arc<Mutex<RequestSlot<T>>>);
+
+impl<T> AsyncRequest<T> {
+    /// The request which is already done.
+    pub fn ready(value: T) -> Self {
+        Self(Arc::new(Mutex::new(RequestSlot { value: Some(value), waker: None })))
+    }
+
+    fn pending() -> Self {
+        Self(Arc::new(Mutex::new(RequestSlot { value: None, waker: None })))
+    }
+
+    fn complete(&self, value: T) {
+        let mut slot = self.0.lock().unwrap();
+        slot.value = Some(value);
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Future for AsyncRequest<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut slot = self.0.lock().unwrap();
+        match slot.value.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            },
+        }
+    }
+}
+
+type ActivationTokenResult = Result<ActivationToken, ExternalError>;
+
+/// The activation token requests awaited by the user.
+static ACTIVATION_TOKEN_REQUESTS: Mutex<
+    Option<HashMap<AsyncRequestSerial, AsyncRequest<ActivationTokenResult>>>,
+> = Mutex::new(None);
+
+/// Send the activation token request and get the future resolving once it's done.
+pub fn request_activation_token(
+    request: impl FnOnce() -> Result<AsyncRequestSerial, ExternalError>,
+) -> AsyncRequest<ActivationTokenResult> {
+    // NOTE: Hold the lock while requesting, so the request can't be completed from the event
+    // loop thread before it's registered.
+    let mut requests = ACTIVATION_TOKEN_REQUESTS.lock().unwrap();
+    match request() {
+        Ok(serial) => {
+            let future = AsyncRequest::pending();
+            let slot = AsyncRequest(future.0.clone());
+            requests.get_or_insert_with(Default::default).insert(serial, slot);
+            future
+        },
+        Err(error) => AsyncRequest::ready(Err(error)),
+    }
+}
+
+/// Complete the activation token request, if it's awaited.
+pub fn complete_activation_token(serial: AsyncRequestSerial, result: ActivationTokenResult) {
+    let request = ACTIVATION_TOKEN_REQUESTS
+        .lock()
+        .unwrap()
+        .as_mut()
+        .and_then(|requests| requests.remove(&serial));
+    if let Some(request) = request {
+        request.complete(result);
+    }
+}
@@ -0,0 +1,1029 @@
+//! The gamepads and joysticks read from the evdev devices.
+//!
+//! The devices are discovered by watching `/dev/input` with inotify, and their buttons and axes
+//! are translated to the standard layout either with the SDL mapping matching the device or with
+//! the conventions of the kernel gamepad drivers.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::ffi::{CStr, OsStr};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::mem::{self, MaybeUninit};
+use std::os::raw::{c_int, c_ulong};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::{AsFd, AsRawFd, FromRawFd, OwnedFd};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
+
+use calloop::generic::Generic;
+use calloop::{Interest, LoopHandle, Mode, PostAction, RegistrationToken};
+
+use crate::error::{ExternalError, NotSupportedError};
+use crate::event::{
+    DeviceAxis, DeviceEvent, DeviceId as RootDeviceId, DeviceInfo, DeviceKind, ElementState,
+    GamepadAxis, GamepadButton,
+};
+use crate::platform_impl::{DeviceId as PlatformDeviceId, OsError};
+
+const INPUT_DIR: &str = "/dev/input";
+
+/// The environment variable with the SDL mappings, one per line.
+const MAPPINGS_ENV: &str = "SDL_GAMECONTROLLERCONFIG";
+
+// These values are coming from <linux/input-event-codes.h>.
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const EV_ABS: u16 = 0x03;
+const EV_FF: u16 = 0x15;
+const SYN_DROPPED: u16 = 3;
+const KEY_CNT: usize = 0x300;
+const ABS_CNT: usize = 0x40;
+const FF_CNT: usize = 0x80;
+const BTN_MISC: u16 = 0x100;
+const BTN_JOYSTICK: u16 = 0x120;
+const BTN_THUMBR: u16 = 0x13e;
+const BTN_SOUTH: u16 = 0x130;
+const BTN_EAST: u16 = 0x131;
+const BTN_NORTH: u16 = 0x133;
+const BTN_WEST: u16 = 0x134;
+const BTN_TL: u16 = 0x136;
+const BTN_TR: u16 = 0x137;
+const BTN_TL2: u16 = 0x138;
+const BTN_TR2: u16 = 0x139;
+const BTN_SELECT: u16 = 0x13a;
+const BTN_START: u16 = 0x13b;
+const BTN_MODE: u16 = 0x13c;
+const BTN_THUMBL: u16 = 0x13d;
+const BTN_DPAD_UP: u16 = 0x220;
+const BTN_DPAD_DOWN: u16 = 0x221;
+const BTN_DPAD_LEFT: u16 = 0x222;
+const BTN_DPAD_RIGHT: u16 = 0x223;
+const ABS_X: u16 = 0x00;
+const ABS_Y: u16 = 0x01;
+const ABS_Z: u16 = 0x02;
+const ABS_RX: u16 = 0x03;
+const ABS_RY: u16 = 0x04;
+const ABS_RZ: u16 = 0x05;
+const ABS_HAT0X: u16 = 0x10;
+const ABS_HAT0Y: u16 = 0x11;
+const ABS_HAT3Y: u16 = 0x17;
+const FF_RUMBLE: u16 = 0x50;
+
+// The `ioctl` requests from <linux/input.h>.
+#[cfg(not(any(
+    target_arch = "mips",
+    target_arch = "mips64",
+    target_arch = "powerpc",
+    target_arch = "powerpc64",
+    target_arch = "sparc64"
+)))]
+mod ioc {
+    pub const SIZE_BITS: u32 = 14;
+    pub const WRITE: u32 = 1;
+    pub const READ: u32 = 2;
+}
+#[cfg(any(
+    target_arch = "mips",
+    target_arch = "mips64",
+    target_arch = "powerpc",
+    target_arch = "powerpc64",
+    target_arch = "sparc64"
+))]
+mod ioc {
+    pub const SIZE_BITS: u32 = 13;
+    pub const WRITE: u32 = 4;
+    pub const READ: u32 = 2;
+}
+
+const fn ioc(dir: u32, nr: u32, size: usize) -> c_ulong {
+    ((dir << (16 + ioc::SIZE_BITS)) | ((size as u32) << 16) | ((b'E' as u32) << 8) | nr) as c_ulong
+}
+
+const EVIOCGID: c_ulong = ioc(ioc::READ, 0x02, mem::size_of::<libc::input_id>());
+const EVIOCSFF: c_ulong = ioc(ioc::WRITE, 0x80, mem::size_of::<libc::ff_effect>());
+const EVIOCRMFF: c_ulong = ioc(ioc::WRITE, 0x81, mem::size_of::<c_int>());
+
+const fn eviocgname(len: usize) -> c_ulong {
+    ioc(ioc::READ, 0x06, len)
+}
+
+const fn eviocgbit(ev: u16, len: usize) -> c_ulong {
+    ioc(ioc::READ, 0x20 + ev as u32, len)
+}
+
+const fn eviocgabs(abs: u16) -> c_ulong {
+    ioc(ioc::READ, 0x40 + abs as u32, mem::size_of::<libc::input_absinfo>())
+}
+
+/// Identifier of the gamepad, unique for the lifetime of the event loop.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GamepadId(u32);
+
+/// The gamepads opened by the event loop.
+pub struct Gamepads<D: 'static> {
+    handle: LoopHandle<'static, D>,
+    inner: Rc<RefCell<Inner>>,
+    watch: Option<RegistrationToken>,
+}
+
+struct Inner {
+    /// The SDL mappings provided by the user.
+    mappings: Vec<SdlMapping>,
+    gamepads: BTreeMap<GamepadId, Gamepad>,
+    pending: VecDeque<(GamepadId, DeviceEvent)>,
+    next_id: u32,
+}
+
+impl<D: 'static> Gamepads<D> {
+    /// Open the gamepads which are already connected and watch for the new ones.
+    ///
+    /// The `mappings` are in the SDL mapping database format and take precedence over the ones
+    /// from the `SDL_GAMECONTROLLERCONFIG` environment variable.
+    pub fn new(handle: LoopHandle<'static, D>, mappings: &str) -> Self {
+        let env_mappings = std::env::var(MAPPINGS_ENV).unwrap_or_default();
+        let mappings = mappings.lines().chain(env_mappings.lines()).filter_map(SdlMapping::parse);
+        let inner = Rc::new(RefCell::new(Inner {
+            mappings: mappings.collect(),
+            gamepads: Default::default(),
+            pending: Default::default(),
+            next_id: 0,
+        }));
+
+        // Watch before listing the directory, so the devices added in the meantime aren't missed.
+        let watch = match Self::watch(&handle, &inner) {
+            Ok(token) => Some(token),
+            Err(err) => {
+                tracing::warn!("Failed to watch `{INPUT_DIR}` for gamepads: {err}");
+                None
+            },
+        };
+
+        let gamepads = Self { handle, inner, watch };
+        if let Ok(entries) = std::fs::read_dir(INPUT_DIR) {
+            let mut paths: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+            paths.sort();
+            for path in paths {
+                open_gamepad(&gamepads.handle, &gamepads.inner, &path);
+            }
+        }
+
+        // NOTE: The gamepads connected at startup are reported as added, like the hotplugged ones.
+        gamepads
+    }
+
+    fn watch(
+        handle: &LoopHandle<'static, D>,
+        inner: &Rc<RefCell<Inner>>,
+    ) -> io::Result<RegistrationToken> {
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        let dir = [INPUT_DIR.as_bytes(), b"\0"].concat();
+        let mask = libc::IN_CREATE | libc::IN_ATTRIB;
+        if unsafe { libc::inotify_add_watch(fd.as_raw_fd(), dir.as_ptr().cast(), mask) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let inner = inner.clone();
+        let loop_handle = handle.clone();
+        let source = Generic::new(fd, Interest::READ, Mode::Level);
+        handle
+            .insert_source(source, move |_, fd, _| {
+                for name in read_inotify(fd.as_fd())? {
+                    open_gamepad(&loop_handle, &inner, &Path::new(INPUT_DIR).join(name));
+                }
+
+                Ok(PostAction::Continue)
+            })
+            .map_err(|error| error.error.into())
+    }
+
+    /// The descriptions of the connected gamepads.
+    pub fn devices(&self) -> Vec<DeviceInfo> {
+        self.inner.borrow().gamepads.values().map(|gamepad| gamepad.info.clone()).collect()
+    }
+
+    /// Play the rumble effect on the gamepad, replacing the one in progress.
+    ///
+    /// The magnitudes are in the `0.0..=1.0` range.
+    pub fn rumble(
+        &self,
+        gamepad_id: GamepadId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<(), ExternalError> {
+        let mut inner = self.inner.borrow_mut();
+        let gamepad = match inner.gamepads.get_mut(&gamepad_id) {
+            Some(gamepad) if gamepad.has_rumble => gamepad,
+            _ => return Err(ExternalError::NotSupported(NotSupportedError::new())),
+        };
+
+        gamepad.rumble(strong, weak, duration).map_err(|err| {
+            tracing::warn!("Failed to play the rumble effect on {:?}: {err}", gamepad.info.name);
+            ExternalError::Os(os_error!(OsError::Misc("failed to play the rumble effect")))
+        })
+    }
+
+    /// Whether there're events waiting to be delivered.
+    pub fn has_pending(&self) -> bool {
+        !self.inner.borrow().pending.is_empty()
+    }
+
+    /// Take the next event.
+    pub fn pop(&self) -> Option<(RootDeviceId, DeviceEvent)> {
+        let (gamepad_id, event) = self.inner.borrow_mut().pending.pop_front()?;
+        Some((mkdid(gamepad_id), event))
+    }
+}
+
+impl<D: 'static> Drop for Gamepads<D> {
+    fn drop(&mut self) {
+        if let Some(watch) = self.watch.take() {
+            self.handle.remove(watch);
+        }
+
+        let mut inner = self.inner.borrow_mut();
+        for gamepad in mem::take(&mut inner.gamepads).into_values() {
+            self.handle.remove(gamepad.token);
+        }
+    }
+}
+
+fn mkdid(gamepad_id: GamepadId) -> RootDeviceId {
+    RootDeviceId(PlatformDeviceId::Gamepad(gamepad_id))
+}
+
+/// Read the names of the files created or which permissions changed.
+fn read_inotify(fd: impl AsFd) -> io::Result<Vec<PathBuf>> {
+    const HEADER_SIZE: usize = mem::size_of::<libc::inotify_event>();
+
+    let mut names = Vec::new();
+    let mut buffer = [0u8; 4096];
+    loop {
+        let len =
+            unsafe { libc::read(fd.as_fd().as_raw_fd(), buffer.as_mut_ptr().cast(), buffer.len()) };
+        if len < 0 {
+            let err = io::Error::last_os_error();
+            return match err.kind() {
+                io::ErrorKind::WouldBlock => Ok(names),
+                io::ErrorKind::Interrupted => continue,
+                _ => Err(err),
+            };
+        }
+
+        let mut offset = 0;
+        while offset + HEADER_SIZE <= len as usize {
+            let event: libc::inotify_event = unsafe {
+                buffer.as_ptr().add(offset).cast::<libc::inotify_event>().read_unaligned()
+            };
+            let name = &buffer[offset + HEADER_SIZE..][..event.len as usize];
+            // The name is padded with the nul bytes.
+            let name = name.split(|&byte| byte == 0).next().unwrap_or_default();
+            names.push(PathBuf::from(OsStr::from_bytes(name)));
+            offset += HEADER_SIZE + event.len as usize;
+        }
+    }
+}
+
+/// Open the device if it's a gamepad which isn't opened yet, and watch its events.
+fn open_gamepad<D: 'static>(
+    handle: &LoopHandle<'static, D>,
+    inner: &Rc<RefCell<Inner>>,
+    path: &Path,
+) {
+    let is_event_device =
+        path.file_name().is_some_and(|name| name.as_bytes().starts_with(b"event"));
+    if !is_event_device
+        || inner.borrow().gamepads.values().any(|gamepad| gamepad.path.as_path() == path)
+    {
+        return;
+    }
+
+    let options = |write| {
+        OpenOptions::new()
+            .read(true)
+            .write(write)
+            .custom_flags(libc::O_NONBLOCK | libc::O_CLOEXEC)
+            .open(path)
+    };
+    // The write access is only needed for the force feedback.
+    let file = match options(true).or_else(|_| options(false)) {
+        Ok(file) => Rc::new(file),
+        Err(_) => return,
+    };
+
+    let mut inner_ref = inner.borrow_mut();
+    let gamepad_id = GamepadId(inner_ref.next_id);
+    let capabilities = match Capabilities::query(&file) {
+        Some(capabilities) if capabilities.is_gamepad() => capabilities,
+        _ => return,
+    };
+
+    let source = Generic::new(file.clone(), Interest::READ, Mode::Level);
+    let callback_inner = inner.clone();
+    let token = match handle.insert_source(source, move |_, _, _| {
+        let mut inner = callback_inner.borrow_mut();
+        let inner = &mut *inner;
+        let gamepad = match inner.gamepads.get_mut(&gamepad_id) {
+            Some(gamepad) => gamepad,
+            None => return Ok(PostAction::Remove),
+        };
+
+        match gamepad.read(gamepad_id, &mut inner.pending) {
+            Ok(()) => Ok(PostAction::Continue),
+            Err(_) => {
+                // NOTE: The device is gone once reading fails with `ENODEV`.
+                inner.gamepads.remove(&gamepad_id);
+                inner.pending.push_back((gamepad_id, DeviceEvent::Removed));
+                Ok(PostAction::Remove)
+            },
+        }
+    }) {
+        Ok(token) => token,
+        Err(err) => {
+            tracing::warn!("Failed to watch the gamepad {}: {}", path.display(), err.error);
+            return;
+        },
+    };
+
+    let mapping = inner_ref
+        .mappings
+        .iter()
+        .find(|mapping| mapping.matches(&capabilities.guid()))
+        .map(|mapping| mapping.resolve(&capabilities))
+        .unwrap_or_else(|| Mapping::standard(&capabilities));
+
+    let info = capabilities.describe(mkdid(gamepad_id));
+    tracing::debug!("Opened the gamepad {:?} at {}", info.name, path.display());
+    let gamepad = Gamepad {
+        file,
+        path: path.to_owned(),
+        token,
+        info,
+        mapping,
+        has_rumble: capabilities.rumble,
+        absinfo: capabilities.absinfo,
+        buttons: HashMap::new(),
+        axes: HashMap::new(),
+        rumble_effect: None,
+    };
+
+    inner_ref.next_id += 1;
+    inner_ref.gamepads.insert(gamepad_id, gamepad);
+    inner_ref.pending.push_back((gamepad_id, DeviceEvent::Added));
+}
+
+/// The capabilities of the evdev device.
+struct Capabilities {
+    name: String,
+    id: libc::input_id,
+    keys: Vec<u16>,
+    absinfo: BTreeMap<u16, libc::input_absinfo>,
+    rumble: bool,
+}
+
+impl Capabilities {
+    fn query(file: &File) -> Option<Self> {
+        let fd = file.as_raw_fd();
+
+        let mut name = [0u8; 256];
+        if unsafe { libc::ioctl(fd, eviocgname(name.len()) as _, name.as_mut_ptr()) } < 0 {
+            return None;
+        }
+        let name = CStr::from_bytes_until_nul(&name).ok()?.to_string_lossy().into_owned();
+
+        let mut id = MaybeUninit::<libc::input_id>::uninit();
+        if unsafe { libc::ioctl(fd, EVIOCGID as _, id.as_mut_ptr()) } < 0 {
+            return None;
+        }
+        let id = unsafe { id.assume_init() };
+
+        let keys = query_bits::<{ KEY_CNT / 8 }>(fd, EV_KEY)?;
+        let abs = query_bits::<{ ABS_CNT / 8 }>(fd, EV_ABS)?;
+        let ff = query_bits::<{ FF_CNT / 8 }>(fd, EV_FF).unwrap_or_default();
+
+        let mut absinfo = BTreeMap::new();
+        for code in abs {
+            let mut info = MaybeUninit::<libc::input_absinfo>::uninit();
+            if unsafe { libc::ioctl(fd, eviocgabs(code) as _, info.as_mut_ptr()) } >= 0 {
+                absinfo.insert(code, unsafe { info.assume_init() });
+            }
+        }
+
+        Some(Self { name, id, keys, absinfo, rumble: ff.contains(&FF_RUMBLE) })
+    }
+
+    /// Only the joystick and gamepad buttons are checked, since the tablets, touchpads and mice
+    /// have absolute axes too.
+    fn is_gamepad(&self) -> bool {
+        !self.absinfo.is_empty()
+            && self.keys.iter().any(|&code| (BTN_JOYSTICK..=BTN_THUMBR).contains(&code))
+    }
+
+    /// The GUID of the device used by the SDL mappings.
+    fn guid(&self) -> [u8; 16] {
+        let mut guid = [0; 16];
+        guid[0..2].copy_from_slice(&self.id.bustype.to_le_bytes());
+        guid[4..6].copy_from_slice(&self.id.vendor.to_le_bytes());
+        guid[8..10].copy_from_slice(&self.id.product.to_le_bytes());
+        guid[12..14].copy_from_slice(&self.id.version.to_le_bytes());
+        guid
+    }
+
+    /// The buttons in the order they're indexed by the SDL mappings.
+    fn sdl_buttons(&self) -> impl Iterator<Item = u16> + '_ {
+        let joystick = self.keys.iter().copied().filter(|&code| code >= BTN_JOYSTICK);
+        let misc =
+            self.keys.iter().copied().filter(|&code| (BTN_MISC..BTN_JOYSTICK).contains(&code));
+        joystick.chain(misc)
+    }
+
+    /// The axes in the order they're indexed by the SDL mappings.
+    fn sdl_axes(&self) -> impl Iterator<Item = u16> + '_ {
+        self.absinfo.keys().copied().filter(|&code| !(ABS_HAT0X..=ABS_HAT3Y).contains(&code))
+    }
+
+    /// The hats in the order they're indexed by the SDL mappings, as their horizontal axis.
+    fn sdl_hats(&self) -> impl Iterator<Item = u16> + '_ {
+        (ABS_HAT0X..=ABS_HAT3Y).step_by(2).filter(|code| self.absinfo.contains_key(code))
+    }
+
+    fn describe(&self, id: RootDeviceId) -> DeviceInfo {
+        let axes = self
+            .absinfo
+            .iter()
+            .map(|(&code, info)| DeviceAxis {
+                id: code as _,
+                name: None,
+                min: info.minimum as f64,
+                max: info.maximum as f64,
+                // NOTE: The resolution is in units per millimeter.
+                resolution: (info.resolution as u32).saturating_mul(1000),
+            })
+            .collect();
+
+        DeviceInfo {
+            id,
+            name: self.name.clone(),
+            kind: DeviceKind::Gamepad,
+            vendor_id: (self.id.vendor != 0).then_some(self.id.vendor),
+            product_id: (self.id.product != 0).then_some(self.id.product),
+            axes,
+        }
+    }
+}
+
+/// Query the codes supported by the device for the event type.
+fn query_bits<const N: usize>(fd: c_int, ev: u16) -> Option<Vec<u16>> {
+    let mut bits = [0u8; N];
+    if unsafe { libc::ioctl(fd, eviocgbit(ev, N) as _, bits.as_mut_ptr()) } < 0 {
+        return None;
+    }
+
+    let codes = (0..N * 8).filter(|code| bits[code / 8] & (1 << (code % 8)) != 0);
+    Some(codes.map(|code| code as u16).collect())
+}
+
+struct Gamepad {
+    file: Rc<File>,
+    path: PathBuf,
+    token: RegistrationToken,
+    info: DeviceInfo,
+    mapping: Mapping,
+    has_rumble: bool,
+    absinfo: BTreeMap<u16, libc::input_absinfo>,
+    /// The last reported state of the buttons, the ones emulated from the axes included.
+    buttons: HashMap<GamepadButton, bool>,
+    /// The last reported value of the axes.
+    axes: HashMap<GamepadAxis, f64>,
+    rumble_effect: Option<i16>,
+}
+
+impl Gamepad {
+    /// Read the pending events of the device.
+    fn read(
+        &mut self,
+        gamepad_id: GamepadId,
+        pending: &mut VecDeque<(GamepadId, DeviceEvent)>,
+    ) -> io::Result<()> {
+        let mut events = [MaybeUninit::<libc::input_event>::uninit(); 64];
+        loop {
+            let len = unsafe {
+                libc::read(
+                    self.file.as_raw_fd(),
+                    events.as_mut_ptr().cast(),
+                    mem::size_of_val(&events),
+                )
+            };
+            if len < 0 {
+                let err = io::Error::last_os_error();
+                return match err.kind() {
+                    io::ErrorKind::WouldBlock => Ok(()),
+                    io::ErrorKind::Interrupted => continue,
+                    _ => Err(err),
+                };
+            } else if len == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+
+            let count = len as usize / mem::size_of::<libc::input_event>();
+            for event in &events[..count] {
+                let event = unsafe { event.assume_init_read() };
+                match (event.type_, event.code) {
+                    (EV_SYN, SYN_DROPPED) => {
+                        // NOTE: The next events describe the current state, thus only the changes
+                        // from the last reported state are delivered.
+                        tracing::debug!("Dropped the events of the gamepad {:?}", self.info.name);
+                    },
+                    // NOTE: The value of `2` is the key repeat.
+                    (EV_KEY, code) if event.value != 2 => {
+                        self.input(Input::Key(code), event.value, pending, gamepad_id);
+                    },
+                    (EV_ABS, code) => {
+                        if let Some(info) = self.absinfo.get_mut(&code) {
+                            info.value = event.value;
+                        }
+                        self.input(Input::Abs(code), event.value, pending, gamepad_id);
+                    },
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    /// Translate the input with the mapping and report the changes.
+    fn input(
+        &mut self,
+        input: Input,
+        value: i32,
+        pending: &mut VecDeque<(GamepadId, DeviceEvent)>,
+        gamepad_id: GamepadId,
+    ) {
+        for binding in
+            self.mapping.bindings.iter().filter(|binding| binding.source.input() == input)
+        {
+            // Normalize the value to `-1.0..=1.0` for the full range of the axis.
+            let value = match binding.source {
+                Source::Key(_) => value as f64,
+                Source::Abs { code, range, inverted } => {
+                    let value = match self.absinfo.get(&code) {
+                        Some(info) => normalize(info, value),
+                        None => 0.0,
+                    };
+                    let value = if inverted { -value } else { value };
+                    match range {
+                        Range::Full => value,
+                        Range::Positive => value.max(0.0),
+                        Range::Negative => (-value).max(0.0),
+                    }
+                },
+                Source::Hat { positive, .. } => match (value.signum(), positive) {
+                    (1, true) | (-1, false) => 1.0,
+                    _ => 0.0,
+                },
+            };
+
+            match binding.target {
+                Target::Button(button) => {
+                    let pressed = value > 0.5;
+                    if self.buttons.insert(button, pressed).unwrap_or(false) != pressed {
+                        let state =
+                            if pressed { ElementState::Pressed } else { ElementState::Released };
+                        pending
+                            .push_back((gamepad_id, DeviceEvent::GamepadButton { button, state }));
+                    }
+                },
+                Target::HalfAxis { axis, positive } => {
+                    // The full range of the source drives the half of the axis.
+                    let value = match binding.source {
+                        Source::Abs { range: Range::Full, .. } => (value + 1.0) / 2.0,
+                        _ => value,
+                    };
+                    let value = if positive { value } else { -value };
+                    if self.axes.insert(axis, value) != Some(value) {
+                        pending.push_back((gamepad_id, DeviceEvent::GamepadAxis { axis, value }));
+                    }
+                },
+                Target::Axis(axis) => {
+                    let value = match (axis, binding.source) {
+                        // The triggers rest at the minimum of the full range.
+                        (
+                            GamepadAxis::LeftTrigger | GamepadAxis::RightTrigger,
+                            Source::Abs { range: Range::Full, .. },
+                        ) => (value + 1.0) / 2.0,
+                        _ => value,
+                    };
+                    if self.axes.insert(axis, value) != Some(value) {
+                        pending.push_back((gamepad_id, DeviceEvent::GamepadAxis { axis, value }));
+                    }
+
+                    // Report the triggers as buttons too.
+                    let button = match axis {
+                        GamepadAxis::LeftTrigger => GamepadButton::LeftTrigger,
+                        GamepadAxis::RightTrigger => GamepadButton::RightTrigger,
+                        _ => continue,
+                    };
+                    let pressed = value > 0.5;
+                    if self.buttons.insert(button, pressed).unwrap_or(false) != pressed {
+                        let state =
+                            if pressed { ElementState::Pressed } else { ElementState::Released };
+                        pending
+                            .push_back((gamepad_id, DeviceEvent::GamepadButton { button, state }));
+                    }
+                },
+            }
+        }
+    }
+
+    fn rumble(&mut self, strong: f32, weak: f32, duration: Duration) -> io::Result<()> {
+        let fd = self.file.as_raw_fd();
+        let mut effect: libc::ff_effect = unsafe { mem::zeroed() };
+        effect.type_ = FF_RUMBLE;
+        // Update the uploaded effect in place.
+        effect.id = self.rumble_effect.unwrap_or(-1);
+        effect.replay.length = duration.as_millis().min(u16::MAX as u128) as u16;
+        let rumble = libc::ff_rumble_effect {
+            strong_magnitude: (strong.clamp(0.0, 1.0) * u16::MAX as f32) as u16,
+            weak_magnitude: (weak.clamp(0.0, 1.0) * u16::MAX as f32) as u16,
+        };
+        unsafe { effect.u.as_mut_ptr().cast::<libc::ff_rumble_effect>().write(rumble) };
+
+        if unsafe { libc::ioctl(fd, EVIOCSFF as _, &mut effect) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        self.rumble_effect = Some(effect.id);
+
+        let play = libc::input_event {
+            time: libc::timeval { tv_sec: 0, tv_usec: 0 },
+            type_: EV_FF,
+            code: effect.id as u16,
+            value: 1,
+        };
+        let size = mem::size_of_val(&play);
+        let written = unsafe { libc::write(fd, (&play as *const libc::input_event).cast(), size) };
+        if written < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Gamepad {
+    fn drop(&mut self) {
+        if let Some(effect) = self.rumble_effect {
+            unsafe { libc::ioctl(self.file.as_raw_fd(), EVIOCRMFF as _, effect as c_int) };
+        }
+    }
+}
+
+/// Normalize the value of the axis to the `-1.0..=1.0` range, applying the dead zone around the
+/// center.
+fn normalize(info: &libc::input_absinfo, value: i32) -> f64 {
+    let (min, max) = (info.minimum as f64, info.maximum as f64);
+    if max <= min {
+        return 0.0;
+    }
+
+    let center = (min + max) / 2.0;
+    if (value as f64 - center).abs() <= info.flat as f64 {
+        return 0.0;
+    }
+
+    ((value as f64 - min) / (max - min) * 2.0 - 1.0).clamp(-1.0, 1.0)
+}
+
+/// The evdev input the binding is triggered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Input {
+    Key(u16),
+    Abs(u16),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Range {
+    Full,
+    Positive,
+    Negative,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Source {
+    Key(u16),
+    Abs {
+        code: u16,
+        range: Range,
+        inverted: bool,
+    },
+    /// The direction of the hat axis.
+    Hat {
+        code: u16,
+        positive: bool,
+    },
+}
+
+impl Source {
+    fn input(&self) -> Input {
+        match *self {
+            Self::Key(code) => Input::Key(code),
+            Self::Abs { code, .. } | Self::Hat { code, .. } => Input::Abs(code),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Target {
+    Button(GamepadButton),
+    Axis(GamepadAxis),
+    /// The positive or the negative half of the axis.
+    HalfAxis {
+        axis: GamepadAxis,
+        positive: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Binding {
+    source: Source,
+    target: Target,
+}
+
+/// The translation of the device inputs to the standard layout.
+#[derive(Debug)]
+struct Mapping {
+    bindings: Vec<Binding>,
+}
+
+impl Mapping {
+    /// The mapping following the conventions of the kernel gamepad drivers, documented in
+    /// `Documentation/input/gamepad.rst`.
+    fn standard(capabilities: &Capabilities) -> Self {
+        let mut bindings = Vec::new();
+        for &code in &capabilities.keys {
+            let button = match code {
+                BTN_SOUTH => GamepadButton::South,
+                BTN_EAST => GamepadButton::East,
+                BTN_NORTH => GamepadButton::North,
+                BTN_WEST => GamepadButton::West,
+                BTN_TL => GamepadButton::LeftShoulder,
+                BTN_TR => GamepadButton::RightShoulder,
+                BTN_TL2 => GamepadButton::LeftTrigger,
+                BTN_TR2 => GamepadButton::RightTrigger,
+                BTN_SELECT => GamepadButton::Select,
+                BTN_START => GamepadButton::Start,
+                BTN_MODE => GamepadButton::Mode,
+                BTN_THUMBL => GamepadButton::LeftStick,
+                BTN_THUMBR => GamepadButton::RightStick,
+                BTN_DPAD_UP => GamepadButton::DPadUp,
+                BTN_DPAD_DOWN => GamepadButton::DPadDown,
+                BTN_DPAD_LEFT => GamepadButton::DPadLeft,
+                BTN_DPAD_RIGHT => GamepadButton::DPadRight,
+                code if code >= BTN_MISC => GamepadButton::Other(code),
+                _ => continue,
+            };
+            bindings.push(Binding { source: Source::Key(code), target: Target::Button(button) });
+        }
+
+        // The analog triggers are reported along the digital ones by some drivers.
+        let analog_triggers =
+            capabilities.absinfo.contains_key(&ABS_Z) && capabilities.absinfo.contains_key(&ABS_RZ);
+        for &code in capabilities.absinfo.keys() {
+            let target = match code {
+                ABS_X => Target::Axis(GamepadAxis::LeftStickX),
+                ABS_Y => Target::Axis(GamepadAxis::LeftStickY),
+                ABS_RX => Target::Axis(GamepadAxis::RightStickX),
+                ABS_RY => Target::Axis(GamepadAxis::RightStickY),
+                ABS_Z if analog_triggers => Target::Axis(GamepadAxis::LeftTrigger),
+                ABS_RZ if analog_triggers => Target::Axis(GamepadAxis::RightTrigger),
+                ABS_HAT0X | ABS_HAT0Y => {
+                    let (negative, positive) = if code == ABS_HAT0X {
+                        (GamepadButton::DPadLeft, GamepadButton::DPadRight)
+                    } else {
+                        (GamepadButton::DPadUp, GamepadButton::DPadDown)
+                    };
+                    bindings.push(Binding {
+                        source: Source::Hat { code, positive: false },
+                        target: Target::Button(negative),
+                    });
+                    bindings.push(Binding {
+                        source: Source::Hat { code, positive: true },
+                        target: Target::Button(positive),
+                    });
+                    continue;
+                },
+                code => Target::Axis(GamepadAxis::Other(code)),
+            };
+            let source = Source::Abs { code, range: Range::Full, inverted: false };
+            bindings.push(Binding { source, target });
+        }
+
+        Self { bindings }
+    }
+}
+
+/// The mapping in the SDL mapping database format, e.g.
+/// `030000005e0400008e02000010010000,Xbox 360,a:b0,b:b1,leftx:a0,dpup:h0.1,platform:Linux,`.
+#[derive(Debug)]
+struct SdlMapping {
+    guid: [u8; 16],
+    bindings: Vec<(String, String)>,
+}
+
+impl SdlMapping {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let mut fields = line.split(',');
+        let guid = fields.next()?;
+        let _name = fields.next()?;
+        if guid.len() != 32 {
+            return None;
+        }
+
+        let mut guid_bytes = [0; 16];
+        for (byte, hex) in guid_bytes.iter_mut().zip(guid.as_bytes().chunks(2)) {
+            *byte = u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?;
+        }
+
+        let mut bindings = Vec::new();
+        for field in fields.filter(|field| !field.is_empty()) {
+            let (target, source) = field.split_once(':')?;
+            if target == "platform" {
+                if source != "Linux" {
+                    return None;
+                }
+            } else {
+                bindings.push((target.to_owned(), source.to_owned()));
+            }
+        }
+
+        Some(Self { guid: guid_bytes, bindings })
+    }
+
+    /// Whether the mapping is for the device with the `guid`, ignoring the CRC of the name.
+    fn matches(&self, guid: &[u8; 16]) -> bool {
+        self.guid[0..2] == guid[0..2] && self.guid[4..] == guid[4..]
+    }
+
+    /// Resolve the indices of the mapping to the codes of the device.
+    fn resolve(&self, capabilities: &Capabilities) -> Mapping {
+        let buttons: Vec<_> = capabilities.sdl_buttons().collect();
+        let axes: Vec<_> = capabilities.sdl_axes().collect();
+        let hats: Vec<_> = capabilities.sdl_hats().collect();
+
+        let bindings = self
+            .bindings
+            .iter()
+            .filter_map(|(target, source)| {
+                let (target, half) = match target.as_bytes().first()? {
+                    b'+' => (&target[1..], Some(true)),
+                    b'-' => (&target[1..], Some(false)),
+                    _ => (target.as_str(), None),
+                };
+                let target = match target {
+                    "a" => Target::Button(GamepadButton::South),
+                    "b" => Target::Button(GamepadButton::East),
+                    "x" => Target::Button(GamepadButton::West),
+                    "y" => Target::Button(GamepadButton::North),
+                    "back" => Target::Button(GamepadButton::Select),
+                    "start" => Target::Button(GamepadButton::Start),
+                    "guide" => Target::Button(GamepadButton::Mode),
+                    "leftshoulder" => Target::Button(GamepadButton::LeftShoulder),
+                    "rightshoulder" => Target::Button(GamepadButton::RightShoulder),
+                    "leftstick" => Target::Button(GamepadButton::LeftStick),
+                    "rightstick" => Target::Button(GamepadButton::RightStick),
+                    "dpup" => Target::Button(GamepadButton::DPadUp),
+                    "dpdown" => Target::Button(GamepadButton::DPadDown),
+                    "dpleft" => Target::Button(GamepadButton::DPadLeft),
+                    "dpright" => Target::Button(GamepadButton::DPadRight),
+                    "leftx" => Target::Axis(GamepadAxis::LeftStickX),
+                    "lefty" => Target::Axis(GamepadAxis::LeftStickY),
+                    "rightx" => Target::Axis(GamepadAxis::RightStickX),
+                    "righty" => Target::Axis(GamepadAxis::RightStickY),
+                    "lefttrigger" => Target::Axis(GamepadAxis::LeftTrigger),
+                    "righttrigger" => Target::Axis(GamepadAxis::RightTrigger),
+                    _ => return None,
+                };
+                let target = match (target, half) {
+                    (Target::Axis(axis), Some(positive)) => Target::HalfAxis { axis, positive },
+                    (target, _) => target,
+                };
+
+                let source = if let Some(index) = source.strip_prefix('b') {
+                    let code = *buttons.get(index.parse::<usize>().ok()?)?;
+                    Source::Key(code)
+                } else if let Some(hat) = source.strip_prefix('h') {
+                    let (index, mask) = hat.split_once('.')?;
+                    let code = *hats.get(index.parse::<usize>().ok()?)?;
+                    // The mask is 1 for up, 2 for right, 4 for down and 8 for left.
+                    match mask.parse::<u8>().ok()? {
+                        1 => Source::Hat { code: code + 1, positive: false },
+                        2 => Source::Hat { code, positive: true },
+                        4 => Source::Hat { code: code + 1, positive: true },
+                        8 => Source::Hat { code, positive: false },
+                        _ => return None,
+                    }
+                } else {
+                    let (range, axis) = match source.as_bytes().first()? {
+                        b'+' => (Range::Positive, &source[1..]),
+                        b'-' => (Range::Negative, &source[1..]),
+                        _ => (Range::Full, source.as_str()),
+                    };
+                    let (axis, inverted) = match axis.strip_suffix('~') {
+                        Some(axis) => (axis, true),
+                        None => (axis, false),
+                    };
+                    let index = axis.strip_prefix('a')?.parse::<usize>().ok()?;
+                    Source::Abs { code: *axes.get(index)?, range, inverted }
+                };
+
+                // The triggers mapped to the buttons are reported as buttons.
+                let target = match (target, source) {
+                    (
+                        Target::Axis(GamepadAxis::LeftTrigger),
+                        Source::Key(_) | Source::Hat { .. },
+                    ) => Target::Button(GamepadButton::LeftTrigger),
+                    (
+                        Target::Axis(GamepadAxis::RightTrigger),
+                        Source::Key(_) | Source::Hat { .. },
+                    ) => Target::Button(GamepadButton::RightTrigger),
+                    (target, _) => target,
+                };
+
+                Some(Binding { source, target })
+            })
+            .collect();
+
+        Mapping { bindings }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capabilities() -> Capabilities {
+        let absinfo = libc::input_absinfo {
+            value: 0,
+            minimum: -32768,
+            maximum: 32767,
+            fuzz: 16,
+            flat: 128,
+            resolution: 0,
+        };
+        let hat = libc::input_absinfo { minimum: -1, maximum: 1, fuzz: 0, flat: 0, ..absinfo };
+        Capabilities {
+            name: String::from("Test Pad"),
+            id: libc::input_id { bustype: 3, vendor: 0x045e, product: 0x028e, version: 0x0110 },
+            keys: vec![BTN_SOUTH, BTN_EAST, BTN_NORTH, BTN_WEST, BTN_TL, BTN_TR, BTN_SELECT],
+            absinfo: [(ABS_X, absinfo), (ABS_Y, absinfo), (ABS_HAT0X, hat), (ABS_HAT0Y, hat)]
+                .into_iter()
+                .collect(),
+            rumble: false,
+        }
+    }
+
+    #[test]
+    fn sdl_mapping() {
+        let capabilities = capabilities();
+        let mapping = SdlMapping::parse(
+            "030000005e0400008e02000010010000,Test \
+             Pad,a:b1,dpup:h0.1,leftx:a1~,-lefty:a0,lefttrigger:b6,platform:Linux,",
+        )
+        .unwrap();
+        assert!(mapping.matches(&capabilities.guid()));
+        assert!(SdlMapping::parse("030000005e0400008e02000010010000,Pad,a:b0,platform:Windows,")
+            .is_none());
+
+        let mapping = mapping.resolve(&capabilities);
+        let bindings: Vec<_> =
+            mapping.bindings.iter().map(|binding| format!("{binding:?}")).collect();
+        assert_eq!(bindings, [
+            "Binding { source: Key(305), target: Button(South) }",
+            "Binding { source: Hat { code: 17, positive: false }, target: Button(DPadUp) }",
+            "Binding { source: Abs { code: 1, range: Full, inverted: true }, target: \
+             Axis(LeftStickX) }",
+            "Binding { source: Abs { code: 0, range: Full, inverted: false }, target: HalfAxis { \
+             axis: LeftStickY, positive: false } }",
+            "Binding { source: Key(314), target: Button(LeftTrigger) }",
+        ]);
+    }
+
+    #[test]
+    fn normalized_axes() {
+        let info = capabilities().absinfo[&ABS_X];
+        assert_eq!(normalize(&info, -32768), -1.0);
+        assert_eq!(normalize(&info, 100), 0.0);
+        assert_eq!(normalize(&info, 32767), 1.0);
+    }
+}
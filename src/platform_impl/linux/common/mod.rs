@@ -1 +1,6 @@
+pub mod executor;
+#[cfg(target_os = "linux")]
+pub mod gamepad;
+pub mod modal;
+pub mod user_sources;
 pub mod xkb;
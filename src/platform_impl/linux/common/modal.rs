@@ -0,0 +1,62 @@
+//! Blocking of the user input to the parents of modal windows.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::event::WindowEvent;
+use crate::platform_impl::WindowId;
+
+/// The windows blocked by their modal children.
+#[derive(Debug, Default, Clone)]
+pub struct ModalBlocker {
+    blocked: Arc<Mutex<HashMap<WindowId, usize>>>,
+}
+
+impl ModalBlocker {
+    /// Block the `parent` until the returned guard is dropped.
+    pub fn block(&self, parent: WindowId) -> ModalGuard {
+        *self.blocked.lock().unwrap().entry(parent).or_default() += 1;
+        ModalGuard { blocker: self.clone(), parent }
+    }
+
+    /// Whether the `event` to the `window_id` must be dropped, because the window has a modal
+    /// child.
+    pub fn should_filter(&self, window_id: WindowId, event: &WindowEvent) -> bool {
+        let is_user_input = matches!(
+            event,
+            WindowEvent::KeyboardInput { .. }
+                | WindowEvent::Ime(_)
+                | WindowEvent::CursorMoved { .. }
+                | WindowEvent::MouseWheel { .. }
+                | WindowEvent::MouseInput { .. }
+                | WindowEvent::PinchGesture { .. }
+                | WindowEvent::PanGesture { .. }
+                | WindowEvent::DoubleTapGesture { .. }
+                | WindowEvent::RotationGesture { .. }
+                | WindowEvent::TouchpadPressure { .. }
+                | WindowEvent::AxisMotion { .. }
+                | WindowEvent::Touch(_)
+        );
+
+        is_user_input && self.blocked.lock().unwrap().contains_key(&window_id)
+    }
+}
+
+/// Keeps the parent of the modal window blocked while alive.
+#[derive(Debug)]
+pub struct ModalGuard {
+    blocker: ModalBlocker,
+    parent: WindowId,
+}
+
+impl Drop for ModalGuard {
+    fn drop(&mut self) {
+        let mut blocked = self.blocker.blocked.lock().unwrap();
+        if let Some(count) = blocked.get_mut(&self.parent) {
+            *count -= 1;
+            if *count == 0 {
+                blocked.remove(&self.parent);
+            }
+        }
+    }
+}
@@ -0,0 +1,316 @@
+//! The file descriptors, timers, idle timeouts, and global shortcuts registered with the event
+//! loop by the user.
+
+use std::cell::{Cell, RefCell};
+#[cfg(any(x11_platform, feature = "wayland-global-shortcuts"))]
+use std::collections::HashSet;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::os::unix::io::AsFd;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use calloop::generic::Generic;
+use calloop::timer::{TimeoutAction, Timer};
+use calloop::{Interest, LoopHandle, Mode, PostAction, Readiness, RegistrationToken};
+
+use crate::application::ApplicationHandler;
+#[cfg(any(x11_platform, feature = "wayland-global-shortcuts"))]
+use crate::event::ElementState;
+#[cfg(any(x11_platform, feature = "wayland-global-shortcuts"))]
+use crate::event_loop::GlobalShortcutId;
+use crate::event_loop::{
+    ActiveEventLoop as RootActiveEventLoop, FdId, FdReadiness, IdleTimeoutId, TimerId,
+};
+
+/// How often the idle time is polled to detect the user activity once the user is idle.
+#[cfg(x11_platform)]
+const IDLE_RESUME_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The event from the user source waiting to be delivered.
+#[derive(Debug, Clone, Copy)]
+pub enum UserSourceEvent {
+    Fd(FdId, FdReadiness),
+    Timer(TimerId),
+    Idle(IdleTimeoutId, bool),
+    #[cfg(any(x11_platform, feature = "wayland-global-shortcuts"))]
+    GlobalShortcut(GlobalShortcutId, ElementState),
+}
+
+impl UserSourceEvent {
+    /// Deliver the event to the application.
+    pub fn deliver<A: ApplicationHandler>(self, app: &mut A, event_loop: &RootActiveEventLoop) {
+        match self {
+            Self::Fd(fd_id, readiness) => app.fd_ready(event_loop, fd_id, readiness),
+            Self::Timer(timer_id) => app.timer_expired(event_loop, timer_id),
+            Self::Idle(timeout_id, idle) => app.idle_changed(event_loop, timeout_id, idle),
+            #[cfg(any(x11_platform, feature = "wayland-global-shortcuts"))]
+            Self::GlobalShortcut(shortcut_id, state) => {
+                app.global_shortcut(event_loop, shortcut_id, state)
+            },
+        }
+    }
+}
+
+/// The sink reporting the activation or the deactivation of the global shortcuts.
+#[cfg(feature = "wayland-global-shortcuts")]
+#[derive(Clone)]
+pub struct GlobalShortcutSink(Rc<RefCell<VecDeque<UserSourceEvent>>>);
+
+#[cfg(feature = "wayland-global-shortcuts")]
+impl GlobalShortcutSink {
+    pub fn push(&self, shortcut_id: GlobalShortcutId, state: ElementState) {
+        self.0.borrow_mut().push_back(UserSourceEvent::GlobalShortcut(shortcut_id, state));
+    }
+}
+
+/// The timer registered by the user.
+struct UserTimer {
+    token: RegistrationToken,
+    /// The next expiration of the timer, updated by the event loop.
+    deadline: Rc<Cell<Instant>>,
+    repeating: bool,
+}
+
+/// The timer polling the idle time for the idle timeout.
+struct IdlePoll {
+    token: RegistrationToken,
+    deadline: Rc<Cell<Instant>>,
+}
+
+/// The registry of the user sources inserted into the calloop event loop.
+pub struct UserSources<D: 'static> {
+    handle: LoopHandle<'static, D>,
+    pending: Rc<RefCell<VecDeque<UserSourceEvent>>>,
+    fds: RefCell<HashMap<FdId, RegistrationToken>>,
+    timers: RefCell<HashMap<TimerId, UserTimer>>,
+    /// The idle timeouts, which are either polled by us or driven by the display server.
+    idle_timeouts: Rc<RefCell<HashMap<IdleTimeoutId, Option<IdlePoll>>>>,
+    #[cfg(any(x11_platform, feature = "wayland-global-shortcuts"))]
+    global_shortcuts: RefCell<HashSet<GlobalShortcutId>>,
+    next_id: Cell<u64>,
+}
+
+impl<D: 'static> UserSources<D> {
+    pub fn new(handle: LoopHandle<'static, D>) -> Self {
+        Self {
+            handle,
+            pending: Default::default(),
+            fds: Default::default(),
+            timers: Default::default(),
+            idle_timeouts: Default::default(),
+            #[cfg(any(x11_platform, feature = "wayland-global-shortcuts"))]
+            global_shortcuts: Default::default(),
+            next_id: Cell::new(0),
+        }
+    }
+
+    pub fn register_fd<F: AsFd + 'static>(
+        &self,
+        fd: F,
+        readable: bool,
+        writable: bool,
+    ) -> io::Result<FdId> {
+        let fd_id = FdId(self.next_id());
+        let pending = self.pending.clone();
+        let source = Generic::new(fd, Interest { readable, writable }, Mode::Level);
+        let token = self
+            .handle
+            .insert_source(source, move |readiness: Readiness, _, _| {
+                let readiness = FdReadiness {
+                    readable: readiness.readable,
+                    writable: readiness.writable,
+                    error: readiness.error,
+                };
+                pending.borrow_mut().push_back(UserSourceEvent::Fd(fd_id, readiness));
+                Ok(PostAction::Continue)
+            })
+            .map_err(|error| error.error)?;
+
+        self.fds.borrow_mut().insert(fd_id, token);
+        Ok(fd_id)
+    }
+
+    pub fn unregister_fd(&self, fd_id: FdId) {
+        if let Some(token) = self.fds.borrow_mut().remove(&fd_id) {
+            self.handle.remove(token);
+        }
+    }
+
+    pub fn set_timer(&self, deadline: Instant, interval: Option<Duration>) -> TimerId {
+        let timer_id = TimerId(self.next_id());
+        let pending = self.pending.clone();
+        let next_deadline = Rc::new(Cell::new(deadline));
+        let timer_deadline = next_deadline.clone();
+        let token = self
+            .handle
+            .insert_source(Timer::from_deadline(deadline), move |_, _, _| {
+                pending.borrow_mut().push_back(UserSourceEvent::Timer(timer_id));
+                match interval {
+                    Some(interval) => {
+                        let deadline = Instant::now() + interval;
+                        timer_deadline.set(deadline);
+                        TimeoutAction::ToInstant(deadline)
+                    },
+                    None => TimeoutAction::Drop,
+                }
+            })
+            .expect("Failed to register the timer");
+
+        let timer = UserTimer { token, deadline: next_deadline, repeating: interval.is_some() };
+        self.timers.borrow_mut().insert(timer_id, timer);
+        timer_id
+    }
+
+    pub fn cancel_timer(&self, timer_id: TimerId) {
+        if let Some(timer) = self.timers.borrow_mut().remove(&timer_id) {
+            // NOTE: The token is versioned, thus removing the already expired timer is fine.
+            self.handle.remove(timer.token);
+        }
+    }
+
+    /// Register the idle timeout, which state is reported with [`UserSources::push_idle`].
+    #[cfg(wayland_platform)]
+    pub fn add_idle_timeout(&self) -> IdleTimeoutId {
+        let timeout_id = IdleTimeoutId(self.next_id());
+        self.idle_timeouts.borrow_mut().insert(timeout_id, None);
+        timeout_id
+    }
+
+    /// Register the idle timeout, which state is tracked by polling the time since the last user
+    /// input with `idle_time`.
+    ///
+    /// The idle timeout is removed once `idle_time` returns `None`.
+    #[cfg(x11_platform)]
+    pub fn poll_idle_timeout<F>(&self, timeout: Duration, idle_time: F) -> IdleTimeoutId
+    where
+        F: Fn() -> Option<Duration> + 'static,
+    {
+        let timeout_id = IdleTimeoutId(self.next_id());
+        let pending = self.pending.clone();
+        let idle_timeouts = self.idle_timeouts.clone();
+        let deadline = Rc::new(Cell::new(Instant::now() + timeout));
+        let poll_deadline = deadline.clone();
+        let mut idle = false;
+        let token = self
+            .handle
+            .insert_source(Timer::from_deadline(deadline.get()), move |_, _, _| {
+                let idle_time = match idle_time() {
+                    Some(idle_time) => idle_time,
+                    None => {
+                        // NOTE: Forget the poll, so its deadline doesn't wake up the loop.
+                        idle_timeouts.borrow_mut().remove(&timeout_id);
+                        return TimeoutAction::Drop;
+                    },
+                };
+
+                // NOTE: The idle time is reset by the user input, thus once it's below the
+                // timeout the user is active again.
+                if idle_time >= timeout {
+                    if !idle {
+                        idle = true;
+                        pending.borrow_mut().push_back(UserSourceEvent::Idle(timeout_id, true));
+                    }
+                } else if idle {
+                    idle = false;
+                    pending.borrow_mut().push_back(UserSourceEvent::Idle(timeout_id, false));
+                }
+
+                let next_poll = if idle { IDLE_RESUME_POLL_INTERVAL } else { timeout - idle_time };
+                let deadline = Instant::now() + next_poll;
+                poll_deadline.set(deadline);
+                TimeoutAction::ToInstant(deadline)
+            })
+            .expect("Failed to register the idle timeout");
+
+        self.idle_timeouts.borrow_mut().insert(timeout_id, Some(IdlePoll { token, deadline }));
+        timeout_id
+    }
+
+    /// Report the change of the idle state for the idle timeout.
+    #[cfg(wayland_platform)]
+    pub fn push_idle(&self, timeout_id: IdleTimeoutId, idle: bool) {
+        self.pending.borrow_mut().push_back(UserSourceEvent::Idle(timeout_id, idle));
+    }
+
+    pub fn remove_idle_timeout(&self, timeout_id: IdleTimeoutId) {
+        if let Some(Some(poll)) = self.idle_timeouts.borrow_mut().remove(&timeout_id) {
+            self.handle.remove(poll.token);
+        }
+    }
+
+    /// Register the global shortcut, which state is reported with
+    /// [`UserSources::push_global_shortcut`].
+    #[cfg(any(x11_platform, feature = "wayland-global-shortcuts"))]
+    pub fn add_global_shortcut(&self) -> GlobalShortcutId {
+        let shortcut_id = GlobalShortcutId(self.next_id());
+        self.global_shortcuts.borrow_mut().insert(shortcut_id);
+        shortcut_id
+    }
+
+    /// Report the activation or the deactivation of the global shortcut.
+    #[cfg(x11_platform)]
+    pub fn push_global_shortcut(&self, shortcut_id: GlobalShortcutId, state: ElementState) {
+        self.pending.borrow_mut().push_back(UserSourceEvent::GlobalShortcut(shortcut_id, state));
+    }
+
+    /// The sink reporting the global shortcuts from the futures running on the event loop thread.
+    #[cfg(feature = "wayland-global-shortcuts")]
+    pub fn global_shortcut_sink(&self) -> GlobalShortcutSink {
+        GlobalShortcutSink(self.pending.clone())
+    }
+
+    #[cfg(any(x11_platform, feature = "wayland-global-shortcuts"))]
+    pub fn remove_global_shortcut(&self, shortcut_id: GlobalShortcutId) {
+        self.global_shortcuts.borrow_mut().remove(&shortcut_id);
+    }
+
+    /// Whether there're events waiting to be delivered.
+    pub fn has_pending(&self) -> bool {
+        !self.pending.borrow().is_empty()
+    }
+
+    /// The earliest expiration among the registered timers.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let timers = self.timers.borrow();
+        let idle_timeouts = self.idle_timeouts.borrow();
+        let idle_polls = idle_timeouts.values().flatten().map(|poll| poll.deadline.get());
+        timers.values().map(|timer| timer.deadline.get()).chain(idle_polls).min()
+    }
+
+    /// Take the next event, skipping the ones from the sources removed in the meantime.
+    pub fn pop(&self) -> Option<UserSourceEvent> {
+        loop {
+            let event = self.pending.borrow_mut().pop_front()?;
+            let alive = match event {
+                UserSourceEvent::Fd(fd_id, _) => self.fds.borrow().contains_key(&fd_id),
+                UserSourceEvent::Timer(timer_id) => {
+                    let mut timers = self.timers.borrow_mut();
+                    match timers.get(&timer_id) {
+                        Some(timer) if timer.repeating => true,
+                        // The one-shot timer is dropped by the event loop once expired.
+                        Some(_) => timers.remove(&timer_id).is_some(),
+                        None => false,
+                    }
+                },
+                UserSourceEvent::Idle(timeout_id, _) => {
+                    self.idle_timeouts.borrow().contains_key(&timeout_id)
+                },
+                #[cfg(any(x11_platform, feature = "wayland-global-shortcuts"))]
+                UserSourceEvent::GlobalShortcut(shortcut_id, _) => {
+                    self.global_shortcuts.borrow().contains(&shortcut_id)
+                },
+            };
+
+            if alive {
+                return Some(event);
+            }
+        }
+    }
+
+    fn next_id(&self) -> u64 {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        id
+    }
+}
@@ -1,6 +1,6 @@
 //! XKB keymap.
 
-use std::ffi::c_char;
+use std::ffi::{c_char, CStr};
 use std::ops::Deref;
 use std::ptr::{self, NonNull};
 
@@ -18,6 +18,24 @@ use crate::keyboard::{Key, KeyCode, KeyLocation, NamedKey, NativeKey, NativeKeyC
 #[cfg(x11_platform)]
 use crate::platform_impl::common::xkb::XKBXH;
 use crate::platform_impl::common::xkb::{XkbContext, XKBH};
+use crate::utils::Lazy;
+
+type LayoutGetName = unsafe extern "C" fn(*mut xkb_keymap, xkb_layout_index_t) -> *const c_char;
+
+/// The `xkb_keymap_layout_get_name` isn't exposed by `xkbcommon-dl`, thus look it up in the
+/// already loaded library.
+static XKB_KEYMAP_LAYOUT_GET_NAME: Lazy<Option<LayoutGetName>> = Lazy::new(|| unsafe {
+    let library = libc::dlopen(
+        b"libxkbcommon.so.0\0".as_ptr() as *const c_char,
+        libc::RTLD_LAZY | libc::RTLD_NOLOAD,
+    );
+    if library.is_null() {
+        return None;
+    }
+
+    let symbol = libc::dlsym(library, b"xkb_keymap_layout_get_name\0".as_ptr() as *const c_char);
+    (!symbol.is_null()).then(|| std::mem::transmute::<*mut libc::c_void, LayoutGetName>(symbol))
+});
 
 /// Map the raw X11-style keycode to the `KeyCode` enum.
 ///
@@ -965,7 +983,6 @@ impl XkbKeymap {
         Self { keymap, _mods_indices: mods_indices, _core_keyboard_id }
     }
 
-    #[cfg(x11_platform)]
     pub fn mods_indices(&self) -> ModsIndices {
         self._mods_indices
     }
@@ -994,12 +1011,44 @@ impl XkbKeymap {
         }
     }
 
+    /// The names of the layouts in the keymap, empty for the unnamed ones.
+    pub fn layout_names(&self) -> Vec<String> {
+        let count = unsafe { (XKBH.xkb_keymap_num_layouts)(self.keymap.as_ptr()) };
+        (0..count)
+            .map(|layout| {
+                let name = match *XKB_KEYMAP_LAYOUT_GET_NAME {
+                    Some(layout_get_name) => unsafe {
+                        layout_get_name(self.keymap.as_ptr(), layout)
+                    },
+                    None => ptr::null(),
+                };
+
+                if name.is_null() {
+                    String::new()
+                } else {
+                    unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned()
+                }
+            })
+            .collect()
+    }
+
     /// Check whether the given key repeats.
     pub fn key_repeats(&mut self, keycode: xkb_keycode_t) -> bool {
         unsafe { (XKBH.xkb_keymap_key_repeats)(self.keymap.as_ptr(), keycode) == 1 }
     }
 }
 
+impl Clone for XkbKeymap {
+    fn clone(&self) -> Self {
+        let keymap = unsafe { (XKBH.xkb_keymap_ref)(self.keymap.as_ptr()) };
+        Self {
+            keymap: NonNull::new(keymap).unwrap(),
+            _mods_indices: self._mods_indices,
+            _core_keyboard_id: self._core_keyboard_id,
+        }
+    }
+}
+
 impl Drop for XkbKeymap {
     fn drop(&mut self) {
         unsafe {
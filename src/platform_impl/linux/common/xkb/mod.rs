@@ -1,21 +1,23 @@
+use std::mem;
 use std::ops::Deref;
 use std::os::raw::c_char;
 #[cfg(wayland_platform)]
 use std::os::unix::io::OwnedFd;
 use std::ptr::{self, NonNull};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use smol_str::SmolStr;
 use tracing::warn;
 use xkbcommon_dl::{
-    self as xkb, xkb_compose_status, xkb_context, xkb_context_flags, xkbcommon_compose_handle,
-    xkbcommon_handle, XkbCommon, XkbCommonCompose,
+    self as xkb, keysyms, xkb_compose_status, xkb_context, xkb_context_flags,
+    xkbcommon_compose_handle, xkbcommon_handle, XkbCommon, XkbCommonCompose,
 };
 #[cfg(x11_platform)]
 use {x11_dl::xlib_xcb::xcb_connection_t, xkbcommon_dl::x11::xkbcommon_x11_handle};
 
-use crate::event::{ElementState, KeyEvent};
-use crate::keyboard::{Key, KeyLocation};
+use crate::event::{Compose, ElementState, KeyEvent};
+use crate::keyboard::{Key, KeyLocation, ModifiersState, NativeKey, PhysicalKey};
 use crate::platform_impl::KeyEventExtra;
 use crate::utils::Lazy;
 
@@ -30,17 +32,18 @@ use keymap::XkbKeymap;
 pub use keymap::{physicalkey_to_scancode, scancode_to_physicalkey};
 pub use state::XkbState;
 
-// TODO: Wire this up without using a static `AtomicBool`.
-static RESET_DEAD_KEYS: AtomicBool = AtomicBool::new(false);
-
 static XKBH: Lazy<&'static XkbCommon> = Lazy::new(xkbcommon_handle);
 static XKBCH: Lazy<&'static XkbCommonCompose> = Lazy::new(xkbcommon_compose_handle);
 #[cfg(feature = "x11")]
 static XKBXH: Lazy<&'static xkb::x11::XkbCommonX11> = Lazy::new(xkbcommon_x11_handle);
 
+/// The request to reset the dead keys, shared between the windows and the context of their
+/// event loop.
+pub type ResetDeadKeys = Arc<AtomicBool>;
+
 #[inline(always)]
-pub fn reset_dead_keys() {
-    RESET_DEAD_KEYS.store(true, Ordering::SeqCst);
+pub fn reset_dead_keys(reset_dead_keys: &ResetDeadKeys) {
+    reset_dead_keys.store(true, Ordering::SeqCst);
 }
 
 #[derive(Debug)]
@@ -59,12 +62,14 @@ pub struct Context {
     compose_state1: Option<XkbComposeState>,
     compose_state2: Option<XkbComposeState>,
     _compose_table: Option<XkbComposeTable>,
+    compose_preedit: ComposePreedit,
     context: XkbContext,
     scratch_buffer: Vec<u8>,
+    reset_dead_keys: ResetDeadKeys,
 }
 
 impl Context {
-    pub fn new() -> Result<Self, Error> {
+    pub fn new(reset_dead_keys: ResetDeadKeys) -> Result<Self, Error> {
         if xkb::xkbcommon_option().is_none() {
             return Err(Error::XKBNotFound);
         }
@@ -89,13 +94,18 @@ impl Context {
             #[cfg(x11_platform)]
             core_keyboard_id: 0,
             _compose_table: compose_table,
+            compose_preedit: Default::default(),
             context,
             scratch_buffer: Vec::with_capacity(8),
+            reset_dead_keys,
         })
     }
 
     #[cfg(feature = "x11")]
-    pub fn from_x11_xkb(xcb: *mut xcb_connection_t) -> Result<Self, Error> {
+    pub fn from_x11_xkb(
+        xcb: *mut xcb_connection_t,
+        reset_dead_keys: ResetDeadKeys,
+    ) -> Result<Self, Error> {
         let result = unsafe {
             (XKBXH.xkb_x11_setup_xkb_extension)(
                 xcb,
@@ -113,7 +123,7 @@ impl Context {
             return Err(Error::XKBNotFound);
         }
 
-        let mut this = Self::new()?;
+        let mut this = Self::new(reset_dead_keys)?;
         this.core_keyboard_id = unsafe { (XKBXH.xkb_x11_get_core_keyboard_device_id)(xcb) };
         this.set_keymap_from_x11(xcb);
         Ok(this)
@@ -130,7 +140,7 @@ impl Context {
     #[cfg(wayland_platform)]
     pub fn set_keymap_from_fd(&mut self, fd: OwnedFd, size: usize) {
         let keymap = XkbKeymap::from_fd(&self.context, fd, size);
-        let state = keymap.as_ref().and_then(XkbState::new_wayland);
+        let state = keymap.as_ref().and_then(XkbState::new);
         if keymap.is_none() || state.is_none() {
             warn!("failed to update xkb keymap");
         }
@@ -149,14 +159,36 @@ impl Context {
         self.keymap = keymap;
     }
 
+    /// The snapshot of the current keymap along with its active layout.
+    pub fn keymap_snapshot(&self) -> Option<KeymapSnapshot> {
+        let state = self.state.as_ref()?;
+        let keymap = self.keymap.as_ref()?;
+        Some(KeymapSnapshot { keymap: keymap.clone(), layout: state.active_layout() })
+    }
+
+    /// Take the change of the compose sequence since the last call.
+    pub fn take_compose(&mut self) -> Option<Compose> {
+        self.compose_preedit.take_update()
+    }
+
     /// Key builder context with the user provided xkb state.
     pub fn key_context(&mut self) -> Option<KeyContext<'_>> {
         let state = self.state.as_mut()?;
         let keymap = self.keymap.as_mut()?;
         let compose_state1 = self.compose_state1.as_mut();
         let compose_state2 = self.compose_state2.as_mut();
+        let compose_preedit = &mut self.compose_preedit;
         let scratch_buffer = &mut self.scratch_buffer;
-        Some(KeyContext { state, keymap, compose_state1, compose_state2, scratch_buffer })
+        let reset_dead_keys = &*self.reset_dead_keys;
+        Some(KeyContext {
+            state,
+            keymap,
+            compose_state1,
+            compose_state2,
+            compose_preedit,
+            scratch_buffer,
+            reset_dead_keys,
+        })
     }
 
     /// Key builder context with the user provided xkb state.
@@ -170,8 +202,18 @@ impl Context {
         let keymap = self.keymap.as_mut()?;
         let compose_state1 = self.compose_state1.as_mut();
         let compose_state2 = self.compose_state2.as_mut();
+        let compose_preedit = &mut self.compose_preedit;
         let scratch_buffer = &mut self.scratch_buffer;
-        Some(KeyContext { state, keymap, compose_state1, compose_state2, scratch_buffer })
+        let reset_dead_keys = &*self.reset_dead_keys;
+        Some(KeyContext {
+            state,
+            keymap,
+            compose_state1,
+            compose_state2,
+            compose_preedit,
+            scratch_buffer,
+            reset_dead_keys,
+        })
     }
 }
 
@@ -180,7 +222,9 @@ pub struct KeyContext<'a> {
     pub keymap: &'a mut XkbKeymap,
     compose_state1: Option<&'a mut XkbComposeState>,
     compose_state2: Option<&'a mut XkbComposeState>,
+    compose_preedit: &'a mut ComposePreedit,
     scratch_buffer: &'a mut Vec<u8>,
+    reset_dead_keys: &'a AtomicBool,
 }
 
 impl<'a> KeyContext<'a> {
@@ -204,37 +248,153 @@ impl<'a> KeyContext<'a> {
     }
 
     fn keysym_to_utf8_raw(&mut self, keysym: u32) -> Option<SmolStr> {
-        self.scratch_buffer.clear();
-        self.scratch_buffer.reserve(8);
-        loop {
-            let bytes_written = unsafe {
-                (XKBH.xkb_keysym_to_utf8)(
-                    keysym,
-                    self.scratch_buffer.as_mut_ptr().cast(),
-                    self.scratch_buffer.capacity(),
-                )
-            };
-            if bytes_written == 0 {
-                return None;
-            } else if bytes_written == -1 {
-                self.scratch_buffer.reserve(8);
-            } else {
-                unsafe { self.scratch_buffer.set_len(bytes_written.try_into().unwrap()) };
-                break;
+        keysym_to_utf8_raw(keysym, self.scratch_buffer)
+    }
+}
+
+/// The text of the compose sequence in progress, as reported to the user.
+#[derive(Debug, Default)]
+struct ComposePreedit {
+    text: String,
+    changed: bool,
+}
+
+impl ComposePreedit {
+    fn push(&mut self, text: &str) {
+        if !text.is_empty() {
+            self.text.push_str(text);
+            self.changed = true;
+        }
+    }
+
+    fn clear(&mut self) {
+        if !self.text.is_empty() {
+            self.text.clear();
+            self.changed = true;
+        }
+    }
+
+    fn take_update(&mut self) -> Option<Compose> {
+        if !mem::take(&mut self.changed) {
+            return None;
+        }
+
+        if self.text.is_empty() {
+            Some(Compose::End)
+        } else {
+            Some(Compose::Preedit(self.text.clone()))
+        }
+    }
+}
+
+/// The keymap along with its active layout, detached from the keyboard state.
+#[derive(Debug, Clone)]
+pub struct KeymapSnapshot {
+    keymap: XkbKeymap,
+    layout: xkb::xkb_layout_index_t,
+}
+
+impl KeymapSnapshot {
+    pub fn layout_names(&self) -> Vec<String> {
+        self.keymap.layout_names()
+    }
+
+    pub fn active_layout(&self) -> u32 {
+        self.layout
+    }
+
+    /// The logical key produced by the physical key under the active layout with the given
+    /// modifiers held.
+    pub fn key(&self, physical_key: PhysicalKey, modifiers: ModifiersState) -> Key {
+        let keycode = match physicalkey_to_scancode(physical_key) {
+            Some(scancode) => scancode + 8,
+            None => return Key::Unidentified(NativeKey::Unidentified),
+        };
+
+        let mods_indices = self.keymap.mods_indices();
+        let mut mods = 0;
+        for (modifier, index) in [
+            (ModifiersState::SHIFT, mods_indices.shift),
+            (ModifiersState::CONTROL, mods_indices.ctrl),
+            (ModifiersState::ALT, mods_indices.alt),
+            (ModifiersState::SUPER, mods_indices.logo),
+            // The level 3 and level 5 shifts are bound to `Mod5` and `Mod3` by the xkb rules.
+            (ModifiersState::ALT_GRAPH, mods_indices.mod5),
+            (ModifiersState::LEVEL5, mods_indices.mod3),
+        ] {
+            if let Some(index) = index.filter(|_| modifiers.contains(modifier)) {
+                mods |= 1 << index;
             }
         }
 
-        // Remove the null-terminator
-        self.scratch_buffer.pop();
-        byte_slice_to_smol_str(self.scratch_buffer)
+        let mut state = match XkbState::new(&self.keymap) {
+            Some(state) => state,
+            None => return Key::Unidentified(NativeKey::Unidentified),
+        };
+        state.update_modifiers(mods, 0, 0, 0, 0, self.layout);
+
+        let keysym = state.get_one_sym_raw(keycode);
+        match keymap::keysym_to_key(keysym) {
+            Key::Unidentified(_) => keysym_to_utf8_raw(keysym, &mut Vec::with_capacity(8))
+                .map(Key::Character)
+                .unwrap_or_else(|| keymap::keysym_to_key(keysym)),
+            key => key,
+        }
+    }
+
+    /// The name of the keysym produced by the physical key under the active layout without any
+    /// modifiers, like `a` or `F1`.
+    #[cfg(feature = "wayland-global-shortcuts")]
+    pub fn keysym_name(&self, physical_key: PhysicalKey) -> Option<String> {
+        let keycode = physicalkey_to_scancode(physical_key)? + 8;
+        // NOTE: The keymap is reference counted, thus cloning it is cheap.
+        let keysym = self.keymap.clone().first_keysym_by_level(self.layout, keycode);
+        if keysym == 0 {
+            return None;
+        }
+
+        // NOTE: The longest keysym name is well below the buffer size.
+        let mut name = [0u8; 64];
+        let len =
+            unsafe { (XKBH.xkb_keysym_get_name)(keysym, name.as_mut_ptr().cast(), name.len()) };
+        let name = name.get(..usize::try_from(len).ok()?)?;
+        Some(String::from_utf8_lossy(name).into_owned())
     }
 }
 
+fn keysym_to_utf8_raw(keysym: u32, scratch_buffer: &mut Vec<u8>) -> Option<SmolStr> {
+    scratch_buffer.clear();
+    scratch_buffer.reserve(8);
+    loop {
+        let bytes_written = unsafe {
+            (XKBH.xkb_keysym_to_utf8)(
+                keysym,
+                scratch_buffer.as_mut_ptr().cast(),
+                scratch_buffer.capacity(),
+            )
+        };
+        if bytes_written == 0 {
+            return None;
+        } else if bytes_written == -1 {
+            scratch_buffer.reserve(8);
+        } else {
+            unsafe { scratch_buffer.set_len(bytes_written.try_into().unwrap()) };
+            break;
+        }
+    }
+
+    // Remove the null-terminator
+    scratch_buffer.pop();
+    byte_slice_to_smol_str(scratch_buffer)
+}
+
 struct KeyEventResults<'a, 'b> {
     context: &'a mut KeyContext<'b>,
     keycode: u32,
     keysym: u32,
     compose: ComposeStatus,
+    /// The dead key produced by the keysym starting the compose sequence.
+    dead_key: Option<Key>,
 }
 
 impl<'a, 'b> KeyEventResults<'a, 'b> {
@@ -242,41 +402,66 @@ impl<'a, 'b> KeyEventResults<'a, 'b> {
         let keysym = context.state.get_one_sym_raw(keycode);
 
         let compose = if let Some(state) = context.compose_state1.as_mut().filter(|_| compose) {
-            if RESET_DEAD_KEYS.swap(false, Ordering::SeqCst) {
+            if context.reset_dead_keys.swap(false, Ordering::SeqCst) {
                 state.reset();
                 context.compose_state2.as_mut().unwrap().reset();
+                context.compose_preedit.clear();
             }
             state.feed(keysym)
         } else {
             ComposeStatus::None
         };
 
-        KeyEventResults { context, keycode, keysym, compose }
+        let mut results = KeyEventResults { context, keycode, keysym, compose, dead_key: None };
+        results.update_compose();
+        results
     }
 
-    pub fn key(&mut self) -> (Key, KeyLocation) {
-        let (key, location) = match self.keysym_to_key(self.keysym) {
-            Ok(known) => return known,
-            Err(undefined) => undefined,
+    /// Track the compose sequence to report it to the user.
+    fn update_compose(&mut self) {
+        let status = match self.compose {
+            ComposeStatus::Accepted(status) => status,
+            _ => return,
         };
 
-        if let ComposeStatus::Accepted(xkb_compose_status::XKB_COMPOSE_COMPOSING) = self.compose {
+        if status != xkb_compose_status::XKB_COMPOSE_COMPOSING {
+            self.context.compose_preedit.clear();
+            return;
+        }
+
+        if matches!(keymap::keysym_to_key(self.keysym), Key::Unidentified(_)) {
             let compose_state = self.context.compose_state2.as_mut().unwrap();
             // When pressing a dead key twice, the non-combining variant of that character will
             // be produced. Since this function only concerns itself with a single keypress, we
             // simulate this double press here by feeding the keysym to the compose state
             // twice.
-
             compose_state.feed(self.keysym);
             if matches!(compose_state.feed(self.keysym), ComposeStatus::Accepted(_)) {
                 // Extracting only a single `char` here *should* be fine, assuming that no
                 // dead key's non-combining variant ever occupies more than one `char`.
                 let text = compose_state.get_string(self.context.scratch_buffer);
-                let key = Key::Dead(text.and_then(|s| s.chars().next()));
-                (key, location)
-            } else {
-                (key, location)
+                self.dead_key = Some(Key::Dead(text.and_then(|s| s.chars().next())));
             }
+        }
+
+        let text = match &self.dead_key {
+            Some(Key::Dead(Some(ch))) => Some(SmolStr::new(ch.encode_utf8(&mut [0; 4]))),
+            _ if self.keysym == keysyms::Multi_key => Some(SmolStr::new_inline("·")),
+            _ => self.context.keysym_to_utf8_raw(self.keysym),
+        };
+        if let Some(text) = text {
+            self.context.compose_preedit.push(&text);
+        }
+    }
+
+    pub fn key(&mut self) -> (Key, KeyLocation) {
+        let (key, location) = match self.keysym_to_key(self.keysym) {
+            Ok(known) => return known,
+            Err(undefined) => undefined,
+        };
+
+        if let ComposeStatus::Accepted(xkb_compose_status::XKB_COMPOSE_COMPOSING) = self.compose {
+            (self.dead_key.clone().unwrap_or(key), location)
         } else {
             let key = self
                 .composed_text()
@@ -15,17 +15,26 @@ use crate::platform_impl::common::xkb::keymap::XkbKeymap;
 use crate::platform_impl::common::xkb::XKBXH;
 use crate::platform_impl::common::xkb::{make_string_with, XKBH};
 
+/// The names of the virtual modifiers, which aren't defined by `xkbcommon`.
+const MOD_NAME_LEVEL3: &[u8] = b"LevelThree\0";
+const MOD_NAME_LEVEL5: &[u8] = b"LevelFive\0";
+const MOD_NAME_HYPER: &[u8] = b"Hyper\0";
+const MOD_NAME_META: &[u8] = b"Meta\0";
+const MOD_NAME_SCROLL: &[u8] = b"ScrollLock\0";
+
 #[derive(Debug)]
 pub struct XkbState {
     state: NonNull<xkb_state>,
     modifiers: ModifiersState,
+    /// Whether the hyper and meta are mapped to the same modifiers as super and alt.
+    hyper_is_super: bool,
+    meta_is_alt: bool,
 }
 
 impl XkbState {
-    #[cfg(wayland_platform)]
-    pub fn new_wayland(keymap: &XkbKeymap) -> Option<Self> {
+    pub fn new(keymap: &XkbKeymap) -> Option<Self> {
         let state = NonNull::new(unsafe { (XKBH.xkb_state_new)(keymap.as_ptr()) })?;
-        Some(Self::new_inner(state))
+        Some(Self::new_inner(state, keymap))
     }
 
     #[cfg(x11_platform)]
@@ -34,16 +43,44 @@ impl XkbState {
             (XKBXH.xkb_x11_state_new_from_device)(keymap.as_ptr(), xcb, keymap._core_keyboard_id)
         };
         let state = NonNull::new(state)?;
-        Some(Self::new_inner(state))
+        Some(Self::new_inner(state, keymap))
     }
 
-    fn new_inner(state: NonNull<xkb_state>) -> Self {
+    fn new_inner(state: NonNull<xkb_state>, keymap: &XkbKeymap) -> Self {
         let modifiers = ModifiersState::default();
-        let mut this = Self { state, modifiers };
+        let (hyper_is_super, meta_is_alt) = Self::aliased_modifiers(keymap);
+        let mut this = Self { state, modifiers, hyper_is_super, meta_is_alt };
         this.reload_modifiers();
         this
     }
 
+    /// Check whether the hyper and meta are active with only the super and alt held
+    /// respectively, which is the case for the most of the layouts.
+    fn aliased_modifiers(keymap: &XkbKeymap) -> (bool, bool) {
+        let state = match NonNull::new(unsafe { (XKBH.xkb_state_new)(keymap.as_ptr()) }) {
+            Some(state) => state,
+            None => return (true, true),
+        };
+
+        let mods_indices = keymap.mods_indices();
+        let is_active_with = |index: Option<u32>, name: &[u8]| unsafe {
+            let mask = index.map_or(0, |index| 1 << index);
+            (XKBH.xkb_state_update_mask)(state.as_ptr(), mask, 0, 0, 0, 0, 0);
+            (XKBH.xkb_state_mod_name_is_active)(
+                state.as_ptr(),
+                name.as_ptr() as *const c_char,
+                xkb_state_component::XKB_STATE_MODS_EFFECTIVE,
+            ) > 0
+        };
+        let aliased = (
+            is_active_with(mods_indices.logo, MOD_NAME_HYPER),
+            is_active_with(mods_indices.alt, MOD_NAME_META),
+        );
+
+        unsafe { (XKBH.xkb_state_unref)(state.as_ptr()) };
+        aliased
+    }
+
     pub fn get_one_sym_raw(&mut self, keycode: xkb_keycode_t) -> xkb_keysym_t {
         unsafe { (XKBH.xkb_state_key_get_one_sym)(self.state.as_ptr(), keycode) }
     }
@@ -52,6 +89,16 @@ impl XkbState {
         unsafe { (XKBH.xkb_state_key_get_layout)(self.state.as_ptr(), key) }
     }
 
+    /// The index of the currently active layout.
+    pub fn active_layout(&self) -> xkb_layout_index_t {
+        unsafe {
+            (XKBH.xkb_state_serialize_layout)(
+                self.state.as_ptr(),
+                xkb_state_component::XKB_STATE_LAYOUT_EFFECTIVE,
+            )
+        }
+    }
+
     #[cfg(x11_platform)]
     pub fn depressed_modifiers(&mut self) -> xkb::xkb_mod_mask_t {
         unsafe {
@@ -128,18 +175,33 @@ impl XkbState {
         self.modifiers.ctrl = self.mod_name_is_active(xkb::XKB_MOD_NAME_CTRL);
         self.modifiers.alt = self.mod_name_is_active(xkb::XKB_MOD_NAME_ALT);
         self.modifiers.shift = self.mod_name_is_active(xkb::XKB_MOD_NAME_SHIFT);
-        self.modifiers.caps_lock = self.mod_name_is_active(xkb::XKB_MOD_NAME_CAPS);
         self.modifiers.logo = self.mod_name_is_active(xkb::XKB_MOD_NAME_LOGO);
-        self.modifiers.num_lock = self.mod_name_is_active(xkb::XKB_MOD_NAME_NUM);
+        self.modifiers.alt_graph = self.mod_name_is_active(MOD_NAME_LEVEL3);
+        self.modifiers.level5 = self.mod_name_is_active(MOD_NAME_LEVEL5);
+        self.modifiers.hyper = !self.hyper_is_super && self.mod_name_is_active(MOD_NAME_HYPER);
+        self.modifiers.meta = !self.meta_is_alt && self.mod_name_is_active(MOD_NAME_META);
+
+        self.modifiers.caps_lock = self.mod_name_is_locked(xkb::XKB_MOD_NAME_CAPS);
+        self.modifiers.num_lock = self.mod_name_is_locked(xkb::XKB_MOD_NAME_NUM);
+        self.modifiers.scroll_lock = self.mod_name_is_locked(MOD_NAME_SCROLL);
     }
 
     /// Check if the modifier is active within xkb.
     fn mod_name_is_active(&mut self, name: &[u8]) -> bool {
+        self.mod_name_is_active_in(name, xkb_state_component::XKB_STATE_MODS_EFFECTIVE)
+    }
+
+    /// Check if the modifier is locked within xkb.
+    fn mod_name_is_locked(&mut self, name: &[u8]) -> bool {
+        self.mod_name_is_active_in(name, xkb_state_component::XKB_STATE_MODS_LOCKED)
+    }
+
+    fn mod_name_is_active_in(&mut self, name: &[u8], component: xkb_state_component) -> bool {
         unsafe {
             (XKBH.xkb_state_mod_name_is_active)(
                 self.state.as_ptr(),
                 name.as_ptr() as *const c_char,
-                xkb_state_component::XKB_STATE_MODS_EFFECTIVE,
+                component,
             ) > 0
         }
     }
@@ -175,6 +237,16 @@ pub struct ModifiersState {
     pub logo: bool,
     /// The "Num lock" key
     pub num_lock: bool,
+    /// The "Scroll lock" key
+    pub scroll_lock: bool,
+    /// The "AltGr" key
+    pub alt_graph: bool,
+    /// The level 5 shift
+    pub level5: bool,
+    /// The "hyper" key
+    pub hyper: bool,
+    /// The "meta" key
+    pub meta: bool,
 }
 
 impl From<ModifiersState> for crate::keyboard::ModifiersState {
@@ -184,6 +256,24 @@ impl From<ModifiersState> for crate::keyboard::ModifiersState {
         to_mods.set(crate::keyboard::ModifiersState::CONTROL, mods.ctrl);
         to_mods.set(crate::keyboard::ModifiersState::ALT, mods.alt);
         to_mods.set(crate::keyboard::ModifiersState::SUPER, mods.logo);
+        to_mods.set(crate::keyboard::ModifiersState::ALT_GRAPH, mods.alt_graph);
+        to_mods.set(crate::keyboard::ModifiersState::LEVEL5, mods.level5);
+        to_mods.set(crate::keyboard::ModifiersState::HYPER, mods.hyper);
+        to_mods.set(crate::keyboard::ModifiersState::META, mods.meta);
         to_mods
     }
 }
+
+impl From<ModifiersState> for crate::event::Modifiers {
+    fn from(mods: ModifiersState) -> crate::event::Modifiers {
+        let mut lock_state = crate::keyboard::LockState::empty();
+        lock_state.set(crate::keyboard::LockState::CAPS_LOCK, mods.caps_lock);
+        lock_state.set(crate::keyboard::LockState::NUM_LOCK, mods.num_lock);
+        lock_state.set(crate::keyboard::LockState::SCROLL_LOCK, mods.scroll_lock);
+
+        let mut modifiers =
+            crate::event::Modifiers::from(crate::keyboard::ModifiersState::from(mods));
+        modifiers.lock_state = lock_state;
+        modifiers
+    }
+}
@@ -4,12 +4,19 @@
 compile_error!("Please select a feature to build for unix: `x11`, `wayland`");
 
 use std::collections::VecDeque;
+use std::future::Future;
 use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
 use std::sync::Arc;
-use std::time::Duration;
-use std::{env, fmt};
+use std::time::{Duration, Instant};
+use std::{env, fmt, io};
 #[cfg(x11_platform)]
-use std::{ffi::CStr, mem::MaybeUninit, os::raw::*, sync::Mutex};
+use std::{
+    ffi::CStr,
+    mem::MaybeUninit,
+    os::raw::*,
+    sync::atomic::{AtomicBool, Ordering},
+    sync::{Mutex, Weak},
+};
 
 use smol_str::SmolStr;
 
@@ -20,10 +27,13 @@ use crate::application::ApplicationHandler;
 pub(crate) use crate::cursor::OnlyCursorImageSource as PlatformCustomCursorSource;
 use crate::dpi::{PhysicalPosition, PhysicalSize, Position, Size};
 use crate::error::{EventLoopError, ExternalError, NotSupportedError, OsError as RootOsError};
-use crate::event_loop::{AsyncRequestSerial, ControlFlow, DeviceEvents};
+use crate::event::DeviceInfo;
+use crate::event_loop::{
+    AsyncRequestSerial, ControlFlow, DeviceEvents, FdId, GlobalShortcutId, IdleTimeoutId, TimerId,
+};
 use crate::icon::Icon;
 pub(crate) use crate::icon::RgbaIcon as PlatformIcon;
-use crate::keyboard::Key;
+use crate::keyboard::{Key, KeyCode, ModifiersState};
 use crate::platform::pump_events::PumpStatus;
 #[cfg(x11_platform)]
 use crate::platform::x11::{WindowType as XWindowType, XlibErrorHook};
@@ -32,7 +42,8 @@ pub(crate) use crate::platform_impl::Fullscreen;
 use crate::utils::Lazy;
 use crate::window::{
     ActivationToken, Cursor, CursorGrabMode, CustomCursor, CustomCursorSource, ImePurpose,
-    ResizeDirection, Theme, UserAttentionType, WindowAttributes, WindowButtons, WindowLevel,
+    KeyRepeat, KeyRepeatRate, ResizeDirection, Theme, UserAttentionType, WindowAttributes,
+    WindowBounds, WindowButtons, WindowLevel, WindowState, WmCapabilities,
 };
 
 pub(crate) mod common;
@@ -49,10 +60,13 @@ pub(crate) enum Backend {
     Wayland,
 }
 
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub(crate) struct PlatformSpecificEventLoopAttributes {
     pub(crate) forced_backend: Option<Backend>,
     pub(crate) any_thread: bool,
+    pub(crate) gamepads: bool,
+    /// The SDL gamepad mappings, one per line.
+    pub(crate) gamepad_mappings: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -73,6 +87,8 @@ pub struct PlatformSpecificWindowAttributes {
     pub activation_token: Option<ActivationToken>,
     #[cfg(x11_platform)]
     pub x11: X11WindowAttributes,
+    #[cfg(wayland_platform)]
+    pub wayland: WaylandWindowAttributes,
 }
 
 #[derive(Clone, Debug)]
@@ -88,6 +104,17 @@ pub struct X11WindowAttributes {
     pub embed_window: Option<x11rb::protocol::xproto::Window>,
 }
 
+#[derive(Clone, Debug, Default)]
+#[cfg(wayland_platform)]
+pub struct WaylandWindowAttributes {
+    /// Whether the child window commits are synchronized with its parent.
+    pub subsurface_sync: bool,
+    /// The preferred way of decorating the window.
+    pub decoration_preference: crate::platform::wayland::DecorationPreference,
+    /// The builder of the client side decorations frame provided by the user.
+    pub csd_frame: Option<wayland::CsdFrameBuilder>,
+}
+
 #[cfg_attr(not(x11_platform), allow(clippy::derivable_impls))]
 impl Default for PlatformSpecificWindowAttributes {
     fn default() -> Self {
@@ -103,6 +130,8 @@ impl Default for PlatformSpecificWindowAttributes {
                 x11_window_types: vec![XWindowType::Normal],
                 embed_window: None,
             },
+            #[cfg(wayland_platform)]
+            wayland: Default::default(),
         }
     }
 }
@@ -111,6 +140,37 @@ impl Default for PlatformSpecificWindowAttributes {
 pub(crate) static X11_BACKEND: Lazy<Mutex<Result<Arc<XConnection>, XNotSupported>>> =
     Lazy::new(|| Mutex::new(XConnection::new(Some(x_error_callback)).map(Arc::new)));
 
+/// Whether the [`X11_BACKEND`] connection is used by an event loop already.
+#[cfg(x11_platform)]
+static X11_BACKEND_IN_USE: AtomicBool = AtomicBool::new(false);
+
+/// Let the next event loop use the [`X11_BACKEND`] connection once the one using it is dropped.
+#[cfg(x11_platform)]
+pub(crate) fn release_x11_backend(xconn: &Arc<XConnection>) {
+    let backend = X11_BACKEND.lock().unwrap();
+    if backend.as_ref().is_ok_and(|backend| Arc::ptr_eq(backend, xconn)) {
+        X11_BACKEND_IN_USE.store(false, Ordering::Relaxed);
+    }
+}
+
+/// The connections opened for the event loops besides the first one.
+#[cfg(x11_platform)]
+static X11_CONNECTIONS: Mutex<Vec<Weak<XConnection>>> = Mutex::new(Vec::new());
+
+/// Find the connection the Xlib `display` belongs to.
+#[cfg(x11_platform)]
+fn x11_connection(display: *mut x11::ffi::Display) -> Option<Arc<XConnection>> {
+    if let Ok(xconn) = X11_BACKEND.lock().unwrap().as_ref() {
+        if xconn.display == display {
+            return Some(xconn.clone());
+        }
+    }
+
+    let mut connections = X11_CONNECTIONS.lock().unwrap();
+    connections.retain(|xconn| xconn.strong_count() != 0);
+    connections.iter().filter_map(Weak::upgrade).find(|xconn| xconn.display == display)
+}
+
 #[derive(Debug, Clone)]
 pub enum OsError {
     Misc(&'static str),
@@ -132,6 +192,8 @@ impl fmt::Display for OsError {
     }
 }
 
+// NOTE: The window is created once and never moved around, thus the size difference is fine.
+#[allow(clippy::large_enum_variant)]
 pub(crate) enum Window {
     #[cfg(x11_platform)]
     X(x11::Window),
@@ -166,6 +228,8 @@ pub enum DeviceId {
     X(x11::DeviceId),
     #[cfg(wayland_platform)]
     Wayland(wayland::DeviceId),
+    #[cfg(target_os = "linux")]
+    Gamepad(common::gamepad::GamepadId),
 }
 
 impl DeviceId {
@@ -326,6 +390,26 @@ impl Window {
         x11_or_wayland!(match self; Window(w) => w.set_blur(blur));
     }
 
+    #[inline]
+    pub fn set_idle_inhibit(&self, inhibit: bool) {
+        x11_or_wayland!(match self; Window(w) => w.set_idle_inhibit(inhibit))
+    }
+
+    #[inline]
+    pub fn set_keyboard_shortcuts_inhibited(&self, inhibited: bool) {
+        x11_or_wayland!(match self; Window(w) => w.set_keyboard_shortcuts_inhibited(inhibited))
+    }
+
+    #[inline]
+    pub fn set_key_repeat(&self, repeat: KeyRepeat) {
+        x11_or_wayland!(match self; Window(w) => w.set_key_repeat(repeat))
+    }
+
+    #[inline]
+    pub fn key_repeat_rate(&self) -> Option<KeyRepeatRate> {
+        x11_or_wayland!(match self; Window(w) => w.key_repeat_rate())
+    }
+
     #[inline]
     pub fn set_visible(&self, visible: bool) {
         x11_or_wayland!(match self; Window(w) => w.set_visible(visible))
@@ -371,6 +455,50 @@ impl Window {
         x11_or_wayland!(match self; Window(w) => w.request_activation_token())
     }
 
+    #[cfg(wayland_platform)]
+    #[inline]
+    pub fn set_subsurface_sync(&self, sync: bool) {
+        match self {
+            Window::Wayland(window) => window.set_subsurface_sync(sync),
+            #[cfg(x11_platform)]
+            Window::X(_) => (),
+        }
+    }
+
+    #[cfg(wayland_platform)]
+    #[inline]
+    pub fn place_subsurface_above(&self, sibling: &Window) {
+        match (self, sibling) {
+            (Window::Wayland(window), Window::Wayland(sibling)) => {
+                window.place_subsurface_above(sibling)
+            },
+            #[cfg(x11_platform)]
+            _ => (),
+        }
+    }
+
+    #[cfg(wayland_platform)]
+    #[inline]
+    pub fn place_subsurface_below(&self, sibling: &Window) {
+        match (self, sibling) {
+            (Window::Wayland(window), Window::Wayland(sibling)) => {
+                window.place_subsurface_below(sibling)
+            },
+            #[cfg(x11_platform)]
+            _ => (),
+        }
+    }
+
+    #[cfg(wayland_platform)]
+    #[inline]
+    pub fn decoration_mode(&self) -> Option<crate::window::DecorationMode> {
+        match self {
+            Window::Wayland(window) => window.decoration_mode(),
+            #[cfg(x11_platform)]
+            _ => None,
+        }
+    }
+
     #[inline]
     pub fn set_min_inner_size(&self, dimensions: Option<Size>) {
         x11_or_wayland!(match self; Window(w) => w.set_min_inner_size(dimensions))
@@ -466,6 +594,26 @@ impl Window {
         x11_or_wayland!(match self; Window(w) => w.is_maximized())
     }
 
+    #[inline]
+    pub fn normal_bounds(&self) -> Option<WindowBounds> {
+        x11_or_wayland!(match self; Window(w) => w.normal_bounds())
+    }
+
+    #[inline]
+    pub fn set_normal_bounds(&self, position: Option<Position>, inner_size: Size) {
+        x11_or_wayland!(match self; Window(w) => w.set_normal_bounds(position, inner_size))
+    }
+
+    #[inline]
+    pub fn state(&self) -> WindowState {
+        x11_or_wayland!(match self; Window(w) => w.state())
+    }
+
+    #[inline]
+    pub fn wm_capabilities(&self) -> Option<WmCapabilities> {
+        x11_or_wayland!(match self; Window(w) => w.wm_capabilities())
+    }
+
     #[inline]
     pub fn set_minimized(&self, minimized: bool) {
         x11_or_wayland!(match self; Window(w) => w.set_minimized(minimized))
@@ -513,7 +661,7 @@ impl Window {
 
     #[inline]
     pub fn reset_dead_keys(&self) {
-        common::xkb::reset_dead_keys()
+        x11_or_wayland!(match self; Window(w) => w.reset_dead_keys())
     }
 
     #[inline]
@@ -648,8 +796,7 @@ unsafe extern "C" fn x_error_callback(
     display: *mut x11::ffi::Display,
     event: *mut x11::ffi::XErrorEvent,
 ) -> c_int {
-    let xconn_lock = X11_BACKEND.lock().unwrap();
-    if let Ok(ref xconn) = *xconn_lock {
+    if let Some(xconn) = x11_connection(display) {
         // Call all the hooks.
         let mut error_handled = false;
         for hook in unsafe { XLIB_ERROR_HOOKS.lock() }.unwrap().iter() {
@@ -754,28 +901,46 @@ impl EventLoop {
             },
         };
 
+        let gamepad_mappings = attributes.gamepads.then_some(attributes.gamepad_mappings.as_str());
+
         // Create the display based on the backend.
         match backend {
             #[cfg(wayland_platform)]
-            Backend::Wayland => EventLoop::new_wayland_any_thread().map_err(Into::into),
+            Backend::Wayland => {
+                EventLoop::new_wayland_any_thread(gamepad_mappings).map_err(Into::into)
+            },
             #[cfg(x11_platform)]
-            Backend::X => EventLoop::new_x11_any_thread().map_err(Into::into),
+            Backend::X => EventLoop::new_x11_any_thread(gamepad_mappings).map_err(Into::into),
         }
     }
 
     #[cfg(wayland_platform)]
-    fn new_wayland_any_thread() -> Result<EventLoop, EventLoopError> {
-        wayland::EventLoop::new().map(|evlp| EventLoop::Wayland(Box::new(evlp)))
+    fn new_wayland_any_thread(gamepad_mappings: Option<&str>) -> Result<EventLoop, EventLoopError> {
+        wayland::EventLoop::new(gamepad_mappings).map(|evlp| EventLoop::Wayland(Box::new(evlp)))
     }
 
     #[cfg(x11_platform)]
-    fn new_x11_any_thread() -> Result<EventLoop, EventLoopError> {
-        let xconn = match X11_BACKEND.lock().unwrap().as_ref() {
-            Ok(xconn) => xconn.clone(),
-            Err(_) => return Err(EventLoopError::NotSupported(NotSupportedError::new())),
+    fn new_x11_any_thread(gamepad_mappings: Option<&str>) -> Result<EventLoop, EventLoopError> {
+        // NOTE: The event loops can't share the connection, since they'd steal each other's
+        // events, thus only the first one uses the shared connection.
+        let xconn = if !X11_BACKEND_IN_USE.swap(true, Ordering::Relaxed) {
+            match X11_BACKEND.lock().unwrap().as_ref() {
+                Ok(xconn) => xconn.clone(),
+                Err(_) => {
+                    X11_BACKEND_IN_USE.store(false, Ordering::Relaxed);
+                    return Err(EventLoopError::NotSupported(NotSupportedError::new()));
+                },
+            }
+        } else {
+            let xconn = match XConnection::new(Some(x_error_callback)) {
+                Ok(xconn) => Arc::new(xconn),
+                Err(_) => return Err(EventLoopError::NotSupported(NotSupportedError::new())),
+            };
+            X11_CONNECTIONS.lock().unwrap().push(Arc::downgrade(&xconn));
+            xconn
         };
 
-        Ok(EventLoop::X(x11::EventLoop::new(xconn)))
+        Ok(EventLoop::X(x11::EventLoop::new(xconn, gamepad_mappings)))
     }
 
     #[inline]
@@ -842,6 +1007,89 @@ impl ActiveEventLoop {
         x11_or_wayland!(match self; ActiveEventLoop(evlp) => evlp.create_proxy(); as EventLoopProxy)
     }
 
+    #[inline]
+    pub fn register_fd<F: AsFd + 'static>(
+        &self,
+        fd: F,
+        readable: bool,
+        writable: bool,
+    ) -> io::Result<FdId> {
+        x11_or_wayland!(match self; ActiveEventLoop(evlp) => {
+            evlp.user_sources().register_fd(fd, readable, writable)
+        })
+    }
+
+    #[inline]
+    pub fn unregister_fd(&self, fd_id: FdId) {
+        x11_or_wayland!(match self; ActiveEventLoop(evlp) => evlp.user_sources().unregister_fd(fd_id))
+    }
+
+    #[inline]
+    pub fn set_timer(&self, deadline: Instant, interval: Option<Duration>) -> TimerId {
+        x11_or_wayland!(match self; ActiveEventLoop(evlp) => {
+            evlp.user_sources().set_timer(deadline, interval)
+        })
+    }
+
+    #[inline]
+    pub fn cancel_timer(&self, timer_id: TimerId) {
+        x11_or_wayland!(match self; ActiveEventLoop(evlp) => evlp.user_sources().cancel_timer(timer_id))
+    }
+
+    #[inline]
+    pub fn add_idle_timeout(&self, timeout: Duration) -> Result<IdleTimeoutId, NotSupportedError> {
+        x11_or_wayland!(match self; ActiveEventLoop(evlp) => evlp.add_idle_timeout(timeout))
+    }
+
+    #[inline]
+    pub fn remove_idle_timeout(&self, timeout_id: IdleTimeoutId) {
+        x11_or_wayland!(match self; ActiveEventLoop(evlp) => evlp.remove_idle_timeout(timeout_id))
+    }
+
+    #[inline]
+    pub fn keymap(&self) -> Option<common::xkb::KeymapSnapshot> {
+        x11_or_wayland!(match self; ActiveEventLoop(evlp) => evlp.keymap())
+    }
+
+    #[inline]
+    pub fn register_global_shortcut(
+        &self,
+        modifiers: ModifiersState,
+        key: KeyCode,
+    ) -> Result<GlobalShortcutId, ExternalError> {
+        x11_or_wayland!(match self; ActiveEventLoop(evlp) => {
+            evlp.register_global_shortcut(modifiers, key)
+        })
+    }
+
+    #[inline]
+    pub fn unregister_global_shortcut(&self, shortcut_id: GlobalShortcutId) {
+        x11_or_wayland!(match self; ActiveEventLoop(evlp) => {
+            evlp.unregister_global_shortcut(shortcut_id)
+        })
+    }
+
+    #[inline]
+    pub fn spawn_local<F: Future<Output = ()> + 'static>(&self, future: F) {
+        x11_or_wayland!(match self; ActiveEventLoop(evlp) => evlp.executor().spawn(future))
+    }
+
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let has_work = self.exiting()
+            || x11_or_wayland!(match self; ActiveEventLoop(evlp) => evlp.prepare_to_wait());
+        if has_work {
+            return Some(Instant::now());
+        }
+
+        let control_flow = match self.control_flow() {
+            ControlFlow::Wait => None,
+            ControlFlow::Poll => Some(Instant::now()),
+            ControlFlow::WaitUntil(deadline) => Some(deadline),
+        };
+        let timers = x11_or_wayland!(match self; ActiveEventLoop(evlp) => evlp.user_sources().next_deadline());
+        control_flow.into_iter().chain(timers).min()
+    }
+
     #[inline]
     pub fn is_wayland(&self) -> bool {
         match *self {
@@ -877,6 +1125,28 @@ impl ActiveEventLoop {
         )
     }
 
+    #[inline]
+    pub fn devices(&self) -> Vec<DeviceInfo> {
+        x11_or_wayland!(match self; Self(evlp) => evlp.devices())
+    }
+
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+    pub fn rumble_gamepad(
+        &self,
+        device_id: crate::event::DeviceId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<(), ExternalError> {
+        match device_id.0 {
+            #[cfg(target_os = "linux")]
+            DeviceId::Gamepad(gamepad_id) => x11_or_wayland!(match self; Self(evlp) => {
+                evlp.rumble_gamepad(gamepad_id, strong, weak, duration)
+            }),
+            _ => Err(ExternalError::NotSupported(NotSupportedError::new())),
+        }
+    }
+
     #[inline]
     pub fn listen_device_events(&self, allowed: DeviceEvents) {
         x11_or_wayland!(match self; Self(evlp) => evlp.listen_device_events(allowed))
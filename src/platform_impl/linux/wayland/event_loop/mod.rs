@@ -1,5 +1,7 @@
 //! The event-loop routines.
 
+#[cfg(feature = "wayland-global-shortcuts")]
+use std::cell::OnceCell;
 use std::cell::{Cell, RefCell};
 use std::io::Result as IOResult;
 use std::marker::PhantomData;
@@ -16,13 +18,28 @@ use sctk::reexports::client::{globals, Connection, QueueHandle};
 use crate::application::ApplicationHandler;
 use crate::cursor::OnlyCursorImage;
 use crate::dpi::LogicalSize;
-use crate::error::{EventLoopError, OsError as RootOsError};
-use crate::event::{Event, InnerSizeWriter, StartCause, WindowEvent};
-use crate::event_loop::{ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents};
+use crate::error::{EventLoopError, ExternalError, NotSupportedError, OsError as RootOsError};
+use crate::event::{
+    DeviceId as RootDeviceId, DeviceInfo, DeviceKind, Event, InnerSizeWriter, StartCause,
+    WindowEvent,
+};
+use crate::event_loop::{
+    ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents, GlobalShortcutId,
+    IdleTimeoutId,
+};
+#[cfg(feature = "wayland-global-shortcuts")]
+use crate::keyboard::PhysicalKey;
+use crate::keyboard::{KeyCode, ModifiersState};
 use crate::platform::pump_events::PumpStatus;
+use crate::platform_impl::common::executor::LocalExecutor;
+#[cfg(target_os = "linux")]
+use crate::platform_impl::common::gamepad::{GamepadId, Gamepads};
+use crate::platform_impl::common::user_sources::UserSources;
+use crate::platform_impl::common::xkb::KeymapSnapshot;
 use crate::platform_impl::platform::min_timeout;
 use crate::platform_impl::{
-    ActiveEventLoop as PlatformActiveEventLoop, OsError, PlatformCustomCursor,
+    ActiveEventLoop as PlatformActiveEventLoop, DeviceId as PlatformDeviceId, OsError,
+    PlatformCustomCursor,
 };
 use crate::window::{CustomCursor as RootCustomCursor, CustomCursorSource};
 
@@ -32,6 +49,8 @@ pub mod sink;
 pub use proxy::EventLoopProxy;
 use sink::EventSink;
 
+#[cfg(feature = "wayland-global-shortcuts")]
+use super::global_shortcuts::{self, GlobalShortcutsPortal};
 use super::state::{WindowCompositorUpdate, WinitState};
 use super::window::state::FrameCallbackState;
 use super::{logical_to_physical_rounded, DeviceId, WaylandError, WindowId};
@@ -63,7 +82,7 @@ pub struct EventLoop {
 }
 
 impl EventLoop {
-    pub fn new() -> Result<EventLoop, EventLoopError> {
+    pub fn new(gamepad_mappings: Option<&str>) -> Result<EventLoop, EventLoopError> {
         macro_rules! map_err {
             ($e:expr, $err:expr) => {
                 $e.map_err(|error| os_error!($err(error).into()))
@@ -141,6 +160,12 @@ impl EventLoop {
             control_flow: Cell::new(ControlFlow::default()),
             exit: Cell::new(None),
             state: RefCell::new(winit_state),
+            user_sources: UserSources::new(event_loop.handle()),
+            #[cfg(target_os = "linux")]
+            gamepads: gamepad_mappings.map(|mappings| Gamepads::new(event_loop.handle(), mappings)),
+            executor: LocalExecutor::new(&event_loop.handle()),
+            #[cfg(feature = "wayland-global-shortcuts")]
+            global_shortcuts: OnceCell::new(),
         };
 
         let event_loop = Self {
@@ -278,7 +303,10 @@ impl EventLoop {
             };
 
             // Reduce spurious wake-ups.
-            let dispatched_events = self.with_state(|state| state.dispatched_events);
+            let dispatched_events = self.with_state(|state| state.dispatched_events)
+                || self.active_event_loop().user_sources().has_pending()
+                || self.active_event_loop().has_pending_gamepad_events()
+                || self.active_event_loop().executor().has_pending();
             if matches!(cause, StartCause::WaitCancelled { .. }) && !dispatched_events {
                 continue;
             }
@@ -408,13 +436,17 @@ impl EventLoop {
         }
 
         // Handle non-synthetic events.
-        self.with_state(|state| {
+        let modal_blocker = self.with_state(|state| {
             buffer_sink.append(&mut state.events_sink);
+            state.modal_blocker.clone()
         });
         for event in buffer_sink.drain() {
             match event {
                 Event::WindowEvent { window_id, event } => {
-                    app.window_event(&self.window_target, window_id, event)
+                    // Drop the user input to the windows blocked by modal dialogs.
+                    if !modal_blocker.should_filter(window_id.0, &event) {
+                        app.window_event(&self.window_target, window_id, event)
+                    }
                 },
                 Event::DeviceEvent { device_id, event } => {
                     app.device_event(&self.window_target, device_id, event)
@@ -423,6 +455,29 @@ impl EventLoop {
             }
         }
 
+        // Poll the futures spawned by the user.
+        //
+        // NOTE: The futures could report the global shortcuts, thus poll them before delivering
+        // the user sources, so the events aren't left until the next wake-up.
+        self.active_event_loop().executor().run();
+
+        // Dispatch the user file descriptors and timers.
+        let idle_events = self.with_state(|state| mem::take(&mut state.idle_events));
+        for (timeout_id, idle) in idle_events {
+            self.active_event_loop().user_sources().push_idle(timeout_id, idle);
+        }
+        while let Some(event) = self.active_event_loop().user_sources().pop() {
+            event.deliver(app, &self.window_target);
+        }
+
+        // Deliver the gamepad events.
+        #[cfg(target_os = "linux")]
+        if let Some(gamepads) = &self.active_event_loop().gamepads {
+            while let Some((device_id, event)) = gamepads.pop() {
+                app.device_event(&self.window_target, device_id, event);
+            }
+        }
+
         // Collect the window ids
         self.with_state(|state| {
             window_ids.extend(state.window_requests.get_mut().keys());
@@ -525,6 +580,14 @@ impl EventLoop {
         callback(state)
     }
 
+    fn active_event_loop(&self) -> &ActiveEventLoop {
+        match &self.window_target.p {
+            PlatformActiveEventLoop::Wayland(window_target) => window_target,
+            #[cfg(x11_platform)]
+            _ => unreachable!(),
+        }
+    }
+
     fn loop_dispatch<D: Into<Option<std::time::Duration>>>(&mut self, timeout: D) -> IOResult<()> {
         let state = match &mut self.window_target.p {
             PlatformActiveEventLoop::Wayland(window_target) => window_target.state.get_mut(),
@@ -606,6 +669,20 @@ pub struct ActiveEventLoop {
 
     /// Connection to the wayland server.
     pub connection: Connection,
+
+    /// The file descriptors and timers registered by the user.
+    user_sources: UserSources<WinitState>,
+
+    /// The gamepads, when enabled with `EventLoopBuilderExtUnix::with_gamepads`.
+    #[cfg(target_os = "linux")]
+    gamepads: Option<Gamepads<WinitState>>,
+
+    /// The executor running the futures spawned by the user.
+    executor: LocalExecutor,
+
+    /// The client of the global shortcuts portal, connected on the first registration.
+    #[cfg(feature = "wayland-global-shortcuts")]
+    global_shortcuts: OnceCell<GlobalShortcutsPortal>,
 }
 
 impl ActiveEventLoop {
@@ -613,6 +690,161 @@ impl ActiveEventLoop {
         self.event_loop_proxy.clone()
     }
 
+    /// The file descriptors and timers registered by the user.
+    #[inline]
+    pub fn user_sources(&self) -> &UserSources<WinitState> {
+        &self.user_sources
+    }
+
+    /// The executor running the futures spawned by the user.
+    #[inline]
+    pub fn executor(&self) -> &LocalExecutor {
+        &self.executor
+    }
+
+    pub(crate) fn add_idle_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<IdleTimeoutId, NotSupportedError> {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        let notifier = state.idle_notifier.as_ref().ok_or_else(NotSupportedError::new)?;
+        // NOTE: The idle state is tracked for the seat, and there's usually only one of them.
+        let seat = state.seat_state.seats().next().ok_or_else(NotSupportedError::new)?;
+
+        let timeout = timeout.as_millis().min(u32::MAX as u128) as u32;
+        let timeout_id = self.user_sources.add_idle_timeout();
+        let notification = notifier.notification(timeout, &seat, &self.queue_handle, timeout_id);
+        state.idle_notifications.insert(timeout_id, notification);
+        Ok(timeout_id)
+    }
+
+    pub(crate) fn remove_idle_timeout(&self, timeout_id: IdleTimeoutId) {
+        let mut state = self.state.borrow_mut();
+        if let Some(notification) = state.idle_notifications.remove(&timeout_id) {
+            notification.destroy();
+        }
+        state.idle_events.retain(|(id, _)| *id != timeout_id);
+        self.user_sources.remove_idle_timeout(timeout_id);
+    }
+
+    pub(crate) fn keymap(&self) -> Option<KeymapSnapshot> {
+        self.state.borrow().seats.values().find_map(|seat| seat.keymap())
+    }
+
+    pub(crate) fn devices(&self) -> Vec<DeviceInfo> {
+        let state = self.state.borrow();
+        let id = RootDeviceId(PlatformDeviceId::Wayland(DeviceId));
+
+        // The seats only advertise their capabilities, so describe each of them as a device.
+        let mut devices = Vec::new();
+        for info in state.seat_state.seats().filter_map(|seat| state.seat_state.info(&seat)) {
+            let capabilities = [
+                (info.has_keyboard, DeviceKind::Keyboard),
+                (info.has_pointer, DeviceKind::Pointer),
+                (info.has_touch, DeviceKind::Touchscreen),
+            ];
+            for (_, kind) in capabilities.into_iter().filter(|(has, _)| *has) {
+                devices.push(DeviceInfo {
+                    id,
+                    name: info.name.clone().unwrap_or_default(),
+                    kind,
+                    vendor_id: None,
+                    product_id: None,
+                    axes: Vec::new(),
+                });
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        if let Some(gamepads) = &self.gamepads {
+            devices.extend(gamepads.devices());
+        }
+
+        devices
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn rumble_gamepad(
+        &self,
+        gamepad_id: GamepadId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<(), ExternalError> {
+        match &self.gamepads {
+            Some(gamepads) => gamepads.rumble(gamepad_id, strong, weak, duration),
+            None => Err(ExternalError::NotSupported(NotSupportedError::new())),
+        }
+    }
+
+    fn has_pending_gamepad_events(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        return self.gamepads.as_ref().is_some_and(Gamepads::has_pending);
+        #[cfg(not(target_os = "linux"))]
+        false
+    }
+
+    #[cfg(feature = "wayland-global-shortcuts")]
+    pub(crate) fn register_global_shortcut(
+        &self,
+        modifiers: ModifiersState,
+        key: KeyCode,
+    ) -> Result<GlobalShortcutId, ExternalError> {
+        let portal = self.global_shortcuts.get_or_init(|| {
+            GlobalShortcutsPortal::new(&self.executor, self.user_sources.global_shortcut_sink())
+        });
+        if portal.is_unavailable() {
+            return Err(ExternalError::NotSupported(NotSupportedError::new()));
+        }
+
+        // NOTE: The trigger is only the hint for the portal, thus it's fine to omit it before the
+        // keymap is known.
+        let trigger = self
+            .keymap()
+            .and_then(|keymap| keymap.keysym_name(PhysicalKey::Code(key)))
+            .map(|name| global_shortcuts::trigger(modifiers, &name));
+
+        let shortcut_id = self.user_sources.add_global_shortcut();
+        portal.bind(&self.executor, shortcut_id, trigger);
+        Ok(shortcut_id)
+    }
+
+    #[cfg(not(feature = "wayland-global-shortcuts"))]
+    pub(crate) fn register_global_shortcut(
+        &self,
+        _modifiers: ModifiersState,
+        _key: KeyCode,
+    ) -> Result<GlobalShortcutId, ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[cfg(feature = "wayland-global-shortcuts")]
+    pub(crate) fn unregister_global_shortcut(&self, shortcut_id: GlobalShortcutId) {
+        if let Some(portal) = self.global_shortcuts.get() {
+            portal.unbind(&self.executor, shortcut_id);
+        }
+
+        self.user_sources.remove_global_shortcut(shortcut_id);
+    }
+
+    #[cfg(not(feature = "wayland-global-shortcuts"))]
+    pub(crate) fn unregister_global_shortcut(&self, _shortcut_id: GlobalShortcutId) {}
+
+    /// Flush the pending requests before going to sleep and check whether there's work which
+    /// won't make the connection readable, like the events already read into the queue.
+    pub fn prepare_to_wait(&self) -> bool {
+        if let Err(error) = self.connection.flush() {
+            tracing::error!("Failed to flush the Wayland connection: {error}");
+        }
+
+        // NOTE: The guard cancels the read once dropped.
+        self.connection.prepare_read().is_none()
+            || self.user_sources.has_pending()
+            || self.has_pending_gamepad_events()
+            || self.executor.has_pending()
+    }
+
     pub(crate) fn set_control_flow(&self, control_flow: ControlFlow) {
         self.control_flow.set(control_flow)
     }
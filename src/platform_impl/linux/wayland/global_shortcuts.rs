@@ -0,0 +1,330 @@
+//! The global shortcuts bound through the `org.freedesktop.portal.GlobalShortcuts` portal.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use tracing::warn;
+use zbus::export::futures_util::future::{FutureExt, LocalBoxFuture, Shared};
+use zbus::export::futures_util::StreamExt;
+use zbus::export::serde::Serialize;
+use zbus::zvariant::{DynamicType, OwnedObjectPath, OwnedValue, Value};
+use zbus::{Connection, MatchRule, MessageStream, MessageType};
+
+use crate::event::ElementState;
+use crate::event_loop::GlobalShortcutId;
+use crate::keyboard::ModifiersState;
+use crate::platform_impl::common::executor::LocalExecutor;
+use crate::platform_impl::common::user_sources::GlobalShortcutSink;
+
+const PORTAL_DESTINATION: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const GLOBAL_SHORTCUTS_INTERFACE: &str = "org.freedesktop.portal.GlobalShortcuts";
+const REQUEST_INTERFACE: &str = "org.freedesktop.portal.Request";
+const SESSION_INTERFACE: &str = "org.freedesktop.portal.Session";
+
+/// The counter making the request and the session tokens unique within the process.
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(0);
+
+/// The connection to the session bus, resolving to `None` when the portal isn't available.
+type PortalConnection = Shared<LocalBoxFuture<'static, Option<Connection>>>;
+
+/// The sessions of the shortcuts, which paths are known once the connection is established.
+type Sessions = Rc<RefCell<HashMap<GlobalShortcutId, Option<OwnedObjectPath>>>>;
+
+/// The client of the global shortcuts portal on the session bus.
+///
+/// Each shortcut is bound in its own session, which is closed once the shortcut is unregistered.
+/// The portal requests run on the event loop executor, and the activations are reported to the
+/// user sources, like the grabbed keys on X11.
+pub struct GlobalShortcutsPortal {
+    connection: PortalConnection,
+    sessions: Sessions,
+}
+
+impl GlobalShortcutsPortal {
+    /// Start connecting to the session bus and listening for the activations.
+    pub fn new(executor: &LocalExecutor, sink: GlobalShortcutSink) -> Self {
+        // NOTE: The activations are subscribed to before the connection resolves, so the
+        // shortcuts bound once it's resolved can't miss them.
+        let signals = Rc::new(Cell::new(None));
+        let connection_signals = signals.clone();
+        let connection = async move {
+            match connect().await {
+                Ok((connection, signals)) => {
+                    connection_signals.set(Some(signals));
+                    Some(connection)
+                },
+                Err(error) => {
+                    warn!("The global shortcuts portal is unavailable: {error}");
+                    None
+                },
+            }
+        }
+        .boxed_local()
+        .shared();
+
+        let sessions = Sessions::default();
+        executor.spawn(listen(connection.clone(), signals, sessions.clone(), sink));
+        Self { connection, sessions }
+    }
+
+    /// Whether the portal is known to be unavailable.
+    pub fn is_unavailable(&self) -> bool {
+        matches!(self.connection.peek(), Some(None))
+    }
+
+    /// Create the session for the shortcut and bind it.
+    ///
+    /// The portal usually asks the user to confirm or to change the trigger, thus the binding
+    /// completes asynchronously, and the failures are only logged.
+    pub fn bind(
+        &self,
+        executor: &LocalExecutor,
+        shortcut_id: GlobalShortcutId,
+        trigger: Option<String>,
+    ) {
+        self.sessions.borrow_mut().insert(shortcut_id, None);
+
+        let connection = self.connection.clone();
+        let sessions = self.sessions.clone();
+        executor.spawn(async move {
+            let connection = match connection.await {
+                Some(connection) => connection,
+                None => return,
+            };
+
+            let session_token = token();
+            let session = match object_path(&connection, "session", &session_token) {
+                Ok(session) => session,
+                Err(error) => {
+                    warn!("Failed to bind the global shortcut: {error}");
+                    return;
+                },
+            };
+
+            // NOTE: The shortcut could be unregistered while connecting.
+            match sessions.borrow_mut().get_mut(&shortcut_id) {
+                Some(path) => *path = Some(session.clone()),
+                None => return,
+            }
+
+            let result = bind(&connection, &session_token, &session, shortcut_id, trigger).await;
+            if let Err(error) = result {
+                warn!("Failed to bind the global shortcut: {error}");
+            }
+
+            // NOTE: The shortcut could be unregistered before its session was created.
+            if !sessions.borrow().contains_key(&shortcut_id) {
+                let _ = close(&connection, &session).await;
+            }
+        });
+    }
+
+    /// Close the session of the shortcut.
+    pub fn unbind(&self, executor: &LocalExecutor, shortcut_id: GlobalShortcutId) {
+        let session = match self.sessions.borrow_mut().remove(&shortcut_id) {
+            Some(Some(session)) => session,
+            // NOTE: The binding is abandoned once it finds the shortcut unregistered.
+            _ => return,
+        };
+
+        let connection = self.connection.clone();
+        executor.spawn(async move {
+            // NOTE: The session which isn't created yet is closed once the binding is done.
+            if let Some(connection) = connection.await {
+                let _ = close(&connection, &session).await;
+            }
+        });
+    }
+}
+
+/// The trigger in the format of the XDG shortcuts specification, like `CTRL+SHIFT+a`.
+pub fn trigger(modifiers: ModifiersState, key: &str) -> String {
+    let mut trigger = String::new();
+    for (modifier, name) in [
+        (ModifiersState::CONTROL, "CTRL"),
+        (ModifiersState::ALT, "ALT"),
+        (ModifiersState::SHIFT, "SHIFT"),
+        (ModifiersState::SUPER, "LOGO"),
+    ] {
+        if modifiers.contains(modifier) {
+            trigger.push_str(name);
+            trigger.push('+');
+        }
+    }
+
+    trigger.push_str(key);
+    trigger
+}
+
+/// Connect to the session bus and subscribe to the activations.
+async fn connect() -> zbus::Result<(Connection, MessageStream)> {
+    let connection = Connection::session().await?;
+
+    // NOTE: Query the version, so the missing portal is reported right away instead of failing
+    // every request.
+    connection
+        .call_method(
+            Some(PORTAL_DESTINATION),
+            PORTAL_PATH,
+            Some("org.freedesktop.DBus.Properties"),
+            "Get",
+            &(GLOBAL_SHORTCUTS_INTERFACE, "version"),
+        )
+        .await?;
+
+    let rule = MatchRule::builder()
+        .msg_type(MessageType::Signal)
+        .interface(GLOBAL_SHORTCUTS_INTERFACE)?
+        .path(PORTAL_PATH)?
+        .build();
+    let signals = MessageStream::for_match_rule(rule, &connection, None).await?;
+    Ok((connection, signals))
+}
+
+/// Report the activations of the bound shortcuts until the connection is closed.
+async fn listen(
+    connection: PortalConnection,
+    signals: Rc<Cell<Option<MessageStream>>>,
+    sessions: Sessions,
+    sink: GlobalShortcutSink,
+) {
+    // NOTE: The signals are subscribed to by the connection future.
+    let mut signals = match connection.await.and_then(|_| signals.take()) {
+        Some(signals) => signals,
+        None => return,
+    };
+
+    while let Some(message) = signals.next().await {
+        let message = match message {
+            Ok(message) => message,
+            Err(error) => {
+                warn!("Failed to read the global shortcuts portal signal: {error}");
+                continue;
+            },
+        };
+
+        let state = match message.member().as_deref() {
+            Some("Activated") => ElementState::Pressed,
+            Some("Deactivated") => ElementState::Released,
+            _ => continue,
+        };
+
+        let body: zbus::Result<(OwnedObjectPath, String, u64, HashMap<String, OwnedValue>)> =
+            message.body();
+        let session = match body {
+            Ok((session, ..)) => session,
+            Err(error) => {
+                warn!("Malformed global shortcuts portal signal: {error}");
+                continue;
+            },
+        };
+
+        let sessions = sessions.borrow();
+        let shortcut_id = sessions.iter().find(|(_, path)| path.as_ref() == Some(&session));
+        if let Some((shortcut_id, _)) = shortcut_id {
+            sink.push(*shortcut_id, state);
+        }
+    }
+}
+
+async fn bind(
+    connection: &Connection,
+    session_token: &str,
+    session: &OwnedObjectPath,
+    shortcut_id: GlobalShortcutId,
+    trigger: Option<String>,
+) -> zbus::Result<()> {
+    let request_token = token();
+    let options = HashMap::from([
+        ("handle_token", Value::from(request_token.as_str())),
+        ("session_handle_token", Value::from(session_token)),
+    ]);
+    request(connection, "CreateSession", &request_token, &(options,)).await?;
+
+    // NOTE: The description is required, and it's shown to the user along with the trigger.
+    let description = trigger.clone().unwrap_or_else(|| format!("Shortcut {}", shortcut_id.0));
+    let mut shortcut = HashMap::from([("description", Value::from(description))]);
+    if let Some(trigger) = trigger {
+        shortcut.insert("preferred_trigger", Value::from(trigger));
+    }
+
+    let request_token = token();
+    let shortcuts = vec![(shortcut_id.0.to_string(), shortcut)];
+    let options = HashMap::from([("handle_token", Value::from(request_token.as_str()))]);
+    request(connection, "BindShortcuts", &request_token, &(session, shortcuts, "", options)).await
+}
+
+/// Call the portal method and wait for the response to its request.
+async fn request<B>(
+    connection: &Connection,
+    method: &str,
+    token: &str,
+    body: &B,
+) -> zbus::Result<()>
+where
+    B: Serialize + DynamicType,
+{
+    // NOTE: Subscribe before the call, since the response could arrive before the reply.
+    let path = object_path(connection, "request", token)?;
+    let rule = MatchRule::builder()
+        .msg_type(MessageType::Signal)
+        .interface(REQUEST_INTERFACE)?
+        .member("Response")?
+        .path(path.into_inner())?
+        .build();
+    let mut responses = MessageStream::for_match_rule(rule, connection, None).await?;
+
+    connection
+        .call_method(
+            Some(PORTAL_DESTINATION),
+            PORTAL_PATH,
+            Some(GLOBAL_SHORTCUTS_INTERFACE),
+            method,
+            body,
+        )
+        .await?;
+
+    let response = match responses.next().await {
+        Some(response) => response?,
+        None => return Err(zbus::Error::Failure("the connection is closed".into())),
+    };
+    let (code, _): (u32, HashMap<String, OwnedValue>) = response.body()?;
+    match code {
+        0 => Ok(()),
+        1 => Err(zbus::Error::Failure(format!("`{method}` was cancelled by the user"))),
+        _ => Err(zbus::Error::Failure(format!("`{method}` has failed"))),
+    }
+}
+
+async fn close(connection: &Connection, session: &OwnedObjectPath) -> zbus::Result<()> {
+    connection
+        .call_method(Some(PORTAL_DESTINATION), session, Some(SESSION_INTERFACE), "Close", &())
+        .await
+        .map(drop)
+}
+
+/// The path of the portal object created for the token, like the request or the session.
+fn object_path(connection: &Connection, kind: &str, token: &str) -> zbus::Result<OwnedObjectPath> {
+    let sender = connection.unique_name().map(|name| name.as_str()).unwrap_or_default();
+    let sender = sender.trim_start_matches(':').replace('.', "_");
+    Ok(OwnedObjectPath::try_from(format!("{PORTAL_PATH}/{kind}/{sender}/{token}"))?)
+}
+
+fn token() -> String {
+    format!("winit{}", NEXT_TOKEN.fetch_add(1, Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trigger_format() {
+        assert_eq!(trigger(ModifiersState::empty(), "F1"), "F1");
+        let modifiers = ModifiersState::SHIFT | ModifiersState::CONTROL | ModifiersState::SUPER;
+        assert_eq!(trigger(modifiers, "a"), "CTRL+SHIFT+LOGO+a");
+    }
+}
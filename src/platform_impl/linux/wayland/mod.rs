@@ -8,13 +8,15 @@ pub use output::{MonitorHandle, VideoModeHandle};
 use sctk::reexports::client::globals::{BindError, GlobalError};
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{self, ConnectError, DispatchError, Proxy};
-pub use window::Window;
+pub use window::{CsdFrameBuilder, Window};
 
 pub(super) use crate::cursor::OnlyCursorImage as CustomCursor;
 use crate::dpi::{LogicalSize, PhysicalSize};
 pub use crate::platform_impl::platform::{OsError, WindowId};
 
 mod event_loop;
+#[cfg(feature = "wayland-global-shortcuts")]
+mod global_shortcuts;
 mod output;
 mod seat;
 mod state;
@@ -1,8 +1,10 @@
 //! The keyboard input handling.
 
-use std::sync::Mutex;
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use ahash::AHashMap;
 use calloop::timer::{TimeoutAction, Timer};
 use calloop::{LoopHandle, RegistrationToken};
 use sctk::reexports::client::protocol::wl_keyboard::{
@@ -12,11 +14,11 @@ use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::{Connection, Dispatch, Proxy, QueueHandle, WEnum};
 use tracing::warn;
 
-use crate::event::{ElementState, WindowEvent};
-use crate::keyboard::ModifiersState;
-use crate::platform_impl::common::xkb::Context;
+use crate::event::{ElementState, Modifiers, WindowEvent};
+use crate::platform_impl::common::xkb::{Context, ResetDeadKeys};
 use crate::platform_impl::wayland::event_loop::sink::EventSink;
 use crate::platform_impl::wayland::state::WinitState;
+use crate::platform_impl::wayland::window::WindowState;
 use crate::platform_impl::wayland::{self, DeviceId, WindowId};
 
 impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
@@ -52,6 +54,12 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     WlKeymapFormat::XkbV1 => {
                         let context = &mut keyboard_state.xkb_context;
                         context.set_keymap_from_fd(fd, size as usize);
+
+                        if let Some(window_id) = *data.window_id.lock().unwrap() {
+                            state
+                                .events_sink
+                                .push_window_event(WindowEvent::KeyboardLayoutChanged, window_id);
+                        }
                     },
                     _ => unreachable!(),
                 },
@@ -68,6 +76,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                         let mut window = window.lock().unwrap();
                         let was_unfocused = !window.has_focus();
                         window.add_seat_focus(data.seat.id());
+                        window.set_seat_repeat_info(keyboard_state.repeat_info);
                         was_unfocused
                     },
                     None => return,
@@ -89,7 +98,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                 // HACK: this is just for GNOME not fixing their ordering issue of modifiers.
                 if std::mem::take(&mut seat_state.modifiers_pending) {
                     state.events_sink.push_window_event(
-                        WindowEvent::ModifiersChanged(seat_state.modifiers.into()),
+                        WindowEvent::ModifiersChanged(seat_state.modifiers),
                         window_id,
                     );
                 }
@@ -122,7 +131,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                 if !focused {
                     // Notify that no modifiers are being pressed.
                     state.events_sink.push_window_event(
-                        WindowEvent::ModifiersChanged(ModifiersState::empty().into()),
+                        WindowEvent::ModifiersChanged(Modifiers::default()),
                         window_id,
                     );
 
@@ -141,7 +150,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     false,
                 );
 
-                let delay = match keyboard_state.repeat_info {
+                let delay = match window_repeat_info(&state.windows, data) {
                     RepeatInfo::Repeat { delay, .. } => delay,
                     RepeatInfo::Disable => return,
                 };
@@ -194,7 +203,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                         );
 
                         // NOTE: the gap could change dynamically while repeat is going.
-                        match keyboard_state.repeat_info {
+                        match window_repeat_info(&state.windows, data) {
                             RepeatInfo::Repeat { gap, .. } => TimeoutAction::ToDuration(gap),
                             RepeatInfo::Disable => TimeoutAction::Drop,
                         }
@@ -213,8 +222,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     false,
                 );
 
-                if keyboard_state.repeat_info != RepeatInfo::Disable
-                    && keyboard_state.xkb_context.keymap_mut().unwrap().key_repeats(key)
+                if keyboard_state.xkb_context.keymap_mut().unwrap().key_repeats(key)
                     && Some(key) == keyboard_state.current_repeat
                 {
                     keyboard_state.current_repeat = None;
@@ -232,8 +240,10 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     None => return,
                 };
 
+                let layout = xkb_state.active_layout();
                 xkb_state.update_modifiers(mods_depressed, mods_latched, mods_locked, 0, 0, group);
                 seat_state.modifiers = xkb_state.modifiers().into();
+                let layout_changed = layout != xkb_state.active_layout();
 
                 // HACK: part of the workaround from `WlKeyboardEvent::Enter`.
                 let window_id = match *data.window_id.lock().unwrap() {
@@ -245,9 +255,15 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                 };
 
                 state.events_sink.push_window_event(
-                    WindowEvent::ModifiersChanged(seat_state.modifiers.into()),
+                    WindowEvent::ModifiersChanged(seat_state.modifiers),
                     window_id,
                 );
+
+                if layout_changed {
+                    state
+                        .events_sink
+                        .push_window_event(WindowEvent::KeyboardLayoutChanged, window_id);
+                }
             },
             WlKeyboardEvent::RepeatInfo { rate, delay } => {
                 keyboard_state.repeat_info = if rate == 0 {
@@ -262,6 +278,12 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     let delay = Duration::from_millis(delay as u64);
                     RepeatInfo::Repeat { gap, delay }
                 };
+
+                if let Some(window_id) = *data.window_id.lock().unwrap() {
+                    if let Some(window) = state.windows.get_mut().get(&window_id) {
+                        window.lock().unwrap().set_seat_repeat_info(keyboard_state.repeat_info);
+                    }
+                }
             },
             _ => unreachable!(),
         }
@@ -291,11 +313,15 @@ pub struct KeyboardState {
 }
 
 impl KeyboardState {
-    pub fn new(keyboard: WlKeyboard, loop_handle: LoopHandle<'static, WinitState>) -> Self {
+    pub fn new(
+        keyboard: WlKeyboard,
+        loop_handle: LoopHandle<'static, WinitState>,
+        reset_dead_keys: ResetDeadKeys,
+    ) -> Self {
         Self {
             keyboard,
             loop_handle,
-            xkb_context: Context::new().unwrap(),
+            xkb_context: Context::new(reset_dead_keys).unwrap(),
             repeat_info: RepeatInfo::default(),
             repeat_token: None,
             current_repeat: None,
@@ -356,6 +382,22 @@ impl KeyboardData {
     }
 }
 
+/// The repeat rate in effect for the window focused by the keyboard.
+fn window_repeat_info(
+    windows: &RefCell<AHashMap<WindowId, Arc<Mutex<WindowState>>>>,
+    data: &KeyboardData,
+) -> RepeatInfo {
+    let window_id = match *data.window_id.lock().unwrap() {
+        Some(window_id) => window_id,
+        None => return RepeatInfo::Disable,
+    };
+
+    match windows.borrow().get(&window_id) {
+        Some(window) => window.lock().unwrap().repeat_info(),
+        None => RepeatInfo::Disable,
+    }
+}
+
 fn key_input(
     keyboard_state: &mut KeyboardState,
     event_sink: &mut EventSink,
@@ -372,6 +414,12 @@ fn key_input(
     let device_id = crate::event::DeviceId(crate::platform_impl::DeviceId::Wayland(DeviceId));
     if let Some(mut key_context) = keyboard_state.xkb_context.key_context() {
         let event = key_context.process_key_event(keycode, state, repeat);
+
+        // The compose sequence must end before the composed text is delivered.
+        if let Some(compose) = keyboard_state.xkb_context.take_compose() {
+            event_sink.push_window_event(WindowEvent::Compose(compose), window_id);
+        }
+
         let event = WindowEvent::KeyboardInput { device_id, event, is_synthetic: false };
         event_sink.push_window_event(event, window_id);
     }
@@ -13,8 +13,8 @@ use sctk::seat::pointer::{ThemeSpec, ThemedPointer};
 use sctk::seat::{Capability as SeatCapability, SeatHandler, SeatState};
 use tracing::warn;
 
-use crate::event::WindowEvent;
-use crate::keyboard::ModifiersState;
+use crate::event::{Modifiers, WindowEvent};
+use crate::platform_impl::common::xkb::KeymapSnapshot;
 use crate::platform_impl::wayland::state::WinitState;
 
 mod keyboard;
@@ -22,8 +22,10 @@ mod pointer;
 mod text_input;
 mod touch;
 
+pub use keyboard::RepeatInfo;
 use keyboard::{KeyboardData, KeyboardState};
 pub use pointer::relative_pointer::RelativePointerState;
+pub use pointer::scroll::ScrollSeatState;
 pub use pointer::{PointerConstraintsState, WinitPointerData, WinitPointerDataExt};
 use text_input::TextInputData;
 pub use text_input::{TextInputState, ZwpTextInputV3Ext};
@@ -50,7 +52,7 @@ pub struct WinitSeatState {
     keyboard_state: Option<KeyboardState>,
 
     /// The current modifiers state on the seat.
-    modifiers: ModifiersState,
+    modifiers: Modifiers,
 
     /// Whether we have pending modifiers.
     modifiers_pending: bool,
@@ -60,6 +62,18 @@ impl WinitSeatState {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// The seat along with the serial of the latest button press on it, if any.
+    pub fn latest_button_serial(&self) -> Option<(WlSeat, u32)> {
+        let pointer_data = self.pointer.as_ref()?.pointer().winit_data();
+        let serial = pointer_data.latest_button_serial();
+        (serial != 0).then(|| (pointer_data.seat().clone(), serial))
+    }
+
+    /// The snapshot of the keymap of the keyboard on the seat, if any.
+    pub fn keymap(&self) -> Option<KeymapSnapshot> {
+        self.keyboard_state.as_ref()?.xkb_context.keymap_snapshot()
+    }
 }
 
 impl SeatHandler for WinitState {
@@ -88,8 +102,11 @@ impl SeatHandler for WinitState {
             },
             SeatCapability::Keyboard if seat_state.keyboard_state.is_none() => {
                 let keyboard = seat.get_keyboard(queue_handle, KeyboardData::new(seat.clone()));
-                seat_state.keyboard_state =
-                    Some(KeyboardState::new(keyboard, self.loop_handle.clone()));
+                seat_state.keyboard_state = Some(KeyboardState::new(
+                    keyboard,
+                    self.loop_handle.clone(),
+                    self.reset_dead_keys.clone(),
+                ));
             },
             SeatCapability::Pointer if seat_state.pointer.is_none() => {
                 let surface = self.compositor_state.create_surface(queue_handle);
@@ -7,7 +7,7 @@ use std::time::Duration;
 use tracing::warn;
 
 use sctk::reexports::client::delegate_dispatch;
-use sctk::reexports::client::protocol::wl_pointer::WlPointer;
+use sctk::reexports::client::protocol::wl_pointer::{AxisSource, WlPointer};
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Connection, Proxy, QueueHandle, Dispatch};
@@ -27,12 +27,15 @@ use sctk::seat::pointer::{
 use sctk::seat::SeatState;
 
 use crate::dpi::{LogicalPosition, PhysicalPosition};
-use crate::event::{ElementState, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent};
+use crate::event::{
+    ElementState, MouseButton, MouseScrollDelta, ScrollSource, TouchPhase, WindowEvent,
+};
 
 use crate::platform_impl::wayland::state::WinitState;
 use crate::platform_impl::wayland::{self, DeviceId, WindowId};
 
 pub mod relative_pointer;
+pub mod scroll;
 
 impl PointerHandler for WinitState {
     fn pointer_frame(
@@ -168,26 +171,20 @@ impl PointerHandler for WinitState {
                         window_id,
                     );
                 },
-                PointerEventKind::Axis { horizontal, vertical, .. } => {
+                // NOTE: The scrolling is reported by the scroll pointers once they're available.
+                PointerEventKind::Axis { .. } if self.scroll_seats.has_pointer() => (),
+                PointerEventKind::Axis { horizontal, vertical, source, .. } => {
                     // Get the current phase.
                     let mut pointer_data = pointer.winit_data().inner.lock().unwrap();
 
-                    let has_discrete_scroll = horizontal.discrete != 0 || vertical.discrete != 0;
+                    // The source is optional, so keep the last one for the frames without it.
+                    if let Some(source) = source {
+                        pointer_data.scroll_source = wayland_axis_source_to_winit(source);
+                    }
 
-                    // Figure out what to do about start/ended phases here.
-                    //
-                    // Figure out how to deal with `Started`. Also the `Ended` is not guaranteed
-                    // to be sent for mouse wheels.
-                    let phase = if horizontal.stop || vertical.stop {
-                        TouchPhase::Ended
-                    } else {
-                        match pointer_data.phase {
-                            // Discrete scroll only results in moved events.
-                            _ if has_discrete_scroll => TouchPhase::Moved,
-                            TouchPhase::Started | TouchPhase::Moved => TouchPhase::Moved,
-                            _ => TouchPhase::Started,
-                        }
-                    };
+                    let has_discrete_scroll = horizontal.discrete != 0 || vertical.discrete != 0;
+                    let stop = horizontal.stop || vertical.stop;
+                    let phase = scroll_phase(pointer_data.phase, stop, has_discrete_scroll);
 
                     // Update the phase.
                     pointer_data.phase = phase;
@@ -209,7 +206,13 @@ impl PointerHandler for WinitState {
                     };
 
                     self.events_sink.push_window_event(
-                        WindowEvent::MouseWheel { device_id, delta, phase },
+                        WindowEvent::MouseWheel {
+                            device_id,
+                            delta,
+                            phase,
+                            source: pointer_data.scroll_source,
+                            inverted: false,
+                        },
                         window_id,
                     )
                 },
@@ -336,6 +339,9 @@ pub struct WinitPointerDataInner {
 
     /// Current axis phase.
     phase: TouchPhase,
+
+    /// Source of the current axis sequence.
+    scroll_source: ScrollSource,
 }
 
 impl Drop for WinitPointerDataInner {
@@ -358,6 +364,7 @@ impl Default for WinitPointerDataInner {
             confined_pointer: None,
             latest_button_serial: 0,
             phase: TouchPhase::Ended,
+            scroll_source: ScrollSource::Unknown,
         }
     }
 }
@@ -383,6 +390,35 @@ fn wayland_button_to_winit(button: u32) -> MouseButton {
     }
 }
 
+/// The phase of the scroll following the `previous` one.
+fn scroll_phase(previous: TouchPhase, stop: bool, discrete: bool) -> TouchPhase {
+    // Figure out what to do about start/ended phases here.
+    //
+    // Figure out how to deal with `Started`. Also the `Ended` is not guaranteed to be sent for
+    // mouse wheels.
+    if stop {
+        return TouchPhase::Ended;
+    }
+
+    match previous {
+        // Discrete scroll only results in moved events.
+        _ if discrete => TouchPhase::Moved,
+        TouchPhase::Started | TouchPhase::Moved => TouchPhase::Moved,
+        _ => TouchPhase::Started,
+    }
+}
+
+/// Convert the Wayland axis source into winit.
+fn wayland_axis_source_to_winit(source: AxisSource) -> ScrollSource {
+    match source {
+        AxisSource::Wheel => ScrollSource::Wheel,
+        AxisSource::Finger => ScrollSource::Finger,
+        AxisSource::Continuous => ScrollSource::Continuous,
+        AxisSource::WheelTilt => ScrollSource::WheelTilt,
+        _ => ScrollSource::Unknown,
+    }
+}
+
 pub trait WinitPointerDataExt {
     fn winit_data(&self) -> &WinitPointerData;
 }
@@ -0,0 +1,334 @@
+//! The high-resolution and natural scrolling.
+//!
+//! The sctk binds the seats with version up to 7, which lacks the `axis_value120` and the
+//! `axis_relative_direction` events, thus the seats are bound for the second time to get the
+//! pointers reporting only the scrolling.
+
+use std::sync::Mutex;
+
+use sctk::compositor::SurfaceData;
+use sctk::reexports::client::globals::GlobalList;
+use sctk::reexports::client::protocol::wl_pointer::{self, Axis, AxisRelativeDirection, WlPointer};
+use sctk::reexports::client::protocol::wl_seat::{self, Capability, WlSeat};
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{delegate_dispatch, Dispatch};
+use sctk::reexports::client::{Connection, Proxy, QueueHandle, WEnum};
+use sctk::registry::RegistryHandler;
+
+use crate::dpi::LogicalPosition;
+use crate::event::{MouseScrollDelta, ScrollSource, TouchPhase, WindowEvent};
+use crate::platform_impl::wayland::state::WinitState;
+use crate::platform_impl::wayland::{self, DeviceId};
+
+use super::{scroll_phase, wayland_axis_source_to_winit};
+
+/// The first seat version with the `axis_value120` event.
+const AXIS_VALUE120_VERSION: u32 = 8;
+
+/// The seat version with the `axis_relative_direction` event.
+const MAX_SEAT_VERSION: u32 = 9;
+
+/// The seats bound for the scrolling.
+#[derive(Debug, Default)]
+pub struct ScrollSeatState {
+    seats: Vec<ScrollSeat>,
+}
+
+impl ScrollSeatState {
+    /// Bind the seats supporting the high-resolution scrolling.
+    pub fn new(globals: &GlobalList, queue_handle: &QueueHandle<WinitState>) -> Self {
+        let mut state = Self::default();
+        let seats: Vec<_> = globals.contents().with_list(|globals| {
+            globals
+                .iter()
+                .filter(|global| global.interface == WlSeat::interface().name)
+                .map(|global| (global.name, global.version))
+                .collect()
+        });
+
+        for (name, version) in seats {
+            state.bind(globals.registry(), queue_handle, name, version);
+        }
+
+        state
+    }
+
+    /// Whether the scrolling is reported by the scroll pointers.
+    pub fn has_pointer(&self) -> bool {
+        self.seats.iter().any(|seat| seat.pointer.is_some())
+    }
+
+    fn bind(
+        &mut self,
+        registry: &sctk::reexports::client::protocol::wl_registry::WlRegistry,
+        queue_handle: &QueueHandle<WinitState>,
+        name: u32,
+        version: u32,
+    ) {
+        if version < AXIS_VALUE120_VERSION {
+            return;
+        }
+
+        let seat = registry.bind(name, version.min(MAX_SEAT_VERSION), queue_handle, ScrollSeatData);
+        self.seats.push(ScrollSeat { name, seat, pointer: None });
+    }
+}
+
+#[derive(Debug)]
+struct ScrollSeat {
+    /// The name of the seat global.
+    name: u32,
+
+    /// The seat bound for the scrolling.
+    seat: WlSeat,
+
+    /// The pointer of the seat.
+    pointer: Option<WlPointer>,
+}
+
+impl Drop for ScrollSeat {
+    fn drop(&mut self) {
+        if let Some(pointer) = self.pointer.take() {
+            pointer.release();
+        }
+
+        self.seat.release();
+    }
+}
+
+/// The data of the seat bound for the scrolling.
+#[derive(Debug)]
+pub struct ScrollSeatData;
+
+/// The data of the pointer reporting the scrolling.
+#[derive(Debug, Default)]
+pub struct ScrollPointerData {
+    inner: Mutex<ScrollPointerDataInner>,
+}
+
+#[derive(Debug)]
+struct ScrollPointerDataInner {
+    /// The surface the pointer is over.
+    surface: Option<WlSurface>,
+
+    /// The current scroll phase.
+    phase: TouchPhase,
+
+    /// The source of the scrolling.
+    source: ScrollSource,
+
+    /// The horizontal scrolling within the current frame.
+    horizontal: AxisScroll,
+
+    /// The vertical scrolling within the current frame.
+    vertical: AxisScroll,
+}
+
+impl Default for ScrollPointerDataInner {
+    fn default() -> Self {
+        Self {
+            surface: None,
+            phase: TouchPhase::Ended,
+            source: ScrollSource::default(),
+            horizontal: AxisScroll::default(),
+            vertical: AxisScroll::default(),
+        }
+    }
+}
+
+impl ScrollPointerDataInner {
+    fn axis(&mut self, axis: WEnum<Axis>) -> Option<&mut AxisScroll> {
+        match axis {
+            WEnum::Value(Axis::HorizontalScroll) => Some(&mut self.horizontal),
+            WEnum::Value(Axis::VerticalScroll) => Some(&mut self.vertical),
+            _ => None,
+        }
+    }
+}
+
+/// The scrolling on the axis within a frame.
+#[derive(Debug, Default, Clone, Copy)]
+struct AxisScroll {
+    /// The scrolled distance in the surface coordinates.
+    absolute: f64,
+
+    /// The scrolled distance in the fractions of a wheel detent, where 120 is a whole detent.
+    value120: i32,
+
+    /// Whether the scrolling on the axis has stopped.
+    stop: bool,
+
+    /// Whether the physical direction is inverted relative to the scrolling.
+    inverted: bool,
+}
+
+impl RegistryHandler<WinitState> for ScrollSeatState {
+    fn new_global(
+        state: &mut WinitState,
+        _conn: &Connection,
+        queue_handle: &QueueHandle<WinitState>,
+        name: u32,
+        interface: &str,
+        version: u32,
+    ) {
+        if interface == WlSeat::interface().name {
+            let registry = state.registry_state.registry().clone();
+            state.scroll_seats.bind(&registry, queue_handle, name, version);
+        }
+    }
+
+    fn remove_global(
+        state: &mut WinitState,
+        _conn: &Connection,
+        _queue_handle: &QueueHandle<WinitState>,
+        name: u32,
+        interface: &str,
+    ) {
+        if interface == WlSeat::interface().name {
+            state.scroll_seats.seats.retain(|seat| seat.name != name);
+        }
+    }
+}
+
+impl Dispatch<WlSeat, ScrollSeatData, WinitState> for ScrollSeatState {
+    fn event(
+        state: &mut WinitState,
+        proxy: &WlSeat,
+        event: <WlSeat as Proxy>::Event,
+        _data: &ScrollSeatData,
+        _conn: &Connection,
+        queue_handle: &QueueHandle<WinitState>,
+    ) {
+        let capabilities = match event {
+            wl_seat::Event::Capabilities { capabilities: WEnum::Value(capabilities) } => {
+                capabilities
+            },
+            _ => return,
+        };
+
+        let seat = match state.scroll_seats.seats.iter_mut().find(|seat| &seat.seat == proxy) {
+            Some(seat) => seat,
+            None => return,
+        };
+
+        let has_pointer = capabilities.contains(Capability::Pointer);
+        match seat.pointer.take() {
+            Some(pointer) if !has_pointer => pointer.release(),
+            None if has_pointer => {
+                seat.pointer = Some(proxy.get_pointer(queue_handle, ScrollPointerData::default()))
+            },
+            pointer => seat.pointer = pointer,
+        }
+    }
+}
+
+impl Dispatch<WlPointer, ScrollPointerData, WinitState> for ScrollSeatState {
+    fn event(
+        state: &mut WinitState,
+        _proxy: &WlPointer,
+        event: <WlPointer as Proxy>::Event,
+        data: &ScrollPointerData,
+        _conn: &Connection,
+        _queue_handle: &QueueHandle<WinitState>,
+    ) {
+        let mut pointer_data = data.inner.lock().unwrap();
+        match event {
+            wl_pointer::Event::Enter { surface, .. } => pointer_data.surface = Some(surface),
+            wl_pointer::Event::Leave { .. } => pointer_data.surface = None,
+            wl_pointer::Event::AxisSource { axis_source: WEnum::Value(source) } => {
+                pointer_data.source = wayland_axis_source_to_winit(source);
+            },
+            wl_pointer::Event::Axis { axis, value, .. } => {
+                if let Some(scroll) = pointer_data.axis(axis) {
+                    scroll.absolute += value;
+                }
+            },
+            wl_pointer::Event::AxisValue120 { axis, value120 } => {
+                if let Some(scroll) = pointer_data.axis(axis) {
+                    scroll.value120 += value120;
+                }
+            },
+            wl_pointer::Event::AxisStop { axis, .. } => {
+                if let Some(scroll) = pointer_data.axis(axis) {
+                    scroll.stop = true;
+                }
+            },
+            wl_pointer::Event::AxisRelativeDirection { axis, direction } => {
+                if let Some(scroll) = pointer_data.axis(axis) {
+                    scroll.inverted = direction == WEnum::Value(AxisRelativeDirection::Inverted);
+                }
+            },
+            wl_pointer::Event::Frame => {
+                let horizontal = std::mem::take(&mut pointer_data.horizontal);
+                let vertical = std::mem::take(&mut pointer_data.vertical);
+                let has_discrete_scroll = horizontal.value120 != 0 || vertical.value120 != 0;
+                let has_scroll = has_discrete_scroll
+                    || horizontal.absolute != 0.
+                    || vertical.absolute != 0.
+                    || horizontal.stop
+                    || vertical.stop;
+                if !has_scroll {
+                    return;
+                }
+
+                // The parent surface.
+                let surface = match pointer_data.surface.as_ref() {
+                    Some(surface) => surface,
+                    None => return,
+                };
+                let parent_surface = match surface.data::<SurfaceData>() {
+                    Some(data) => data.parent_surface().unwrap_or(surface),
+                    None => return,
+                };
+
+                // The scrolling over the decorations isn't reported.
+                if parent_surface != surface {
+                    return;
+                }
+
+                let window_id = wayland::make_wid(parent_surface);
+                let scale_factor = match state.windows.get_mut().get(&window_id) {
+                    Some(window) => window.lock().unwrap().scale_factor(),
+                    None => return,
+                };
+
+                let stop = horizontal.stop || vertical.stop;
+                let phase = scroll_phase(pointer_data.phase, stop, has_discrete_scroll);
+                pointer_data.phase = phase;
+
+                // Mice events have both pixel and discrete delta's at the same time. So prefer
+                // the discrete values if they are present.
+                let delta = if has_discrete_scroll {
+                    // NOTE: Wayland sign convention is the inverse of winit.
+                    MouseScrollDelta::LineDelta(
+                        -horizontal.value120 as f32 / 120.,
+                        -vertical.value120 as f32 / 120.,
+                    )
+                } else {
+                    // NOTE: Wayland sign convention is the inverse of winit.
+                    MouseScrollDelta::PixelDelta(
+                        LogicalPosition::new(-horizontal.absolute, -vertical.absolute)
+                            .to_physical(scale_factor),
+                    )
+                };
+
+                let device_id =
+                    crate::event::DeviceId(crate::platform_impl::DeviceId::Wayland(DeviceId));
+                state.events_sink.push_window_event(
+                    WindowEvent::MouseWheel {
+                        device_id,
+                        delta,
+                        phase,
+                        source: pointer_data.source,
+                        inverted: horizontal.inverted || vertical.inverted,
+                    },
+                    window_id,
+                );
+            },
+            _ => (),
+        }
+    }
+}
+
+delegate_dispatch!(WinitState: [WlSeat: ScrollSeatData] => ScrollSeatState);
+delegate_dispatch!(WinitState: [WlPointer: ScrollPointerData] => ScrollSeatState);
@@ -7,8 +7,9 @@ use sctk::reexports::client::{Connection, Proxy, QueueHandle};
 use sctk::seat::touch::{TouchData, TouchHandler};
 use tracing::warn;
 
-use crate::dpi::LogicalPosition;
-use crate::event::{Touch, TouchPhase, WindowEvent};
+use crate::dpi::{LogicalPosition, LogicalSize};
+use crate::event::{Event, Touch, TouchPhase, WindowEvent};
+use crate::platform_impl::wayland::event_loop::sink::EventSink;
 use crate::platform_impl::wayland::state::WinitState;
 use crate::platform_impl::wayland::{self, DeviceId};
 
@@ -40,7 +41,12 @@ impl TouchHandler for WinitState {
 
         // Update the state of the point.
         let location = LogicalPosition::<f64>::from(position);
-        seat_state.touch_map.insert(id, TouchPoint { surface, location });
+        seat_state.touch_map.insert(id, TouchPoint {
+            surface,
+            location,
+            contact_size: None,
+            orientation: None,
+        });
 
         self.events_sink.push_window_event(
             WindowEvent::Touch(Touch {
@@ -50,6 +56,8 @@ impl TouchHandler for WinitState {
                 phase: TouchPhase::Started,
                 location: location.to_physical(scale_factor),
                 force: None,
+                contact_size: None,
+                orientation: None,
                 id: id as u64,
             }),
             window_id,
@@ -93,6 +101,8 @@ impl TouchHandler for WinitState {
                 phase: TouchPhase::Ended,
                 location: touch_point.location.to_physical(scale_factor),
                 force: None,
+                contact_size: touch_point.contact_size.map(|size| size.to_physical(scale_factor)),
+                orientation: touch_point.orientation,
                 id: id as u64,
             }),
             window_id,
@@ -138,6 +148,8 @@ impl TouchHandler for WinitState {
                 phase: TouchPhase::Moved,
                 location: touch_point.location.to_physical(scale_factor),
                 force: None,
+                contact_size: touch_point.contact_size.map(|size| size.to_physical(scale_factor)),
+                orientation: touch_point.orientation,
                 id: id as u64,
             }),
             window_id,
@@ -157,10 +169,11 @@ impl TouchHandler for WinitState {
             let window_id = wayland::make_wid(&touch_point.surface);
             let scale_factor = match self.windows.get_mut().get(&window_id) {
                 Some(window) => window.lock().unwrap().scale_factor(),
-                None => return,
+                None => continue,
             };
 
             let location = touch_point.location.to_physical(scale_factor);
+            let contact_size = touch_point.contact_size.map(|size| size.to_physical(scale_factor));
 
             self.events_sink.push_window_event(
                 WindowEvent::Touch(Touch {
@@ -170,6 +183,8 @@ impl TouchHandler for WinitState {
                     phase: TouchPhase::Cancelled,
                     location,
                     force: None,
+                    contact_size,
+                    orientation: touch_point.orientation,
                     id: id as u64,
                 }),
                 window_id,
@@ -181,19 +196,80 @@ impl TouchHandler for WinitState {
         &mut self,
         _: &Connection,
         _: &QueueHandle<Self>,
-        _: &WlTouch,
-        _: i32,
-        _: f64,
-        _: f64,
+        touch: &WlTouch,
+        id: i32,
+        major: f64,
+        minor: f64,
     ) {
-        // Blank.
+        let seat_state = match self.seats.get_mut(&touch.seat().id()) {
+            Some(seat_state) => seat_state,
+            None => {
+                warn!("Received wl_touch::shape without seat");
+                return;
+            },
+        };
+
+        let touch_point = match seat_state.touch_map.get_mut(&id) {
+            Some(touch_point) => touch_point,
+            None => return,
+        };
+
+        let window_id = wayland::make_wid(&touch_point.surface);
+        let scale_factor = match self.windows.get_mut().get(&window_id) {
+            Some(window) => window.lock().unwrap().scale_factor(),
+            None => return,
+        };
+
+        let contact_size = LogicalSize::new(major, minor);
+        touch_point.contact_size = Some(contact_size);
+
+        if let Some(event) = queued_touch_event(&mut self.events_sink, id) {
+            event.contact_size = Some(contact_size.to_physical(scale_factor));
+        }
     }
 
-    fn orientation(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlTouch, _: i32, _: f64) {
-        // Blank.
+    fn orientation(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        touch: &WlTouch,
+        id: i32,
+        orientation: f64,
+    ) {
+        let seat_state = match self.seats.get_mut(&touch.seat().id()) {
+            Some(seat_state) => seat_state,
+            None => {
+                warn!("Received wl_touch::orientation without seat");
+                return;
+            },
+        };
+
+        let touch_point = match seat_state.touch_map.get_mut(&id) {
+            Some(touch_point) => touch_point,
+            None => return,
+        };
+
+        touch_point.orientation = Some(orientation);
+
+        if let Some(event) = queued_touch_event(&mut self.events_sink, id) {
+            event.orientation = Some(orientation);
+        }
     }
 }
 
+/// The latest queued event of the touch point.
+///
+/// The shape and orientation follow the `down` or `motion` events they describe within the
+/// same frame, thus the event is updated in place before it's delivered.
+fn queued_touch_event(events_sink: &mut EventSink, id: i32) -> Option<&mut Touch> {
+    events_sink.window_events.iter_mut().rev().find_map(|event| match event {
+        Event::WindowEvent { event: WindowEvent::Touch(touch), .. } if touch.id == id as u64 => {
+            Some(touch)
+        },
+        _ => None,
+    })
+}
+
 /// The state of the touch point.
 #[derive(Debug)]
 pub struct TouchPoint {
@@ -202,6 +278,12 @@ pub struct TouchPoint {
 
     /// The location of the point on the surface.
     pub location: LogicalPosition<f64>,
+
+    /// The size of the contact area on the surface.
+    pub contact_size: Option<LogicalSize<f64>>,
+
+    /// The orientation of the contact area in degrees.
+    pub orientation: Option<f64>,
 }
 
 pub trait TouchDataExt {
@@ -11,9 +11,11 @@ use sctk::reexports::client::globals::GlobalList;
 use sctk::reexports::client::protocol::wl_output::WlOutput;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Connection, Proxy, QueueHandle};
+use sctk::reexports::protocols::ext::idle_notify::v1::client::ext_idle_notification_v1::ExtIdleNotificationV1;
 use sctk::registry::{ProvidesRegistryState, RegistryState};
 use sctk::seat::pointer::ThemedPointer;
 use sctk::seat::SeatState;
+use sctk::shell::xdg::popup::{Popup, PopupConfigure, PopupHandler};
 use sctk::shell::xdg::window::{Window, WindowConfigure, WindowHandler};
 use sctk::shell::xdg::XdgShell;
 use sctk::shell::WaylandSurface;
@@ -21,16 +23,24 @@ use sctk::shm::slot::SlotPool;
 use sctk::shm::{Shm, ShmHandler};
 use sctk::subcompositor::SubcompositorState;
 
+use crate::event::WindowEvent;
+use crate::event_loop::IdleTimeoutId;
+use crate::platform_impl::common::modal::ModalBlocker;
+use crate::platform_impl::common::xkb::ResetDeadKeys;
 use crate::platform_impl::wayland::event_loop::sink::EventSink;
 use crate::platform_impl::wayland::output::MonitorHandle;
 use crate::platform_impl::wayland::seat::{
-    PointerConstraintsState, RelativePointerState, TextInputState, WinitPointerData,
-    WinitPointerDataExt, WinitSeatState,
+    PointerConstraintsState, RelativePointerState, ScrollSeatState, TextInputState,
+    WinitPointerData, WinitPointerDataExt, WinitSeatState,
 };
+use crate::platform_impl::wayland::types::ext_idle_notify::IdleNotifier;
 use crate::platform_impl::wayland::types::kwin_blur::KWinBlurManager;
 use crate::platform_impl::wayland::types::wp_fractional_scaling::FractionalScalingManager;
+use crate::platform_impl::wayland::types::wp_idle_inhibit::IdleInhibitManager;
+use crate::platform_impl::wayland::types::wp_keyboard_shortcuts_inhibit::KeyboardShortcutsInhibitManager;
 use crate::platform_impl::wayland::types::wp_viewporter::ViewporterState;
 use crate::platform_impl::wayland::types::xdg_activation::XdgActivationState;
+use crate::platform_impl::wayland::types::xdg_dialog::XdgDialogManager;
 use crate::platform_impl::wayland::window::{WindowRequests, WindowState};
 use crate::platform_impl::wayland::{WaylandError, WindowId};
 use crate::platform_impl::OsError;
@@ -70,9 +80,15 @@ pub struct WinitState {
     /// The events that were generated directly from the window.
     pub window_events_sink: Arc<Mutex<EventSink>>,
 
+    /// The request to reset the dead keys on all the keyboards of this event loop.
+    pub reset_dead_keys: ResetDeadKeys,
+
     /// The update for the `windows` coming from the compositor.
     pub window_compositor_updates: Vec<WindowCompositorUpdate>,
 
+    /// The seats bound for the high-resolution and natural scrolling.
+    pub scroll_seats: ScrollSeatState,
+
     /// Currently handled seats.
     pub seats: AHashMap<ObjectId, WinitSeatState>,
 
@@ -107,6 +123,27 @@ pub struct WinitState {
     /// KWin blur manager.
     pub kwin_blur_manager: Option<KWinBlurManager>,
 
+    /// Xdg dialog manager.
+    pub xdg_dialog_manager: Option<XdgDialogManager>,
+
+    /// Idle inhibit manager.
+    pub idle_inhibit_manager: Option<IdleInhibitManager>,
+
+    /// Keyboard shortcuts inhibit manager.
+    pub keyboard_shortcuts_inhibit_manager: Option<KeyboardShortcutsInhibitManager>,
+
+    /// Idle notifier.
+    pub idle_notifier: Option<IdleNotifier>,
+
+    /// The idle notifications for the idle timeouts registered by the user.
+    pub idle_notifications: AHashMap<IdleTimeoutId, ExtIdleNotificationV1>,
+
+    /// The idle state changes waiting to be delivered.
+    pub idle_events: Vec<(IdleTimeoutId, bool)>,
+
+    /// The windows blocked by their modal dialogs.
+    pub modal_blocker: ModalBlocker,
+
     /// Loop handle to re-register event sources, such as keyboard repeat.
     pub loop_handle: LoopHandle<'static, Self>,
 
@@ -175,11 +212,24 @@ impl WinitState {
             window_requests: Default::default(),
             window_compositor_updates: Vec::new(),
             window_events_sink: Default::default(),
+            reset_dead_keys: Default::default(),
             viewporter_state,
             fractional_scaling_manager,
             kwin_blur_manager: KWinBlurManager::new(globals, queue_handle).ok(),
+            xdg_dialog_manager: XdgDialogManager::new(globals, queue_handle).ok(),
+            idle_inhibit_manager: IdleInhibitManager::new(globals, queue_handle).ok(),
+            keyboard_shortcuts_inhibit_manager: KeyboardShortcutsInhibitManager::new(
+                globals,
+                queue_handle,
+            )
+            .ok(),
+            idle_notifier: IdleNotifier::new(globals, queue_handle).ok(),
+            idle_notifications: Default::default(),
+            idle_events: Vec::new(),
+            modal_blocker: Default::default(),
 
             seats,
+            scroll_seats: ScrollSeatState::new(globals, queue_handle),
             text_input_state: TextInputState::new(globals, queue_handle).ok(),
 
             relative_pointer: RelativePointerState::new(globals, queue_handle).ok(),
@@ -285,14 +335,37 @@ impl WindowHandler for WinitState {
         };
 
         // Populate the configure to the window.
-        self.window_compositor_updates[pos].resized |= self
+        let mut window_state = self
             .windows
             .get_mut()
             .get_mut(&window_id)
             .expect("got configure for dead window.")
             .lock()
-            .unwrap()
-            .configure(configure, &self.shm, &self.subcompositor_state);
+            .unwrap();
+        let old_decoration_mode = window_state.decoration_mode();
+        let old_state = window_state.state();
+        let old_wm_capabilities = window_state.wm_capabilities();
+        self.window_compositor_updates[pos].resized |=
+            window_state.configure(configure, &self.shm, &self.subcompositor_state);
+        let decoration_mode = window_state.decoration_mode();
+        let state = window_state.state();
+        let wm_capabilities = window_state.wm_capabilities();
+        drop(window_state);
+
+        if let Some(mode) = decoration_mode.filter(|mode| Some(*mode) != old_decoration_mode) {
+            self.events_sink.push_window_event(WindowEvent::DecorationModeChanged(mode), window_id);
+        }
+
+        if state != old_state {
+            self.events_sink.push_window_event(WindowEvent::StateChanged(state), window_id);
+        }
+
+        if let Some(capabilities) =
+            wm_capabilities.filter(|_| wm_capabilities != old_wm_capabilities)
+        {
+            self.events_sink
+                .push_window_event(WindowEvent::WmCapabilitiesChanged(capabilities), window_id);
+        }
 
         // NOTE: configure demands wl_surface::commit, however winit doesn't commit on behalf of the
         // users, since it can break a lot of things, thus it'll ask users to redraw instead.
@@ -308,6 +381,53 @@ impl WindowHandler for WinitState {
     }
 }
 
+impl PopupHandler for WinitState {
+    fn configure(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        popup: &Popup,
+        configure: PopupConfigure,
+    ) {
+        let window_id = super::make_wid(popup.wl_surface());
+
+        let pos = if let Some(pos) =
+            self.window_compositor_updates.iter().position(|update| update.window_id == window_id)
+        {
+            pos
+        } else {
+            self.window_compositor_updates.push(WindowCompositorUpdate::new(window_id));
+            self.window_compositor_updates.len() - 1
+        };
+
+        // Populate the configure to the popup.
+        self.window_compositor_updates[pos].resized |= self
+            .windows
+            .get_mut()
+            .get_mut(&window_id)
+            .expect("got configure for dead popup.")
+            .lock()
+            .unwrap()
+            .configure_popup(configure);
+
+        // NOTE: see `WindowHandler::configure`.
+        self.window_requests
+            .get_mut()
+            .get(&window_id)
+            .unwrap()
+            .redraw_requested
+            .store(true, Ordering::Relaxed);
+
+        self.dispatched_events = true;
+    }
+
+    fn done(&mut self, _: &Connection, _: &QueueHandle<Self>, popup: &Popup) {
+        let window_id = super::make_wid(popup.wl_surface());
+        self.events_sink.push_window_event(WindowEvent::PopupDismissed, window_id);
+        self.dispatched_events = true;
+    }
+}
+
 impl OutputHandler for WinitState {
     fn output_state(&mut self) -> &mut OutputState {
         &mut self.output_state
@@ -399,7 +519,7 @@ impl CompositorHandler for WinitState {
 }
 
 impl ProvidesRegistryState for WinitState {
-    sctk::registry_handlers![OutputState, SeatState];
+    sctk::registry_handlers![OutputState, SeatState, ScrollSeatState];
 
     fn registry(&mut self) -> &mut RegistryState {
         &mut self.registry_state
@@ -423,7 +543,7 @@ pub struct WindowCompositorUpdate {
 }
 
 impl WindowCompositorUpdate {
-    fn new(window_id: WindowId) -> Self {
+    pub fn new(window_id: WindowId) -> Self {
         Self { window_id, resized: false, scale_changed: false, close_window: false }
     }
 }
@@ -435,3 +555,4 @@ sctk::delegate_registry!(WinitState);
 sctk::delegate_shm!(WinitState);
 sctk::delegate_xdg_shell!(WinitState);
 sctk::delegate_xdg_window!(WinitState);
+sctk::delegate_xdg_popup!(WinitState);
@@ -0,0 +1,75 @@
+//! Handling of the idle notifications.
+
+use sctk::globals::GlobalData;
+use sctk::reexports::client::globals::{BindError, GlobalList};
+use sctk::reexports::client::protocol::wl_seat::WlSeat;
+use sctk::reexports::client::{delegate_dispatch, Connection, Dispatch, Proxy, QueueHandle};
+use sctk::reexports::protocols::ext::idle_notify::v1::client::ext_idle_notification_v1::{
+    Event as IdleNotificationEvent, ExtIdleNotificationV1,
+};
+use sctk::reexports::protocols::ext::idle_notify::v1::client::ext_idle_notifier_v1::ExtIdleNotifierV1;
+
+use crate::event_loop::IdleTimeoutId;
+use crate::platform_impl::wayland::state::WinitState;
+
+/// Idle notifier.
+#[derive(Debug, Clone)]
+pub struct IdleNotifier {
+    notifier: ExtIdleNotifierV1,
+}
+
+impl IdleNotifier {
+    pub fn new(
+        globals: &GlobalList,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Result<Self, BindError> {
+        let notifier = globals.bind(queue_handle, 1..=1, GlobalData)?;
+        Ok(Self { notifier })
+    }
+
+    pub fn notification(
+        &self,
+        timeout: u32,
+        seat: &WlSeat,
+        queue_handle: &QueueHandle<WinitState>,
+        timeout_id: IdleTimeoutId,
+    ) -> ExtIdleNotificationV1 {
+        self.notifier.get_idle_notification(timeout, seat, queue_handle, timeout_id)
+    }
+}
+
+impl Dispatch<ExtIdleNotifierV1, GlobalData, WinitState> for IdleNotifier {
+    fn event(
+        _: &mut WinitState,
+        _: &ExtIdleNotifierV1,
+        _: <ExtIdleNotifierV1 as Proxy>::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        unreachable!("no events defined for ext_idle_notifier_v1");
+    }
+}
+
+impl Dispatch<ExtIdleNotificationV1, IdleTimeoutId, WinitState> for IdleNotifier {
+    fn event(
+        state: &mut WinitState,
+        _: &ExtIdleNotificationV1,
+        event: <ExtIdleNotificationV1 as Proxy>::Event,
+        timeout_id: &IdleTimeoutId,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        let idle = match event {
+            IdleNotificationEvent::Idled => true,
+            IdleNotificationEvent::Resumed => false,
+            _ => return,
+        };
+
+        state.idle_events.push((*timeout_id, idle));
+        state.dispatched_events = true;
+    }
+}
+
+delegate_dispatch!(WinitState: [ExtIdleNotifierV1: GlobalData] => IdleNotifier);
+delegate_dispatch!(WinitState: [ExtIdleNotificationV1: IdleTimeoutId] => IdleNotifier);
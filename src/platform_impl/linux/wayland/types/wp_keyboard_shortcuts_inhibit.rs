@@ -0,0 +1,100 @@
+//! Handling of the keyboard shortcuts inhibit.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use sctk::globals::GlobalData;
+use sctk::reexports::client::globals::{BindError, GlobalList};
+use sctk::reexports::client::protocol::wl_seat::WlSeat;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{delegate_dispatch, Connection, Dispatch, Proxy, QueueHandle};
+use sctk::reexports::protocols::wp::keyboard_shortcuts_inhibit::zv1::client::zwp_keyboard_shortcuts_inhibit_manager_v1::ZwpKeyboardShortcutsInhibitManagerV1;
+use sctk::reexports::protocols::wp::keyboard_shortcuts_inhibit::zv1::client::zwp_keyboard_shortcuts_inhibitor_v1::{
+    Event as InhibitorEvent, ZwpKeyboardShortcutsInhibitorV1,
+};
+
+use crate::event::WindowEvent;
+use crate::platform_impl::wayland::state::WinitState;
+use crate::platform_impl::wayland::WindowId;
+
+/// Keyboard shortcuts inhibit manager.
+#[derive(Debug, Clone)]
+pub struct KeyboardShortcutsInhibitManager {
+    manager: ZwpKeyboardShortcutsInhibitManagerV1,
+}
+
+impl KeyboardShortcutsInhibitManager {
+    pub fn new(
+        globals: &GlobalList,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Result<Self, BindError> {
+        let manager = globals.bind(queue_handle, 1..=1, GlobalData)?;
+        Ok(Self { manager })
+    }
+
+    pub fn inhibit_shortcuts(
+        &self,
+        surface: &WlSurface,
+        seat: &WlSeat,
+        queue_handle: &QueueHandle<WinitState>,
+        window_id: WindowId,
+    ) -> ZwpKeyboardShortcutsInhibitorV1 {
+        let data = KeyboardShortcutsInhibitorData { window_id, active: AtomicBool::new(false) };
+        self.manager.inhibit_shortcuts(surface, seat, queue_handle, data)
+    }
+}
+
+/// The data of the keyboard shortcuts inhibitor.
+#[derive(Debug)]
+pub struct KeyboardShortcutsInhibitorData {
+    window_id: WindowId,
+    active: AtomicBool,
+}
+
+impl KeyboardShortcutsInhibitorData {
+    /// Whether the compositor has activated the inhibitor.
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Relaxed)
+    }
+}
+
+impl Dispatch<ZwpKeyboardShortcutsInhibitManagerV1, GlobalData, WinitState>
+    for KeyboardShortcutsInhibitManager
+{
+    fn event(
+        _: &mut WinitState,
+        _: &ZwpKeyboardShortcutsInhibitManagerV1,
+        _: <ZwpKeyboardShortcutsInhibitManagerV1 as Proxy>::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        unreachable!("no events defined for zwp_keyboard_shortcuts_inhibit_manager_v1");
+    }
+}
+
+impl Dispatch<ZwpKeyboardShortcutsInhibitorV1, KeyboardShortcutsInhibitorData, WinitState>
+    for KeyboardShortcutsInhibitManager
+{
+    fn event(
+        state: &mut WinitState,
+        _: &ZwpKeyboardShortcutsInhibitorV1,
+        event: <ZwpKeyboardShortcutsInhibitorV1 as Proxy>::Event,
+        data: &KeyboardShortcutsInhibitorData,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        let inhibited = match event {
+            InhibitorEvent::Active => true,
+            InhibitorEvent::Inactive => false,
+            _ => return,
+        };
+
+        data.active.store(inhibited, Ordering::Relaxed);
+        state
+            .events_sink
+            .push_window_event(WindowEvent::KeyboardShortcutsInhibited(inhibited), data.window_id);
+    }
+}
+
+delegate_dispatch!(WinitState: [ZwpKeyboardShortcutsInhibitManagerV1: GlobalData] => KeyboardShortcutsInhibitManager);
+delegate_dispatch!(WinitState: [ZwpKeyboardShortcutsInhibitorV1: KeyboardShortcutsInhibitorData] => KeyboardShortcutsInhibitManager);
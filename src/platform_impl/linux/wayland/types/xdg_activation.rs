@@ -13,6 +13,7 @@ use sctk::reexports::protocols::xdg::activation::v1::client::xdg_activation_toke
 use sctk::reexports::protocols::xdg::activation::v1::client::xdg_activation_v1::XdgActivationV1;
 
 use crate::event_loop::AsyncRequestSerial;
+use crate::platform_impl::common::executor;
 use crate::platform_impl::wayland::state::WinitState;
 use crate::platform_impl::WindowId;
 use crate::window::ActivationToken;
@@ -76,11 +77,10 @@ impl Dispatch<XdgActivationTokenV1, XdgActivationTokenData, WinitState> for XdgA
                 }
             },
             XdgActivationTokenData::Obtain((window_id, serial)) => {
+                let token = ActivationToken::_new(token);
+                executor::complete_activation_token(*serial, Ok(token.clone()));
                 state.events_sink.push_window_event(
-                    crate::event::WindowEvent::ActivationTokenDone {
-                        serial: *serial,
-                        token: ActivationToken::_new(token),
-                    },
+                    crate::event::WindowEvent::ActivationTokenDone { serial: *serial, token },
                     *window_id,
                 );
             },
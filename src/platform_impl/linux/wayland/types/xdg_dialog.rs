@@ -0,0 +1,63 @@
+//! Handling of xdg dialogs.
+
+use sctk::globals::GlobalData;
+use sctk::reexports::client::globals::{BindError, GlobalList};
+use sctk::reexports::client::{delegate_dispatch, Connection, Dispatch, Proxy, QueueHandle};
+use sctk::reexports::protocols::xdg::dialog::v1::client::xdg_dialog_v1::XdgDialogV1;
+use sctk::reexports::protocols::xdg::dialog::v1::client::xdg_wm_dialog_v1::XdgWmDialogV1;
+use sctk::reexports::protocols::xdg::shell::client::xdg_toplevel::XdgToplevel;
+
+use crate::platform_impl::wayland::state::WinitState;
+
+/// Xdg dialog manager.
+#[derive(Debug, Clone)]
+pub struct XdgDialogManager {
+    manager: XdgWmDialogV1,
+}
+
+impl XdgDialogManager {
+    pub fn new(
+        globals: &GlobalList,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Result<Self, BindError> {
+        let manager = globals.bind(queue_handle, 1..=1, GlobalData)?;
+        Ok(Self { manager })
+    }
+
+    pub fn dialog(
+        &self,
+        toplevel: &XdgToplevel,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> XdgDialogV1 {
+        self.manager.get_xdg_dialog(toplevel, queue_handle, ())
+    }
+}
+
+impl Dispatch<XdgWmDialogV1, GlobalData, WinitState> for XdgDialogManager {
+    fn event(
+        _: &mut WinitState,
+        _: &XdgWmDialogV1,
+        _: <XdgWmDialogV1 as Proxy>::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        unreachable!("no events defined for xdg_wm_dialog_v1");
+    }
+}
+
+impl Dispatch<XdgDialogV1, (), WinitState> for XdgDialogManager {
+    fn event(
+        _: &mut WinitState,
+        _: &XdgDialogV1,
+        _: <XdgDialogV1 as Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        unreachable!("no events defined for xdg_dialog_v1");
+    }
+}
+
+delegate_dispatch!(WinitState: [XdgWmDialogV1: GlobalData] => XdgDialogManager);
+delegate_dispatch!(WinitState: [XdgDialogV1: ()] => XdgDialogManager);
@@ -0,0 +1,644 @@
+//! The client side decorations frame.
+
+use std::error::Error;
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use std::time::Duration;
+use std::{fmt, mem};
+
+#[cfg(feature = "sctk-adwaita")]
+use sctk::compositor::CompositorState;
+use sctk::reexports::client::backend::ObjectId;
+use sctk::reexports::client::protocol::wl_shm;
+use sctk::reexports::client::protocol::wl_subsurface::WlSubsurface;
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{Proxy, QueueHandle};
+use sctk::reexports::csd_frame::{
+    DecorationsFrame, FrameAction, FrameClick, ResizeEdge, WindowManagerCapabilities,
+    WindowState as XdgWindowState,
+};
+use sctk::shell::xdg::window::Window;
+use sctk::shell::WaylandSurface;
+use sctk::shm::slot::SlotPool;
+use sctk::shm::Shm;
+use sctk::subcompositor::SubcompositorState;
+use tracing::warn;
+
+use crate::dpi::{LogicalPosition, LogicalSize};
+use crate::platform::wayland::{
+    CsdFrame, CsdFrameBorders, CsdFrameBuffer, CsdFrameButton, CsdFrameHit, CsdFrameState,
+};
+use crate::platform_impl::wayland::state::WinitState;
+use crate::window::{CursorIcon, ResizeDirection, Theme};
+
+#[cfg(feature = "sctk-adwaita")]
+pub type DefaultFrame = sctk_adwaita::AdwaitaFrame<WinitState>;
+#[cfg(not(feature = "sctk-adwaita"))]
+pub type DefaultFrame = sctk::shell::xdg::fallback_frame::FallbackFrame<WinitState>;
+
+/// The maximum delay between the title bar clicks to toggle the maximized state.
+const DOUBLE_CLICK_DELAY: Duration = Duration::from_millis(400);
+
+/// The builder of the frame provided by the user.
+#[derive(Clone)]
+pub struct CsdFrameBuilder(Arc<dyn Fn() -> Box<dyn CsdFrame> + Send + Sync>);
+
+impl CsdFrameBuilder {
+    pub fn new(builder: Arc<dyn Fn() -> Box<dyn CsdFrame> + Send + Sync>) -> Self {
+        Self(builder)
+    }
+
+    fn build(&self) -> Box<dyn CsdFrame> {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for CsdFrameBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CsdFrameBuilder").finish_non_exhaustive()
+    }
+}
+
+/// The frame drawing the client side decorations.
+// NOTE: The default frame is the common case, so don't box it.
+#[allow(clippy::large_enum_variant)]
+pub enum WinitFrame {
+    /// The frame picked by the cargo features.
+    Default(DefaultFrame),
+
+    /// The frame provided by the user.
+    Custom(CustomFrame),
+}
+
+impl WinitFrame {
+    /// Create the user provided frame if there's one, falling back to the default one.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        builder: Option<&CsdFrameBuilder>,
+        window: &Window,
+        shm: &Shm,
+        #[cfg(feature = "sctk-adwaita")] compositor: Arc<CompositorState>,
+        subcompositor: Arc<SubcompositorState>,
+        queue_handle: QueueHandle<WinitState>,
+        theme: Option<Theme>,
+    ) -> Result<Self, Box<dyn Error>> {
+        if let Some(builder) = builder {
+            let frame =
+                CustomFrame::new(builder.build(), window, shm, subcompositor, queue_handle, theme)?;
+            return Ok(Self::Custom(frame));
+        }
+
+        DefaultFrame::new(
+            window,
+            shm,
+            #[cfg(feature = "sctk-adwaita")]
+            compositor,
+            subcompositor,
+            queue_handle,
+            #[cfg(feature = "sctk-adwaita")]
+            into_sctk_adwaita_config(theme),
+        )
+        .map(Self::Default)
+    }
+
+    /// Set the theme of the frame.
+    pub fn set_theme(&mut self, theme: Option<Theme>) {
+        match self {
+            #[cfg(feature = "sctk-adwaita")]
+            Self::Default(frame) => frame.set_config(into_sctk_adwaita_config(theme)),
+            #[cfg(not(feature = "sctk-adwaita"))]
+            Self::Default(_) => (),
+            Self::Custom(frame) => frame.update(|state| state.theme = theme),
+        }
+    }
+}
+
+macro_rules! frame_dispatch {
+    ($self:ident, $frame:ident => $body:expr) => {
+        match $self {
+            WinitFrame::Default($frame) => $body,
+            WinitFrame::Custom($frame) => $body,
+        }
+    };
+}
+
+impl DecorationsFrame for WinitFrame {
+    fn on_click(
+        &mut self,
+        timestamp: Duration,
+        click: FrameClick,
+        pressed: bool,
+    ) -> Option<FrameAction> {
+        frame_dispatch!(self, frame => frame.on_click(timestamp, click, pressed))
+    }
+
+    fn click_point_moved(
+        &mut self,
+        timestamp: Duration,
+        surface_id: &ObjectId,
+        x: f64,
+        y: f64,
+    ) -> Option<CursorIcon> {
+        frame_dispatch!(self, frame => frame.click_point_moved(timestamp, surface_id, x, y))
+    }
+
+    fn click_point_left(&mut self) {
+        frame_dispatch!(self, frame => frame.click_point_left())
+    }
+
+    fn update_state(&mut self, state: XdgWindowState) {
+        frame_dispatch!(self, frame => frame.update_state(state))
+    }
+
+    fn update_wm_capabilities(&mut self, wm_capabilities: WindowManagerCapabilities) {
+        frame_dispatch!(self, frame => frame.update_wm_capabilities(wm_capabilities))
+    }
+
+    fn resize(&mut self, width: NonZeroU32, height: NonZeroU32) {
+        frame_dispatch!(self, frame => frame.resize(width, height))
+    }
+
+    fn set_scaling_factor(&mut self, scale_factor: f64) {
+        frame_dispatch!(self, frame => frame.set_scaling_factor(scale_factor))
+    }
+
+    fn location(&self) -> (i32, i32) {
+        frame_dispatch!(self, frame => frame.location())
+    }
+
+    fn subtract_borders(
+        &self,
+        width: NonZeroU32,
+        height: NonZeroU32,
+    ) -> (Option<NonZeroU32>, Option<NonZeroU32>) {
+        frame_dispatch!(self, frame => frame.subtract_borders(width, height))
+    }
+
+    fn add_borders(&self, width: u32, height: u32) -> (u32, u32) {
+        frame_dispatch!(self, frame => frame.add_borders(width, height))
+    }
+
+    fn is_dirty(&self) -> bool {
+        frame_dispatch!(self, frame => frame.is_dirty())
+    }
+
+    fn set_hidden(&mut self, hidden: bool) {
+        frame_dispatch!(self, frame => frame.set_hidden(hidden))
+    }
+
+    fn is_hidden(&self) -> bool {
+        frame_dispatch!(self, frame => frame.is_hidden())
+    }
+
+    fn set_resizable(&mut self, resizable: bool) {
+        frame_dispatch!(self, frame => frame.set_resizable(resizable))
+    }
+
+    fn draw(&mut self) -> bool {
+        frame_dispatch!(self, frame => frame.draw())
+    }
+
+    fn set_title(&mut self, title: impl Into<String>) {
+        let title = title.into();
+        frame_dispatch!(self, frame => frame.set_title(title))
+    }
+}
+
+/// The adapter driving the user provided [`CsdFrame`].
+pub struct CustomFrame {
+    /// The user frame.
+    frame: Box<dyn CsdFrame>,
+
+    /// The state passed to the user frame.
+    state: CsdFrameState,
+
+    /// The borders of the frame for the current state.
+    borders: CsdFrameBorders,
+
+    /// The latest window state from the compositor.
+    xdg_state: XdgWindowState,
+
+    /// The wm capabilities.
+    wm_capabilities: WindowManagerCapabilities,
+
+    /// The latest pointer position relative to the frame.
+    pointer: LogicalPosition<f64>,
+
+    /// The time of the latest click on the title bar.
+    last_titlebar_click: Option<Duration>,
+
+    /// Whether the frame is waiting for redraw.
+    dirty: bool,
+
+    /// Whether the frame should sync with the parent.
+    ///
+    /// This should happen in reaction to scale or resize changes.
+    should_sync: bool,
+
+    /// The frame surface. When `None` the frame is hidden.
+    surface: Option<FrameSurface>,
+
+    /// The parent surface.
+    parent: WlSurface,
+
+    /// The memory pool to use for drawing.
+    pool: SlotPool,
+
+    /// The subcompositor.
+    subcompositor: Arc<SubcompositorState>,
+
+    /// The frame queue handle.
+    queue_handle: QueueHandle<WinitState>,
+}
+
+impl CustomFrame {
+    fn new(
+        frame: Box<dyn CsdFrame>,
+        window: &Window,
+        shm: &Shm,
+        subcompositor: Arc<SubcompositorState>,
+        queue_handle: QueueHandle<WinitState>,
+        theme: Option<Theme>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let parent = window.wl_surface().clone();
+        let pool = SlotPool::new(1, shm)?;
+        let surface = Some(FrameSurface::new(&parent, &subcompositor, &queue_handle));
+
+        let state = CsdFrameState {
+            title: String::new(),
+            inner_size: LogicalSize::new(0, 0),
+            scale_factor: 1.,
+            theme,
+            activated: false,
+            maximized: false,
+            tiled: false,
+            resizable: true,
+            minimizable: true,
+            maximizable: true,
+            hovered: None,
+            pressed: None,
+        };
+
+        Ok(Self {
+            borders: frame.borders(&state),
+            frame,
+            state,
+            xdg_state: XdgWindowState::empty(),
+            wm_capabilities: WindowManagerCapabilities::all(),
+            pointer: LogicalPosition::new(0., 0.),
+            last_titlebar_click: None,
+            dirty: true,
+            should_sync: true,
+            surface,
+            parent,
+            pool,
+            subcompositor,
+            queue_handle,
+        })
+    }
+
+    /// Update the state, notifying the user frame when it has changed.
+    fn update(&mut self, update: impl FnOnce(&mut CsdFrameState)) {
+        let old_state = self.state.clone();
+        update(&mut self.state);
+        if self.state == old_state {
+            return;
+        }
+
+        let borders = self.frame.borders(&self.state);
+        self.should_sync |= borders != self.borders;
+        self.borders = borders;
+        self.dirty |= self.frame.state_changed(&self.state);
+    }
+
+    /// The borders which are currently visible.
+    fn visible_borders(&self) -> CsdFrameBorders {
+        if self.xdg_state.contains(XdgWindowState::FULLSCREEN) || self.surface.is_none() {
+            CsdFrameBorders::default()
+        } else {
+            self.borders
+        }
+    }
+
+    fn toggle_maximize(&self) -> FrameAction {
+        if self.state.maximized {
+            FrameAction::UnMaximize
+        } else {
+            FrameAction::Maximize
+        }
+    }
+}
+
+impl DecorationsFrame for CustomFrame {
+    fn on_click(
+        &mut self,
+        timestamp: Duration,
+        click: FrameClick,
+        pressed: bool,
+    ) -> Option<FrameAction> {
+        let hovered = self.state.hovered;
+
+        // Handle alternate click before everything else.
+        if click == FrameClick::Alternate {
+            let show_menu = pressed
+                && hovered == Some(CsdFrameHit::Titlebar)
+                && self.wm_capabilities.contains(WindowManagerCapabilities::WINDOW_MENU);
+            let (x, y) = self.location();
+            return show_menu.then(|| {
+                FrameAction::ShowMenu(self.pointer.x as i32 + x, self.pointer.y as i32 + y)
+            });
+        }
+
+        let pressed_button = self.state.pressed;
+        self.update(|state| {
+            state.pressed = match hovered {
+                Some(CsdFrameHit::Button(button)) if pressed => Some(button),
+                _ => None,
+            };
+        });
+
+        match hovered? {
+            CsdFrameHit::Titlebar if pressed => {
+                let last_click = self.last_titlebar_click.replace(timestamp);
+                if last_click
+                    .is_some_and(|last| timestamp.saturating_sub(last) < DOUBLE_CLICK_DELAY)
+                    && self.state.maximizable
+                {
+                    self.last_titlebar_click = None;
+                    Some(self.toggle_maximize())
+                } else {
+                    Some(FrameAction::Move)
+                }
+            },
+            CsdFrameHit::Resize(direction) if pressed && self.state.resizable => {
+                Some(FrameAction::Resize(direction.into()))
+            },
+            CsdFrameHit::Button(button) if !pressed && pressed_button == Some(button) => {
+                match button {
+                    CsdFrameButton::Close => Some(FrameAction::Close),
+                    CsdFrameButton::Minimize => Some(FrameAction::Minimize),
+                    CsdFrameButton::Maximize => Some(self.toggle_maximize()),
+                }
+            },
+            _ => None,
+        }
+    }
+
+    fn click_point_moved(
+        &mut self,
+        _timestamp: Duration,
+        surface_id: &ObjectId,
+        x: f64,
+        y: f64,
+    ) -> Option<CursorIcon> {
+        if &self.surface.as_ref()?.surface.id() != surface_id {
+            return None;
+        }
+
+        self.pointer = LogicalPosition::new(x, y);
+        let hovered = self.frame.hit_test(&self.state, self.pointer);
+        self.update(|state| state.hovered = hovered);
+
+        Some(match hovered {
+            Some(CsdFrameHit::Resize(direction)) if self.state.resizable => direction.into(),
+            _ => CursorIcon::Default,
+        })
+    }
+
+    fn click_point_left(&mut self) {
+        self.update(|state| {
+            state.hovered = None;
+            state.pressed = None;
+        });
+    }
+
+    fn update_state(&mut self, xdg_state: XdgWindowState) {
+        let fullscreen_changed = !self
+            .xdg_state
+            .symmetric_difference(xdg_state)
+            .intersection(XdgWindowState::FULLSCREEN)
+            .is_empty();
+        self.xdg_state = xdg_state;
+        self.dirty |= fullscreen_changed;
+        self.should_sync |= fullscreen_changed;
+
+        self.update(|state| {
+            state.activated = xdg_state.contains(XdgWindowState::ACTIVATED);
+            state.maximized = xdg_state.contains(XdgWindowState::MAXIMIZED);
+            state.tiled = xdg_state.intersects(XdgWindowState::TILED);
+        });
+    }
+
+    fn update_wm_capabilities(&mut self, wm_capabilities: WindowManagerCapabilities) {
+        self.wm_capabilities = wm_capabilities;
+        self.update(|state| {
+            state.minimizable = wm_capabilities.contains(WindowManagerCapabilities::MINIMIZE);
+            state.maximizable = wm_capabilities.contains(WindowManagerCapabilities::MAXIMIZE);
+        });
+    }
+
+    fn resize(&mut self, width: NonZeroU32, height: NonZeroU32) {
+        if self.surface.is_none() {
+            warn!("trying to resize the hidden frame");
+            return;
+        }
+
+        self.update(|state| state.inner_size = LogicalSize::new(width.get(), height.get()));
+        self.dirty = true;
+        self.should_sync = true;
+    }
+
+    fn set_scaling_factor(&mut self, scale_factor: f64) {
+        self.update(|state| state.scale_factor = scale_factor);
+        self.dirty = true;
+        self.should_sync = true;
+    }
+
+    fn location(&self) -> (i32, i32) {
+        let borders = self.visible_borders();
+        (-(borders.left as i32), -(borders.top as i32))
+    }
+
+    fn subtract_borders(
+        &self,
+        width: NonZeroU32,
+        height: NonZeroU32,
+    ) -> (Option<NonZeroU32>, Option<NonZeroU32>) {
+        let borders = self.visible_borders();
+        (
+            NonZeroU32::new(width.get().saturating_sub(borders.left + borders.right)),
+            NonZeroU32::new(height.get().saturating_sub(borders.top + borders.bottom)),
+        )
+    }
+
+    fn add_borders(&self, width: u32, height: u32) -> (u32, u32) {
+        let borders = self.visible_borders();
+        (width + borders.left + borders.right, height + borders.top + borders.bottom)
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn set_hidden(&mut self, hidden: bool) {
+        if self.is_hidden() == hidden {
+            return;
+        }
+
+        if hidden {
+            self.surface = None;
+        } else {
+            self.surface =
+                Some(FrameSurface::new(&self.parent, &self.subcompositor, &self.queue_handle));
+            self.dirty = true;
+            self.should_sync = true;
+        }
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.surface.is_none()
+    }
+
+    fn set_resizable(&mut self, resizable: bool) {
+        self.update(|state| state.resizable = resizable);
+    }
+
+    fn draw(&mut self) -> bool {
+        let surface = match self.surface.as_ref() {
+            Some(surface) => surface,
+            None => return false,
+        };
+
+        // Reset the dirty bit and sync option.
+        self.dirty = false;
+        let should_sync = mem::take(&mut self.should_sync);
+
+        if self.xdg_state.contains(XdgWindowState::FULLSCREEN) {
+            // Don't draw the decorations for the full screen surface.
+            surface.surface.attach(None, 0, 0);
+            surface.surface.commit();
+            return should_sync;
+        }
+
+        // Fractional scaling is not supported for the frame, so round up.
+        let scale = (self.state.scale_factor.ceil() as u32).max(1);
+        let borders = self.borders;
+        let inner_size = self.state.inner_size;
+        let width = (inner_size.width + borders.left + borders.right) * scale;
+        let height = (inner_size.height + borders.top + borders.bottom) * scale;
+        if width == 0 || height == 0 {
+            return should_sync;
+        }
+
+        let (buffer, pixels) = match self.pool.create_buffer(
+            width as i32,
+            height as i32,
+            width as i32 * 4,
+            wl_shm::Format::Argb8888,
+        ) {
+            Ok((buffer, pixels)) => (buffer, pixels),
+            Err(err) => {
+                warn!("Failed to create the frame buffer: {err}");
+                return should_sync;
+            },
+        };
+
+        pixels.fill(0);
+        let mut canvas = CsdFrameBuffer { pixels: &mut *pixels, width, height, scale };
+        self.frame.draw(&self.state, &mut canvas);
+
+        // Clear the area under the window content, so it doesn't show through the transparent
+        // windows.
+        let content_start = (borders.left * scale) as usize * 4;
+        let content_end = content_start + (inner_size.width * scale) as usize * 4;
+        for row in pixels
+            .chunks_exact_mut(width as usize * 4)
+            .skip((borders.top * scale) as usize)
+            .take((inner_size.height * scale) as usize)
+        {
+            row[content_start..content_end].fill(0);
+        }
+
+        surface.surface.set_buffer_scale(scale as i32);
+        if should_sync {
+            surface.subsurface.set_sync();
+        } else {
+            surface.subsurface.set_desync();
+        }
+
+        // Update the subsurface position.
+        surface.subsurface.set_position(-(borders.left as i32), -(borders.top as i32));
+
+        if let Err(err) = buffer.attach_to(&surface.surface) {
+            warn!("Failed to attach the frame buffer: {err}");
+            return should_sync;
+        }
+
+        if surface.surface.version() >= 4 {
+            surface.surface.damage_buffer(0, 0, i32::MAX, i32::MAX);
+        } else {
+            surface.surface.damage(0, 0, i32::MAX, i32::MAX);
+        }
+
+        surface.surface.commit();
+
+        should_sync
+    }
+
+    fn set_title(&mut self, title: impl Into<String>) {
+        let title = title.into();
+        self.update(|state| state.title = title);
+    }
+}
+
+/// The surface the user frame is drawn on.
+struct FrameSurface {
+    subsurface: WlSubsurface,
+    surface: WlSurface,
+}
+
+impl FrameSurface {
+    fn new(
+        parent: &WlSurface,
+        subcompositor: &SubcompositorState,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Self {
+        let (subsurface, surface) = subcompositor.create_subsurface(parent.clone(), queue_handle);
+        subsurface.set_sync();
+        // The frame covers the whole window, thus keep it below the content.
+        subsurface.place_below(parent);
+        Self { subsurface, surface }
+    }
+}
+
+impl Drop for FrameSurface {
+    fn drop(&mut self) {
+        self.subsurface.destroy();
+        self.surface.destroy();
+    }
+}
+
+impl From<ResizeDirection> for ResizeEdge {
+    fn from(value: ResizeDirection) -> Self {
+        match value {
+            ResizeDirection::North => ResizeEdge::Top,
+            ResizeDirection::West => ResizeEdge::Left,
+            ResizeDirection::NorthWest => ResizeEdge::TopLeft,
+            ResizeDirection::NorthEast => ResizeEdge::TopRight,
+            ResizeDirection::East => ResizeEdge::Right,
+            ResizeDirection::SouthWest => ResizeEdge::BottomLeft,
+            ResizeDirection::SouthEast => ResizeEdge::BottomRight,
+            ResizeDirection::South => ResizeEdge::Bottom,
+        }
+    }
+}
+
+// NOTE: Rust doesn't allow `From<Option<Theme>>`.
+#[cfg(feature = "sctk-adwaita")]
+fn into_sctk_adwaita_config(theme: Option<Theme>) -> sctk_adwaita::FrameConfig {
+    match theme {
+        Some(Theme::Light) => sctk_adwaita::FrameConfig::light(),
+        Some(Theme::Dark) => sctk_adwaita::FrameConfig::dark(),
+        None => sctk_adwaita::FrameConfig::auto(),
+    }
+}
@@ -8,35 +8,46 @@ use sctk::reexports::client::protocol::wl_display::WlDisplay;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Proxy, QueueHandle};
 use sctk::reexports::protocols::xdg::activation::v1::client::xdg_activation_v1::XdgActivationV1;
-use sctk::shell::xdg::window::{Window as SctkWindow, WindowDecorations};
+use sctk::reexports::protocols::xdg::shell::client::xdg_positioner::{
+    Anchor as XdgAnchor, ConstraintAdjustment as XdgConstraintAdjustment, Gravity as XdgGravity,
+};
+use sctk::shell::xdg::popup::Popup;
+use sctk::shell::xdg::window::{DecorationMode, WindowDecorations};
+use sctk::shell::xdg::XdgPositioner;
 use sctk::shell::WaylandSurface;
 use tracing::warn;
 
 use super::event_loop::sink::EventSink;
 use super::output::MonitorHandle;
-use super::state::WinitState;
+use super::seat::RepeatInfo;
+use super::state::{WindowCompositorUpdate, WinitState};
 use super::types::xdg_activation::XdgActivationTokenData;
 use super::{ActiveEventLoop, WaylandError, WindowId};
-use crate::dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Position, Size};
+use crate::dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position, Size};
 use crate::error::{ExternalError, NotSupportedError, OsError as RootOsError};
 use crate::event::{Ime, WindowEvent};
 use crate::event_loop::AsyncRequestSerial;
+use crate::platform_impl::common::modal::ModalGuard;
+use crate::platform_impl::common::xkb::{self, ResetDeadKeys};
 use crate::platform_impl::{
     Fullscreen, MonitorHandle as PlatformMonitorHandle, OsError, PlatformIcon,
 };
 use crate::window::{
-    Cursor, CursorGrabMode, ImePurpose, ResizeDirection, Theme, UserAttentionType,
-    WindowAttributes, WindowButtons, WindowLevel,
+    Cursor, CursorGrabMode, ImePurpose, KeyRepeat, KeyRepeatRate, PopupAnchor, PopupAttributes,
+    ResizeDirection, Theme, UserAttentionType, WindowAttributes, WindowBounds, WindowButtons,
+    WindowLevel, WindowState as WinitWindowState, WmCapabilities,
 };
 
+mod frame;
 pub(crate) mod state;
 
-pub use state::WindowState;
+pub use frame::CsdFrameBuilder;
+pub use state::{ShellSurface, Subsurface, WindowState};
 
 /// The Wayland window.
 pub struct Window {
-    /// Reference to the underlying SCTK window.
-    window: SctkWindow,
+    /// Reference to the underlying SCTK window or popup.
+    window: ShellSurface,
 
     /// Window id.
     window_id: WindowId,
@@ -71,6 +82,12 @@ pub struct Window {
 
     /// The event sink to deliver synthetic events.
     window_events_sink: Arc<Mutex<EventSink>>,
+
+    /// The request to reset the dead keys of the event loop.
+    reset_dead_keys: ResetDeadKeys,
+
+    /// Keeps the parent blocked while the modal window exists.
+    _modal_guard: Option<ModalGuard>,
 }
 
 impl Window {
@@ -83,7 +100,6 @@ impl Window {
 
         let monitors = state.monitors.clone();
 
-        let surface = state.compositor_state.create_surface(&queue_handle);
         let compositor = state.compositor_state.clone();
         let xdg_activation =
             state.xdg_activation.as_ref().map(|activation_state| activation_state.global().clone());
@@ -91,16 +107,46 @@ impl Window {
 
         let size: Size = attributes.inner_size.unwrap_or(LogicalSize::new(800., 600.).into());
 
-        // We prefer server side decorations, however to not have decorations we ask for client
-        // side decorations instead.
-        let default_decorations = if attributes.decorations {
-            WindowDecorations::RequestServer
-        } else {
-            WindowDecorations::RequestClient
+        let decoration_preference = attributes.platform_specific.wayland.decoration_preference;
+        let default_decorations =
+            match state::requested_decoration_mode(decoration_preference, attributes.decorations) {
+                DecorationMode::Server => WindowDecorations::RequestServer,
+                DecorationMode::Client => WindowDecorations::RequestClient,
+            };
+
+        #[cfg(feature = "rwh_06")]
+        let parent_window = match attributes.parent_window.as_ref().map(|handle| handle.0) {
+            Some(rwh_06::RawWindowHandle::Wayland(handle)) => {
+                Some(WindowId(handle.surface.as_ptr() as u64))
+            },
+            Some(raw) => unreachable!("Invalid raw window handle {raw:?} on Wayland"),
+            None => None,
         };
-
-        let window =
-            state.xdg_shell.create_window(surface.clone(), default_decorations, &queue_handle);
+        #[cfg(not(feature = "rwh_06"))]
+        let parent_window: Option<WindowId> = None;
+
+        let mut subsurface_scale_factor = None;
+        let window = match (attributes.popup.as_ref(), parent_window) {
+            (Some(popup), _) => ShellSurface::Popup(create_popup(
+                &state,
+                &queue_handle,
+                state.compositor_state.create_surface(&queue_handle),
+                popup,
+                size,
+            )?),
+            (None, Some(parent_window)) => {
+                let (subsurface, scale_factor) =
+                    create_subsurface(&state, &queue_handle, parent_window, &attributes)?;
+                subsurface_scale_factor = Some(scale_factor);
+                ShellSurface::Subsurface(subsurface)
+            },
+            (None, None) => ShellSurface::Toplevel(state.xdg_shell.create_window(
+                state.compositor_state.create_surface(&queue_handle),
+                default_decorations,
+                &queue_handle,
+            )),
+        };
+        let surface = window.wl_surface().clone();
 
         let mut window_state = WindowState::new(
             event_loop_window_target.connection.clone(),
@@ -111,17 +157,26 @@ impl Window {
             attributes.preferred_theme,
         );
 
+        // Sub-surfaces are never configured, thus use the requested size right away.
+        if let Some(scale_factor) = subsurface_scale_factor {
+            window_state.configure_subsurface(scale_factor);
+        }
+
         // Set transparency hint.
         window_state.set_transparent(attributes.transparent);
 
         window_state.set_blur(attributes.blur);
 
         // Set the decorations hint.
+        window_state.set_decoration_preference(decoration_preference);
+        window_state.set_csd_frame(attributes.platform_specific.wayland.csd_frame.clone());
         window_state.set_decorate(attributes.decorations);
 
         // Set the app_id.
-        if let Some(name) = attributes.platform_specific.name.map(|name| name.general) {
-            window.set_app_id(name);
+        if let (Some(name), Some(toplevel)) =
+            (attributes.platform_specific.name.map(|name| name.general), window.toplevel())
+        {
+            toplevel.set_app_id(name);
         }
 
         // Set the window title.
@@ -137,8 +192,30 @@ impl Window {
         // Non-resizable implies that the min and max sizes are set to the same value.
         window_state.set_resizable(attributes.resizable);
 
+        // Attach the dialog to its parent.
+        let mut modal_guard = None;
+        if let (Some(parent), Some(toplevel)) = (attributes.transient_for, window.toplevel()) {
+            match state.windows.get_mut().get(&parent.0) {
+                Some(parent) => {
+                    if let Some(parent) = parent.lock().unwrap().window.toplevel() {
+                        toplevel.set_parent(Some(parent));
+                    }
+                },
+                None => warn!("The parent of the dialog doesn't exist"),
+            }
+
+            if attributes.modal {
+                if let Some(xdg_dialog_manager) = state.xdg_dialog_manager.as_ref() {
+                    window_state.set_modal(xdg_dialog_manager);
+                }
+
+                modal_guard = Some(state.modal_blocker.block(parent.0));
+            }
+        }
+
         // Set startup mode.
-        match attributes.fullscreen.map(Into::into) {
+        let toplevel = window.toplevel();
+        match attributes.fullscreen.map(Into::into).filter(|_| toplevel.is_some()) {
             Some(Fullscreen::Exclusive(_)) => {
                 warn!("`Fullscreen::Exclusive` is ignored on Wayland");
             },
@@ -150,9 +227,13 @@ impl Window {
                     PlatformMonitorHandle::X(_) => None,
                 });
 
-                window.set_fullscreen(output.as_ref())
+                toplevel.unwrap().set_fullscreen(output.as_ref())
+            },
+            _ if attributes.maximized => {
+                if let Some(toplevel) = toplevel {
+                    toplevel.set_maximized()
+                }
             },
-            _ if attributes.maximized => window.set_maximized(),
             _ => (),
         };
 
@@ -169,13 +250,20 @@ impl Window {
         }
 
         // XXX Do initial commit.
-        window.commit();
+        window.wl_surface().commit();
 
         // Add the window and window requests into the state.
         let window_state = Arc::new(Mutex::new(window_state));
         let window_id = super::make_wid(&surface);
         state.windows.get_mut().insert(window_id, window_state.clone());
 
+        // Deliver the initial size of the sub-surface, like it's done for the configure.
+        if subsurface_scale_factor.is_some() {
+            let mut update = WindowCompositorUpdate::new(window_id);
+            update.resized = true;
+            state.window_compositor_updates.push(update);
+        }
+
         let window_requests = WindowRequests {
             redraw_requested: AtomicBool::new(true),
             closed: AtomicBool::new(false),
@@ -185,6 +273,7 @@ impl Window {
 
         // Setup the event sync to insert `WindowEvents` right from the window.
         let window_events_sink = state.window_events_sink.clone();
+        let reset_dead_keys = state.reset_dead_keys.clone();
 
         let mut wayland_source = event_loop_window_target.wayland_dispatcher.as_source_mut();
         let event_queue = wayland_source.queue();
@@ -218,6 +307,8 @@ impl Window {
             event_loop_awakener,
             window_requests,
             window_events_sink,
+            reset_dead_keys,
+            _modal_guard: modal_guard,
         })
     }
 }
@@ -255,8 +346,41 @@ impl Window {
     }
 
     #[inline]
-    pub fn set_outer_position(&self, _: Position) {
-        // Not possible on Wayland.
+    pub fn set_outer_position(&self, position: Position) {
+        // Only the child windows could be placed, relative to their parent.
+        if let Some(subsurface) = self.window.subsurface() {
+            let scale_factor = self.window_state.lock().unwrap().scale_factor();
+            let position: LogicalPosition<i32> = position.to_logical(scale_factor);
+            subsurface.wl_subsurface().set_position(position.x, position.y);
+        }
+    }
+
+    /// Set whether the commits of the child window are synchronized with its parent.
+    #[inline]
+    pub fn set_subsurface_sync(&self, sync: bool) {
+        if let Some(subsurface) = self.window.subsurface() {
+            if sync {
+                subsurface.wl_subsurface().set_sync();
+            } else {
+                subsurface.wl_subsurface().set_desync();
+            }
+        }
+    }
+
+    /// Place the child window right above the `sibling`.
+    #[inline]
+    pub fn place_subsurface_above(&self, sibling: &Window) {
+        if let Some(subsurface) = self.window.subsurface() {
+            subsurface.wl_subsurface().place_above(sibling.window.wl_surface());
+        }
+    }
+
+    /// Place the child window right below the `sibling`.
+    #[inline]
+    pub fn place_subsurface_below(&self, sibling: &Window) {
+        if let Some(subsurface) = self.window.subsurface() {
+            subsurface.wl_subsurface().place_below(sibling.window.wl_surface());
+        }
     }
 
     #[inline]
@@ -394,6 +518,34 @@ impl Window {
         self.window_state.lock().unwrap().set_blur(blur);
     }
 
+    #[inline]
+    pub fn set_idle_inhibit(&self, inhibit: bool) {
+        self.window_state.lock().unwrap().set_idle_inhibit(inhibit);
+    }
+
+    #[inline]
+    pub fn set_keyboard_shortcuts_inhibited(&self, inhibited: bool) {
+        let mut window_state = self.window_state.lock().unwrap();
+        if window_state.set_keyboard_shortcuts_inhibited(inhibited) {
+            let event = WindowEvent::KeyboardShortcutsInhibited(false);
+            self.window_events_sink.lock().unwrap().push_window_event(event, self.window_id);
+            self.event_loop_awakener.ping();
+        }
+    }
+
+    #[inline]
+    pub fn set_key_repeat(&self, repeat: KeyRepeat) {
+        self.window_state.lock().unwrap().set_key_repeat(repeat);
+    }
+
+    #[inline]
+    pub fn key_repeat_rate(&self) -> Option<KeyRepeatRate> {
+        match self.window_state.lock().unwrap().repeat_info() {
+            RepeatInfo::Repeat { gap, delay } => Some(KeyRepeatRate { delay, interval: gap }),
+            RepeatInfo::Disable => None,
+        }
+    }
+
     #[inline]
     pub fn set_decorations(&self, decorate: bool) {
         self.window_state.lock().unwrap().set_decorate(decorate)
@@ -404,6 +556,11 @@ impl Window {
         self.window_state.lock().unwrap().is_decorated()
     }
 
+    #[inline]
+    pub fn decoration_mode(&self) -> Option<crate::window::DecorationMode> {
+        self.window_state.lock().unwrap().decoration_mode()
+    }
+
     #[inline]
     pub fn set_window_level(&self, _level: WindowLevel) {}
 
@@ -418,7 +575,9 @@ impl Window {
             return;
         }
 
-        self.window.set_minimized();
+        if let Some(window) = self.window.toplevel() {
+            window.set_minimized();
+        }
     }
 
     #[inline]
@@ -432,12 +591,45 @@ impl Window {
             .unwrap_or_default()
     }
 
+    #[inline]
+    pub fn state(&self) -> WinitWindowState {
+        self.window_state.lock().unwrap().state()
+    }
+
+    #[inline]
+    pub fn wm_capabilities(&self) -> Option<WmCapabilities> {
+        self.window_state.lock().unwrap().wm_capabilities()
+    }
+
+    #[inline]
+    pub fn normal_bounds(&self) -> Option<WindowBounds> {
+        let window_state = self.window_state.lock().unwrap();
+        let scale_factor = window_state.scale_factor();
+        let (inner_size, outer_size) = window_state.stateless_size()?;
+        Some(WindowBounds {
+            position: None,
+            inner_size: super::logical_to_physical_rounded(inner_size, scale_factor),
+            outer_size: super::logical_to_physical_rounded(outer_size, scale_factor),
+        })
+    }
+
+    #[inline]
+    pub fn set_normal_bounds(&self, _position: Option<Position>, inner_size: Size) {
+        self.window_state.lock().unwrap().set_stateless_size(inner_size);
+        self.request_redraw();
+    }
+
     #[inline]
     pub fn set_maximized(&self, maximized: bool) {
+        let window = match self.window.toplevel() {
+            Some(window) => window,
+            None => return,
+        };
+
         if maximized {
-            self.window.set_maximized()
+            window.set_maximized()
         } else {
-            self.window.unset_maximized()
+            window.unset_maximized()
         }
     }
 
@@ -462,6 +654,11 @@ impl Window {
 
     #[inline]
     pub(crate) fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        let window = match self.window.toplevel() {
+            Some(window) => window,
+            None => return,
+        };
+
         match fullscreen {
             Some(Fullscreen::Exclusive(_)) => {
                 warn!("`Fullscreen::Exclusive` is ignored on Wayland");
@@ -474,9 +671,9 @@ impl Window {
                     PlatformMonitorHandle::X(_) => None,
                 });
 
-                self.window.set_fullscreen(output.as_ref())
+                window.set_fullscreen(output.as_ref())
             },
-            None => self.window.unset_fullscreen(),
+            None => window.unset_fullscreen(),
         }
     }
 
@@ -521,6 +718,11 @@ impl Window {
         xdg_activation_token.commit();
     }
 
+    #[inline]
+    pub fn reset_dead_keys(&self) {
+        xkb::reset_dead_keys(&self.reset_dead_keys)
+    }
+
     pub fn request_activation_token(&self) -> Result<AsyncRequestSerial, NotSupportedError> {
         let xdg_activation = match self.xdg_activation.as_ref() {
             Some(xdg_activation) => xdg_activation,
@@ -693,6 +895,139 @@ impl Window {
     }
 }
 
+/// Create the `xdg_popup` for the `surface` placed according to the `attributes`.
+fn create_popup(
+    state: &WinitState,
+    queue_handle: &QueueHandle<WinitState>,
+    surface: WlSurface,
+    attributes: &PopupAttributes,
+    size: Size,
+) -> Result<Popup, RootOsError> {
+    let parent = match state.windows.borrow().get(&attributes.parent.0) {
+        Some(parent) => parent.clone(),
+        None => return Err(os_error!(OsError::Misc("the parent of the popup doesn't exist"))),
+    };
+    let parent = parent.lock().unwrap();
+
+    // NOTE: The popup has no scale factor until it's mapped, thus assume that it's the same as
+    // the parent's one.
+    let scale_factor = parent.scale_factor();
+    let size: LogicalSize<i32> = size.to_logical(scale_factor);
+    let anchor_position: LogicalPosition<i32> =
+        attributes.anchor_rect_position.to_logical(scale_factor);
+    let anchor_size: LogicalSize<i32> = attributes.anchor_rect_size.to_logical(scale_factor);
+    let offset: LogicalPosition<i32> = attributes.offset.to_logical(scale_factor);
+
+    // The anchor rectangle is relative to the window geometry, which doesn't start at the
+    // surface origin when the client side decorations are present.
+    let (geometry_x, geometry_y) = parent.geometry_origin();
+
+    let positioner = XdgPositioner::new(&state.xdg_shell)
+        .map_err(|_| os_error!(OsError::Misc("failed to create xdg_positioner")))?;
+    positioner.set_size(size.width.max(1), size.height.max(1));
+    positioner.set_anchor_rect(
+        anchor_position.x - geometry_x,
+        anchor_position.y - geometry_y,
+        anchor_size.width.max(1),
+        anchor_size.height.max(1),
+    );
+    positioner.set_anchor(attributes.anchor.into());
+    positioner.set_gravity(attributes.gravity.into());
+    positioner.set_offset(offset.x, offset.y);
+    positioner.set_constraint_adjustment(XdgConstraintAdjustment::from_bits_truncate(
+        attributes.constraint_adjustment.bits(),
+    ));
+
+    let parent_xdg_surface = parent.window.xdg_surface().ok_or_else(|| {
+        os_error!(OsError::Misc("the parent of the popup must be a toplevel or a popup"))
+    })?;
+
+    let popup = Popup::from_surface(
+        Some(parent_xdg_surface),
+        &positioner,
+        queue_handle,
+        surface,
+        &state.xdg_shell,
+    )
+    .map_err(|_| os_error!(OsError::Misc("failed to create xdg_popup")))?;
+
+    // The grab must be issued before the initial commit of the popup.
+    if attributes.grab {
+        match state.seats.values().find_map(|seat| seat.latest_button_serial()) {
+            Some((seat, serial)) => popup.xdg_popup().grab(&seat, serial),
+            None => warn!("No input serial to grab the popup with"),
+        }
+    }
+
+    Ok(popup)
+}
+
+/// Create the `wl_subsurface` for the child window of the `parent`.
+fn create_subsurface(
+    state: &WinitState,
+    queue_handle: &QueueHandle<WinitState>,
+    parent: WindowId,
+    attributes: &WindowAttributes,
+) -> Result<(Subsurface, f64), RootOsError> {
+    let subcompositor = match state.subcompositor_state.as_ref() {
+        Some(subcompositor) => subcompositor,
+        None => return Err(os_error!(OsError::Misc("wl_subcompositor is not available"))),
+    };
+
+    let parent = match state.windows.borrow().get(&parent) {
+        Some(parent) => parent.clone(),
+        None => return Err(os_error!(OsError::Misc("the parent window doesn't exist"))),
+    };
+    let parent = parent.lock().unwrap();
+    let scale_factor = parent.scale_factor();
+
+    let (subsurface, surface) =
+        subcompositor.create_subsurface(parent.window.wl_surface().clone(), queue_handle);
+
+    if let Some(position) = attributes.position {
+        let position: LogicalPosition<i32> = position.to_logical(scale_factor);
+        subsurface.set_position(position.x, position.y);
+    }
+
+    if !attributes.platform_specific.wayland.subsurface_sync {
+        subsurface.set_desync();
+    }
+
+    Ok((Subsurface::new(subsurface, surface), scale_factor))
+}
+
+impl From<PopupAnchor> for XdgAnchor {
+    fn from(value: PopupAnchor) -> Self {
+        match value {
+            PopupAnchor::Center => XdgAnchor::None,
+            PopupAnchor::Top => XdgAnchor::Top,
+            PopupAnchor::Bottom => XdgAnchor::Bottom,
+            PopupAnchor::Left => XdgAnchor::Left,
+            PopupAnchor::Right => XdgAnchor::Right,
+            PopupAnchor::TopLeft => XdgAnchor::TopLeft,
+            PopupAnchor::BottomLeft => XdgAnchor::BottomLeft,
+            PopupAnchor::TopRight => XdgAnchor::TopRight,
+            PopupAnchor::BottomRight => XdgAnchor::BottomRight,
+        }
+    }
+}
+
+impl From<PopupAnchor> for XdgGravity {
+    fn from(value: PopupAnchor) -> Self {
+        match value {
+            PopupAnchor::Center => XdgGravity::None,
+            PopupAnchor::Top => XdgGravity::Top,
+            PopupAnchor::Bottom => XdgGravity::Bottom,
+            PopupAnchor::Left => XdgGravity::Left,
+            PopupAnchor::Right => XdgGravity::Right,
+            PopupAnchor::TopLeft => XdgGravity::TopLeft,
+            PopupAnchor::BottomLeft => XdgGravity::BottomLeft,
+            PopupAnchor::TopRight => XdgGravity::TopRight,
+            PopupAnchor::BottomRight => XdgGravity::BottomRight,
+        }
+    }
+}
+
 impl Drop for Window {
     fn drop(&mut self) {
         self.window_requests.closed.store(true, Ordering::Relaxed);
@@ -4,21 +4,28 @@ use std::num::NonZeroU32;
 use std::sync::{Arc, Mutex, Weak};
 use std::time::Duration;
 
-use ahash::HashSet;
+use ahash::{HashMap, HashSet};
 use sctk::compositor::{CompositorState, Region, SurfaceData, SurfaceDataExt};
 use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_shm::WlShm;
+use sctk::reexports::client::protocol::wl_subsurface::WlSubsurface;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Connection, Proxy, QueueHandle};
 use sctk::reexports::csd_frame::{
-    DecorationsFrame, FrameAction, FrameClick, ResizeEdge, WindowState as XdgWindowState,
+    DecorationsFrame, FrameAction, FrameClick, ResizeEdge, WindowManagerCapabilities,
+    WindowState as XdgWindowState,
 };
 use sctk::reexports::protocols::wp::fractional_scale::v1::client::wp_fractional_scale_v1::WpFractionalScaleV1;
+use sctk::reexports::protocols::wp::idle_inhibit::zv1::client::zwp_idle_inhibitor_v1::ZwpIdleInhibitorV1;
+use sctk::reexports::protocols::wp::keyboard_shortcuts_inhibit::zv1::client::zwp_keyboard_shortcuts_inhibitor_v1::ZwpKeyboardShortcutsInhibitorV1;
 use sctk::reexports::protocols::wp::text_input::zv3::client::zwp_text_input_v3::ZwpTextInputV3;
 use sctk::reexports::protocols::wp::viewporter::client::wp_viewport::WpViewport;
+use sctk::reexports::protocols::xdg::dialog::v1::client::xdg_dialog_v1::XdgDialogV1;
+use sctk::reexports::protocols::xdg::shell::client::xdg_surface::XdgSurface as XdgSurfaceProxy;
 use sctk::reexports::protocols::xdg::shell::client::xdg_toplevel::ResizeEdge as XdgResizeEdge;
 use sctk::seat::pointer::{PointerDataExt, ThemedPointer};
+use sctk::shell::xdg::popup::{Popup, PopupConfigure};
 use sctk::shell::xdg::window::{DecorationMode, Window, WindowConfigure};
 use sctk::shell::xdg::XdgSurface;
 use sctk::shell::WaylandSurface;
@@ -31,24 +38,112 @@ use wayland_protocols_plasma::blur::client::org_kde_kwin_blur::OrgKdeKwinBlur;
 use crate::cursor::CustomCursor as RootCustomCursor;
 use crate::dpi::{LogicalPosition, LogicalSize, PhysicalSize, Size};
 use crate::error::{ExternalError, NotSupportedError};
+use crate::platform::wayland::DecorationPreference;
 use crate::platform_impl::wayland::logical_to_physical_rounded;
 use crate::platform_impl::wayland::seat::{
-    PointerConstraintsState, WinitPointerData, WinitPointerDataExt, ZwpTextInputV3Ext,
+    PointerConstraintsState, RepeatInfo, WinitPointerData, WinitPointerDataExt, ZwpTextInputV3Ext,
 };
 use crate::platform_impl::wayland::state::{WindowCompositorUpdate, WinitState};
 use crate::platform_impl::wayland::types::cursor::{CustomCursor, SelectedCursor};
 use crate::platform_impl::wayland::types::kwin_blur::KWinBlurManager;
+use crate::platform_impl::wayland::types::wp_idle_inhibit::IdleInhibitManager;
+use crate::platform_impl::wayland::types::wp_keyboard_shortcuts_inhibit::{
+    KeyboardShortcutsInhibitManager, KeyboardShortcutsInhibitorData,
+};
+use crate::platform_impl::wayland::types::xdg_dialog::XdgDialogManager;
+use crate::platform_impl::wayland::window::frame::{CsdFrameBuilder, WinitFrame};
 use crate::platform_impl::{PlatformCustomCursor, WindowId};
-use crate::window::{CursorGrabMode, CursorIcon, ImePurpose, ResizeDirection, Theme};
-
-#[cfg(feature = "sctk-adwaita")]
-pub type WinitFrame = sctk_adwaita::AdwaitaFrame<WinitState>;
-#[cfg(not(feature = "sctk-adwaita"))]
-pub type WinitFrame = sctk::shell::xdg::fallback_frame::FallbackFrame<WinitState>;
+use crate::window::{
+    CursorGrabMode, CursorIcon, DecorationMode as WinitDecorationMode, ImePurpose, KeyRepeat,
+    ResizeDirection, Theme, WindowState as WinitWindowState, WmCapabilities,
+};
 
 // Minimum window inner size.
 const MIN_WINDOW_SIZE: LogicalSize<u32> = LogicalSize::new(2, 1);
 
+/// The role of the window surface.
+#[derive(Debug, Clone)]
+pub enum ShellSurface {
+    /// The regular top-level window.
+    Toplevel(Window),
+
+    /// The popup attached to another window.
+    Popup(Popup),
+
+    /// The child window embedded into another window.
+    Subsurface(Subsurface),
+}
+
+impl ShellSurface {
+    /// The top-level window, if this surface is one.
+    #[inline]
+    pub fn toplevel(&self) -> Option<&Window> {
+        match self {
+            Self::Toplevel(window) => Some(window),
+            Self::Popup(_) | Self::Subsurface(_) => None,
+        }
+    }
+
+    /// The sub-surface, if this surface is one.
+    #[inline]
+    pub fn subsurface(&self) -> Option<&Subsurface> {
+        match self {
+            Self::Subsurface(subsurface) => Some(subsurface),
+            Self::Toplevel(_) | Self::Popup(_) => None,
+        }
+    }
+
+    /// The `xdg_surface` of the window, sub-surfaces don't have one.
+    #[inline]
+    pub fn xdg_surface(&self) -> Option<&XdgSurfaceProxy> {
+        match self {
+            Self::Toplevel(window) => Some(window.xdg_surface()),
+            Self::Popup(popup) => Some(popup.xdg_surface()),
+            Self::Subsurface(_) => None,
+        }
+    }
+}
+
+impl WaylandSurface for ShellSurface {
+    fn wl_surface(&self) -> &WlSurface {
+        match self {
+            Self::Toplevel(window) => window.wl_surface(),
+            Self::Popup(popup) => popup.wl_surface(),
+            Self::Subsurface(subsurface) => &subsurface.inner.surface,
+        }
+    }
+}
+
+/// The `wl_subsurface` along with its surface, destroyed once the last clone is dropped.
+#[derive(Debug, Clone)]
+pub struct Subsurface {
+    inner: Arc<SubsurfaceInner>,
+}
+
+impl Subsurface {
+    pub fn new(subsurface: WlSubsurface, surface: WlSurface) -> Self {
+        Self { inner: Arc::new(SubsurfaceInner { subsurface, surface }) }
+    }
+
+    #[inline]
+    pub fn wl_subsurface(&self) -> &WlSubsurface {
+        &self.inner.subsurface
+    }
+}
+
+#[derive(Debug)]
+struct SubsurfaceInner {
+    subsurface: WlSubsurface,
+    surface: WlSurface,
+}
+
+impl Drop for SubsurfaceInner {
+    fn drop(&mut self) {
+        self.subsurface.destroy();
+        self.surface.destroy();
+    }
+}
+
 /// The state of the window which is being updated from the [`WinitState`].
 pub struct WindowState {
     /// The connection to Wayland server.
@@ -63,6 +158,9 @@ pub struct WindowState {
     /// The last received configure.
     pub last_configure: Option<WindowConfigure>,
 
+    /// The last received configure for the popup.
+    last_popup_configure: Option<PopupConfigure>,
+
     /// The pointers observed on the window.
     pub pointers: Vec<Weak<ThemedPointer<WinitPointerData>>>,
 
@@ -109,6 +207,12 @@ pub struct WindowState {
     /// The current IME purpose.
     ime_purpose: ImePurpose,
 
+    /// The key repeat policy of the window.
+    key_repeat: KeyRepeat,
+
+    /// The repeat rate of the last keyboard which focused the window.
+    seat_repeat_info: RepeatInfo,
+
     /// The text inputs observed on the window.
     text_inputs: Vec<ZwpTextInputV3>,
 
@@ -121,6 +225,12 @@ pub struct WindowState {
     /// Whether we should decorate the frame.
     decorate: bool,
 
+    /// The preferred way of decorating the window.
+    decoration_preference: DecorationPreference,
+
+    /// The builder of the frame provided by the user.
+    csd_frame: Option<CsdFrameBuilder>,
+
     /// Min size.
     min_inner_size: LogicalSize<u32>,
     max_inner_size: Option<LogicalSize<u32>>,
@@ -141,14 +251,25 @@ pub struct WindowState {
     fractional_scale: Option<WpFractionalScaleV1>,
     blur: Option<OrgKdeKwinBlur>,
     blur_manager: Option<KWinBlurManager>,
+    idle_inhibitor: Option<ZwpIdleInhibitorV1>,
+    idle_inhibit_manager: Option<IdleInhibitManager>,
+
+    /// Whether the keyboard shortcuts inhibition was requested.
+    keyboard_shortcuts_inhibited: bool,
+    /// The keyboard shortcuts inhibitors for the seats that have focused the window.
+    keyboard_shortcuts_inhibitors: HashMap<ObjectId, ZwpKeyboardShortcutsInhibitorV1>,
+    keyboard_shortcuts_inhibit_manager: Option<KeyboardShortcutsInhibitManager>,
+
+    /// The dialog object of the modal window.
+    xdg_dialog: Option<XdgDialogV1>,
 
     /// Whether the client side decorations have pending move operations.
     ///
     /// The value is the serial of the event triggered moved.
     has_pending_move: Option<u32>,
 
-    /// The underlying SCTK window.
-    pub window: Window,
+    /// The underlying SCTK window or popup.
+    pub window: ShellSurface,
 
     // NOTE: The spec says that destroying parent(`window` in our case), will unmap the
     // subsurfaces. Thus to achieve atomic unmap of the client, drop the decorations
@@ -165,7 +286,7 @@ impl WindowState {
         queue_handle: &QueueHandle<WinitState>,
         winit_state: &WinitState,
         initial_size: Size,
-        window: Window,
+        window: ShellSurface,
         theme: Option<Theme>,
     ) -> Self {
         let compositor = winit_state.compositor_state.clone();
@@ -182,6 +303,14 @@ impl WindowState {
         Self {
             blur: None,
             blur_manager: winit_state.kwin_blur_manager.clone(),
+            idle_inhibitor: None,
+            idle_inhibit_manager: winit_state.idle_inhibit_manager.clone(),
+            keyboard_shortcuts_inhibited: false,
+            keyboard_shortcuts_inhibitors: Default::default(),
+            keyboard_shortcuts_inhibit_manager: winit_state
+                .keyboard_shortcuts_inhibit_manager
+                .clone(),
+            xdg_dialog: None,
             compositor,
             connection,
             csd_fails: false,
@@ -189,14 +318,19 @@ impl WindowState {
             selected_cursor: Default::default(),
             cursor_visible: true,
             decorate: true,
+            decoration_preference: Default::default(),
+            csd_frame: None,
             fractional_scale,
             frame: None,
             frame_callback_state: FrameCallbackState::None,
             seat_focus: Default::default(),
             has_pending_move: None,
             ime_allowed: false,
+            key_repeat: KeyRepeat::System,
+            seat_repeat_info: RepeatInfo::default(),
             ime_purpose: ImePurpose::Normal,
             last_configure: None,
+            last_popup_configure: None,
             max_inner_size: None,
             min_inner_size: MIN_WINDOW_SIZE,
             pointer_constraints,
@@ -270,20 +404,31 @@ impl WindowState {
             self.stateless_size = self.size;
         }
 
+        let window = match &self.window {
+            ShellSurface::Toplevel(window) => window,
+            ShellSurface::Popup(_) | ShellSurface::Subsurface(_) => return false,
+        };
+
+        let decoration_mode_changed = self
+            .last_configure
+            .as_ref()
+            .is_some_and(|last| last.decoration_mode != configure.decoration_mode);
+
         if let Some(subcompositor) = subcompositor.as_ref().filter(|_| {
             configure.decoration_mode == DecorationMode::Client
                 && self.frame.is_none()
                 && !self.csd_fails
+                && self.may_draw_frame()
         }) {
             match WinitFrame::new(
-                &self.window,
+                self.csd_frame.as_ref(),
+                window,
                 shm,
                 #[cfg(feature = "sctk-adwaita")]
                 self.compositor.clone(),
                 subcompositor.clone(),
                 self.queue_handle.clone(),
-                #[cfg(feature = "sctk-adwaita")]
-                into_sctk_adwaita_config(self.theme),
+                self.theme,
             ) {
                 Ok(mut frame) => {
                     frame.set_title(&self.title);
@@ -353,7 +498,10 @@ impl WindowState {
         // NOTE: Set the configure before doing a resize, since we query it during it.
         self.last_configure = Some(configure);
 
-        if state_change_requires_resize || new_size != self.inner_size() {
+        // NOTE: The frame was either created or dropped when the decoration mode changes, thus
+        // the window geometry must be updated even if the size stays the same.
+        if state_change_requires_resize || decoration_mode_changed || new_size != self.inner_size()
+        {
             self.resize(new_size);
             true
         } else {
@@ -361,6 +509,39 @@ impl WindowState {
         }
     }
 
+    /// Apply the configure sent to the popup, returning whether the popup was resized.
+    pub fn configure_popup(&mut self, configure: PopupConfigure) -> bool {
+        if let Some(initial_size) = self.initial_size.take() {
+            self.size = initial_size.to_logical(self.scale_factor());
+            self.stateless_size = self.size;
+        }
+
+        // NOTE: The popup is not allowed to pick a different size, since the compositor has
+        // already placed it with that size in mind.
+        let new_size =
+            LogicalSize::new(configure.width.max(1) as u32, configure.height.max(1) as u32);
+        let initial_configure = self.last_popup_configure.is_none();
+        self.last_popup_configure = Some(configure);
+
+        if initial_configure || new_size != self.inner_size() {
+            self.resize(new_size);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Apply the initial size to the sub-surface, since the compositor never configures it.
+    pub fn configure_subsurface(&mut self, scale_factor: f64) {
+        self.set_scale_factor(scale_factor);
+        if let Some(initial_size) = self.initial_size.take() {
+            self.size = initial_size.to_logical(scale_factor);
+            self.stateless_size = self.size;
+        }
+
+        self.resize(self.size);
+    }
+
     /// Compute the bounds for the inner size of the surface.
     fn inner_size_bounds(
         &self,
@@ -389,7 +570,10 @@ impl WindowState {
 
     /// Start interacting drag resize.
     pub fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), ExternalError> {
-        let xdg_toplevel = self.window.xdg_toplevel();
+        let xdg_toplevel = match self.window.toplevel() {
+            Some(window) => window.xdg_toplevel(),
+            None => return Err(ExternalError::NotSupported(NotSupportedError::new())),
+        };
 
         // TODO(kchibisov) handle touch serials.
         self.apply_on_pointer(|_, data| {
@@ -403,7 +587,10 @@ impl WindowState {
 
     /// Start the window drag.
     pub fn drag_window(&self) -> Result<(), ExternalError> {
-        let xdg_toplevel = self.window.xdg_toplevel();
+        let xdg_toplevel = match self.window.toplevel() {
+            Some(window) => window.xdg_toplevel(),
+            None => return Err(ExternalError::NotSupported(NotSupportedError::new())),
+        };
         // TODO(kchibisov) handle touch serials.
         self.apply_on_pointer(|_, data| {
             let serial = data.latest_button_serial();
@@ -426,10 +613,11 @@ impl WindowState {
         window_id: WindowId,
         updates: &mut Vec<WindowCompositorUpdate>,
     ) -> Option<bool> {
+        let window = self.window.toplevel()?;
         match self.frame.as_mut()?.on_click(timestamp, click, pressed)? {
-            FrameAction::Minimize => self.window.set_minimized(),
-            FrameAction::Maximize => self.window.set_maximized(),
-            FrameAction::UnMaximize => self.window.unset_maximized(),
+            FrameAction::Minimize => window.set_minimized(),
+            FrameAction::Maximize => window.set_maximized(),
+            FrameAction::UnMaximize => window.unset_maximized(),
             FrameAction::Close => WinitState::queue_close(updates, window_id),
             FrameAction::Move => self.has_pending_move = Some(serial),
             FrameAction::Resize(edge) => {
@@ -445,9 +633,9 @@ impl WindowState {
                     ResizeEdge::BottomRight => XdgResizeEdge::BottomRight,
                     _ => return None,
                 };
-                self.window.resize(seat, serial, edge);
+                window.resize(seat, serial, edge);
             },
-            FrameAction::ShowMenu(x, y) => self.window.show_window_menu(seat, serial, (x, y)),
+            FrameAction::ShowMenu(x, y) => window.show_window_menu(seat, serial, (x, y)),
             _ => (),
         };
 
@@ -472,12 +660,12 @@ impl WindowState {
         // Take the serial if we had any, so it doesn't stick around.
         let serial = self.has_pending_move.take();
 
-        if let Some(frame) = self.frame.as_mut() {
+        if let (Some(frame), Some(window)) = (self.frame.as_mut(), self.window.toplevel()) {
             let cursor = frame.click_point_moved(timestamp, &surface.id(), x, y);
             // If we have a cursor change, that means that cursor is over the decorations,
             // so try to apply move.
             if let Some(serial) = cursor.is_some().then_some(serial).flatten() {
-                self.window.move_(seat, serial);
+                window.move_(seat, serial);
                 None
             } else {
                 cursor
@@ -525,6 +713,27 @@ impl WindowState {
         !self.seat_focus.is_empty()
     }
 
+    /// Set the key repeat policy of the window.
+    #[inline]
+    pub fn set_key_repeat(&mut self, repeat: KeyRepeat) {
+        self.key_repeat = repeat;
+    }
+
+    /// Set the repeat rate of the keyboard focusing the window.
+    #[inline]
+    pub fn set_seat_repeat_info(&mut self, repeat_info: RepeatInfo) {
+        self.seat_repeat_info = repeat_info;
+    }
+
+    /// The repeat rate in effect for the window, accounting for its key repeat policy.
+    pub fn repeat_info(&self) -> RepeatInfo {
+        match self.key_repeat {
+            KeyRepeat::System => self.seat_repeat_info,
+            KeyRepeat::Disabled => RepeatInfo::Disable,
+            KeyRepeat::Custom(rate) => RepeatInfo::Repeat { gap: rate.interval, delay: rate.delay },
+        }
+    }
+
     /// Whether the IME is allowed.
     #[inline]
     pub fn ime_allowed(&self) -> bool {
@@ -541,6 +750,8 @@ impl WindowState {
     #[inline]
     pub fn is_configured(&self) -> bool {
         self.last_configure.is_some()
+            || self.last_popup_configure.is_some()
+            || self.window.subsurface().is_some()
     }
 
     #[inline]
@@ -550,14 +761,85 @@ impl WindowState {
             .as_ref()
             .map(|configure| configure.decoration_mode == DecorationMode::Client)
             .unwrap_or(false);
-        if let Some(frame) = csd.then_some(self.frame.as_ref()).flatten() {
-            !frame.is_hidden()
+        if csd {
+            // Client side decorations, which we may not draw at all.
+            self.frame.as_ref().is_some_and(|frame| !frame.is_hidden())
         } else {
             // Server side decorations.
             true
         }
     }
 
+    /// The decoration mode negotiated with the compositor.
+    #[inline]
+    pub fn decoration_mode(&self) -> Option<WinitDecorationMode> {
+        self.last_configure.as_ref().map(|configure| match configure.decoration_mode {
+            DecorationMode::Client => WinitDecorationMode::Client,
+            DecorationMode::Server => WinitDecorationMode::Server,
+        })
+    }
+
+    /// The states of the top-level exposed to the user.
+    pub fn state(&self) -> WinitWindowState {
+        let state = match self.last_configure.as_ref() {
+            Some(configure) => configure.state,
+            None => return WinitWindowState::empty(),
+        };
+
+        let mut winit_state = WinitWindowState::empty();
+        for (xdg_state, flag) in [
+            (XdgWindowState::TILED_LEFT, WinitWindowState::TILED_LEFT),
+            (XdgWindowState::TILED_RIGHT, WinitWindowState::TILED_RIGHT),
+            (XdgWindowState::TILED_TOP, WinitWindowState::TILED_TOP),
+            (XdgWindowState::TILED_BOTTOM, WinitWindowState::TILED_BOTTOM),
+            (XdgWindowState::SUSPENDED, WinitWindowState::SUSPENDED),
+        ] {
+            winit_state.set(flag, state.contains(xdg_state));
+        }
+        winit_state
+    }
+
+    /// The window management actions supported by the compositor.
+    pub fn wm_capabilities(&self) -> Option<WmCapabilities> {
+        let capabilities = self.last_configure.as_ref()?.capabilities;
+
+        let mut wm_capabilities = WmCapabilities::empty();
+        for (xdg_capability, flag) in [
+            (WindowManagerCapabilities::WINDOW_MENU, WmCapabilities::WINDOW_MENU),
+            (WindowManagerCapabilities::MAXIMIZE, WmCapabilities::MAXIMIZE),
+            (WindowManagerCapabilities::FULLSCREEN, WmCapabilities::FULLSCREEN),
+            (WindowManagerCapabilities::MINIMIZE, WmCapabilities::MINIMIZE),
+        ] {
+            wm_capabilities.set(flag, capabilities.contains(xdg_capability));
+        }
+        Some(wm_capabilities)
+    }
+
+    /// Set the preferred way of decorating the window.
+    ///
+    /// Must be set before the window gets its initial configure.
+    #[inline]
+    pub fn set_decoration_preference(&mut self, preference: DecorationPreference) {
+        self.decoration_preference = preference;
+    }
+
+    /// Set the builder of the client side decorations frame provided by the user.
+    ///
+    /// Must be set before the window gets its initial configure.
+    #[inline]
+    pub fn set_csd_frame(&mut self, csd_frame: Option<CsdFrameBuilder>) {
+        self.csd_frame = csd_frame;
+    }
+
+    /// Whether winit is allowed to draw its own frame in the client side decorations mode.
+    #[inline]
+    fn may_draw_frame(&self) -> bool {
+        matches!(
+            self.decoration_preference,
+            DecorationPreference::Auto | DecorationPreference::ClientOnly
+        )
+    }
+
     /// Get the outer size of the window.
     #[inline]
     pub fn outer_size(&self) -> LogicalSize<u32> {
@@ -627,9 +909,43 @@ impl WindowState {
         }
     }
 
+    /// Get the inner and outer sizes of the top-level when no states are applied to it.
+    pub fn stateless_size(&self) -> Option<(LogicalSize<u32>, LogicalSize<u32>)> {
+        self.window.toplevel()?;
+        let size = self.stateless_size;
+        let outer_size = self
+            .frame
+            .as_ref()
+            .map(|frame| frame.add_borders(size.width, size.height).into())
+            .unwrap_or(size);
+        Some((size, outer_size))
+    }
+
+    /// Set the size the top-level is restored to once no states are applied to it.
+    pub fn set_stateless_size(&mut self, inner_size: Size) {
+        if self.window.toplevel().is_none() {
+            return;
+        }
+
+        // NOTE: The initial size is applied on the first configure.
+        if self.initial_size.is_some() {
+            self.initial_size = Some(inner_size);
+            return;
+        }
+
+        let inner_size = inner_size.to_logical(self.scale_factor());
+        if self.last_configure.as_ref().map(Self::is_stateless).unwrap_or(true) {
+            self.resize(inner_size);
+        } else {
+            self.stateless_size = inner_size;
+        }
+    }
+
     /// Try to resize the window when the user can do so.
     pub fn request_inner_size(&mut self, inner_size: Size) -> PhysicalSize<u32> {
-        if self.last_configure.as_ref().map(Self::is_stateless).unwrap_or(true) {
+        // NOTE: The size of the popup is picked by the compositor when placing it.
+        let is_popup = matches!(self.window, ShellSurface::Popup(_));
+        if !is_popup && self.last_configure.as_ref().map(Self::is_stateless).unwrap_or(true) {
             self.resize(inner_size.to_logical(self.scale_factor()))
         }
 
@@ -664,12 +980,14 @@ impl WindowState {
         self.reload_transparency_hint();
 
         // Set the window geometry.
-        self.window.xdg_surface().set_window_geometry(
-            x,
-            y,
-            outer_size.width as i32,
-            outer_size.height as i32,
-        );
+        if let Some(xdg_surface) = self.window.xdg_surface() {
+            xdg_surface.set_window_geometry(
+                x,
+                y,
+                outer_size.width as i32,
+                outer_size.height as i32,
+            );
+        }
 
         // Update the target viewport, this is used if and only if fractional scaling is in use.
         if let Some(viewport) = self.viewport.as_ref() {
@@ -678,6 +996,12 @@ impl WindowState {
         }
     }
 
+    /// Get the origin of the window geometry relative to the surface.
+    #[inline]
+    pub fn geometry_origin(&self) -> (i32, i32) {
+        self.frame.as_ref().map(|frame| frame.location()).unwrap_or((0, 0))
+    }
+
     /// Get the scale factor of the window.
     #[inline]
     pub fn scale_factor(&self) -> f64 {
@@ -767,7 +1091,9 @@ impl WindowState {
             .unwrap_or(size);
 
         self.min_inner_size = size;
-        self.window.set_min_size(Some(size.into()));
+        if let Some(window) = self.window.toplevel() {
+            window.set_min_size(Some(size.into()));
+        }
     }
 
     /// Set maximum inner window size.
@@ -780,15 +1106,16 @@ impl WindowState {
         });
 
         self.max_inner_size = size;
-        self.window.set_max_size(size.map(Into::into));
+        if let Some(window) = self.window.toplevel() {
+            window.set_max_size(size.map(Into::into));
+        }
     }
 
     /// Set the CSD theme.
     pub fn set_theme(&mut self, theme: Option<Theme>) {
         self.theme = theme;
-        #[cfg(feature = "sctk-adwaita")]
         if let Some(frame) = self.frame.as_mut() {
-            frame.set_config(into_sctk_adwaita_config(theme))
+            frame.set_theme(theme)
         }
     }
 
@@ -856,11 +1183,16 @@ impl WindowState {
     }
 
     pub fn show_window_menu(&self, position: LogicalPosition<u32>) {
+        let window = match self.window.toplevel() {
+            Some(window) => window,
+            None => return,
+        };
+
         // TODO(kchibisov) handle touch serials.
         self.apply_on_pointer(|_, data| {
             let serial = data.latest_button_serial();
             let seat = data.seat();
-            self.window.show_window_menu(seat, serial, position.into());
+            window.show_window_menu(seat, serial, position.into());
         });
     }
 
@@ -911,13 +1243,14 @@ impl WindowState {
 
         self.decorate = decorate;
 
-        match self.last_configure.as_ref().map(|configure| configure.decoration_mode) {
-            Some(DecorationMode::Server) if !self.decorate => {
-                // To disable decorations we should request client and hide the frame.
-                self.window.request_decoration_mode(Some(DecorationMode::Client))
-            },
-            _ if self.decorate => self.window.request_decoration_mode(Some(DecorationMode::Server)),
-            _ => (),
+        let window = match self.window.toplevel() {
+            Some(window) => window,
+            None => return,
+        };
+
+        let mode = requested_decoration_mode(self.decoration_preference, decorate);
+        if self.last_configure.as_ref().map(|configure| configure.decoration_mode) != Some(mode) {
+            window.request_decoration_mode(Some(mode));
         }
 
         if let Some(frame) = self.frame.as_mut() {
@@ -930,7 +1263,8 @@ impl WindowState {
     /// Add seat focus for the window.
     #[inline]
     pub fn add_seat_focus(&mut self, seat: ObjectId) {
-        self.seat_focus.insert(seat);
+        self.seat_focus.insert(seat.clone());
+        self.inhibit_keyboard_shortcuts(seat);
     }
 
     /// Remove seat focus from the window.
@@ -1001,6 +1335,15 @@ impl WindowState {
         }
     }
 
+    /// Mark the window as modal for its parent.
+    pub fn set_modal(&mut self, xdg_dialog_manager: &XdgDialogManager) {
+        if let (Some(window), None) = (self.window.toplevel(), self.xdg_dialog.as_ref()) {
+            let xdg_dialog = xdg_dialog_manager.dialog(window.xdg_toplevel(), &self.queue_handle);
+            xdg_dialog.set_modal();
+            self.xdg_dialog = Some(xdg_dialog);
+        }
+    }
+
     /// Make window background blurred
     #[inline]
     pub fn set_blur(&mut self, blurred: bool) {
@@ -1018,6 +1361,82 @@ impl WindowState {
         }
     }
 
+    /// Prevent the system from going idle while the surface is visible.
+    #[inline]
+    pub fn set_idle_inhibit(&mut self, inhibit: bool) {
+        if inhibit && self.idle_inhibitor.is_none() {
+            if let Some(idle_inhibit_manager) = self.idle_inhibit_manager.as_ref() {
+                self.idle_inhibitor = Some(
+                    idle_inhibit_manager.inhibit(self.window.wl_surface(), &self.queue_handle),
+                );
+            } else {
+                info!("Idle inhibit manager unavailable, unable to inhibit idle")
+            }
+        } else if !inhibit {
+            if let Some(idle_inhibitor) = self.idle_inhibitor.take() {
+                idle_inhibitor.destroy();
+            }
+        }
+    }
+
+    /// Request the compositor to deliver its keyboard shortcuts to the surface.
+    ///
+    /// Returns `true` when an active inhibitor was destroyed, since the compositor doesn't report
+    /// its deactivation.
+    pub fn set_keyboard_shortcuts_inhibited(&mut self, inhibited: bool) -> bool {
+        self.keyboard_shortcuts_inhibited = inhibited;
+        if inhibited {
+            if self.keyboard_shortcuts_inhibit_manager.is_none() {
+                info!(
+                    "Keyboard shortcuts inhibit manager unavailable, unable to inhibit shortcuts"
+                );
+                return false;
+            }
+
+            let seats = self.seat_focus.iter().cloned().collect::<Vec<_>>();
+            for seat in seats {
+                self.inhibit_keyboard_shortcuts(seat);
+            }
+
+            false
+        } else {
+            let mut was_active = false;
+            for (_, inhibitor) in self.keyboard_shortcuts_inhibitors.drain() {
+                let data = inhibitor.data::<KeyboardShortcutsInhibitorData>();
+                was_active |= data.is_some_and(|data| data.is_active());
+                inhibitor.destroy();
+            }
+
+            was_active
+        }
+    }
+
+    /// Create the keyboard shortcuts inhibitor for the seat, if requested.
+    ///
+    /// The inhibitor is kept around after the focus is lost, since the compositor takes care
+    /// of activating it only while the surface is focused.
+    fn inhibit_keyboard_shortcuts(&mut self, seat: ObjectId) {
+        let manager = match self.keyboard_shortcuts_inhibit_manager.as_ref() {
+            Some(manager) if self.keyboard_shortcuts_inhibited => manager,
+            _ => return,
+        };
+
+        if self.keyboard_shortcuts_inhibitors.contains_key(&seat) {
+            return;
+        }
+
+        let seat_proxy = match WlSeat::from_id(&self.connection, seat.clone()) {
+            Ok(seat) => seat,
+            Err(_) => return,
+        };
+
+        let surface = self.window.wl_surface();
+        let window_id = crate::platform_impl::wayland::make_wid(surface);
+        let inhibitor =
+            manager.inhibit_shortcuts(surface, &seat_proxy, &self.queue_handle, window_id);
+        self.keyboard_shortcuts_inhibitors.insert(seat, inhibitor);
+    }
+
     /// Set the window title to a new value.
     ///
     /// This will automatically truncate the title to something meaningful.
@@ -1037,7 +1456,9 @@ impl WindowState {
             frame.set_title(&title);
         }
 
-        self.window.set_title(&title);
+        if let Some(window) = self.window.toplevel() {
+            window.set_title(&title);
+        }
         self.title = title;
     }
 
@@ -1081,6 +1502,18 @@ impl Drop for WindowState {
             fs.destroy();
         }
 
+        if let Some(idle_inhibitor) = self.idle_inhibitor.take() {
+            idle_inhibitor.destroy();
+        }
+
+        for (_, inhibitor) in self.keyboard_shortcuts_inhibitors.drain() {
+            inhibitor.destroy();
+        }
+
+        if let Some(xdg_dialog) = self.xdg_dialog.take() {
+            xdg_dialog.destroy();
+        }
+
         if let Some(viewport) = self.viewport.take() {
             viewport.destroy();
         }
@@ -1133,12 +1566,16 @@ impl From<ResizeDirection> for XdgResizeEdge {
     }
 }
 
-// NOTE: Rust doesn't allow `From<Option<Theme>>`.
-#[cfg(feature = "sctk-adwaita")]
-fn into_sctk_adwaita_config(theme: Option<Theme>) -> sctk_adwaita::FrameConfig {
-    match theme {
-        Some(Theme::Light) => sctk_adwaita::FrameConfig::light(),
-        Some(Theme::Dark) => sctk_adwaita::FrameConfig::dark(),
-        None => sctk_adwaita::FrameConfig::auto(),
+/// The decoration mode to request from the compositor for the given preference.
+pub fn requested_decoration_mode(
+    preference: DecorationPreference,
+    decorate: bool,
+) -> DecorationMode {
+    match preference {
+        DecorationPreference::Auto | DecorationPreference::ServerOnly if decorate => {
+            DecorationMode::Server
+        },
+        // NOTE: To not have decorations we ask for client side decorations and hide the frame.
+        _ => DecorationMode::Client,
     }
 }
@@ -57,8 +57,16 @@ atom_manager! {
     _NET_WM_STATE_HIDDEN,
     _NET_WM_STATE_MAXIMIZED_HORZ,
     _NET_WM_STATE_MAXIMIZED_VERT,
+    _NET_WM_STATE_MODAL,
     _NET_WM_WINDOW_TYPE,
 
+    // Allowed actions atoms.
+    _NET_WM_ALLOWED_ACTIONS,
+    _NET_WM_ACTION_FULLSCREEN,
+    _NET_WM_ACTION_MAXIMIZE_HORZ,
+    _NET_WM_ACTION_MAXIMIZE_VERT,
+    _NET_WM_ACTION_MINIMIZE,
+
     // Activation atoms.
     _NET_STARTUP_INFO_BEGIN,
     _NET_STARTUP_INFO,
@@ -94,14 +102,28 @@ atom_manager! {
     TextUriList: b"text/uri-list",
     None: b"None",
 
+    // Input Device Property Atoms
+    DeviceProductId: b"Device Product ID",
+    LibinputNaturalScrollingEnabled: b"libinput Natural Scrolling Enabled",
+    LibinputScrollMethodEnabled: b"libinput Scroll Method Enabled",
+    LibinputTappingEnabled: b"libinput Tapping Enabled",
+    SynapticsTapAction: b"Synaptics Tap Action",
+
+    // Valuator Label Atoms
+    AbsMtTouchMajor: b"Abs MT Touch Major",
+    AbsMtTouchMinor: b"Abs MT Touch Minor",
+    AbsMtOrientation: b"Abs MT Orientation",
+
     // Miscellaneous Atoms
     _GTK_THEME_VARIANT,
     _MOTIF_WM_HINTS,
     _NET_ACTIVE_WINDOW,
     _NET_CLIENT_LIST,
+    _NET_CURRENT_DESKTOP,
     _NET_FRAME_EXTENTS,
     _NET_SUPPORTED,
     _NET_SUPPORTING_WM_CHECK,
+    _NET_WORKAREA,
     _XEMBED,
     _XSETTINGS_SETTINGS
 }
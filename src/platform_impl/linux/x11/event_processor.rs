@@ -1,12 +1,14 @@
 use std::cell::{Cell, RefCell};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::os::raw::{c_char, c_int, c_long, c_ulong};
-use std::slice;
 use std::sync::{Arc, Mutex};
+use std::{mem, slice};
 
+use calloop::timer::{TimeoutAction, Timer};
+use calloop::{LoopHandle, RegistrationToken};
 use x11_dl::xinput2::{
     self, XIDeviceEvent, XIEnterEvent, XIFocusInEvent, XIFocusOutEvent, XIHierarchyEvent,
-    XILeaveEvent, XIModifierState, XIRawEvent,
+    XILeaveEvent, XIModifierState, XIPropertyEvent, XIRawEvent, XITouchOwnershipEvent,
 };
 use x11_dl::xlib::{
     self, Display as XDisplay, Window as XWindow, XAnyEvent, XClientMessageEvent, XConfigureEvent,
@@ -22,12 +24,11 @@ use xkbcommon_dl::xkb_mod_mask_t;
 
 use crate::dpi::{PhysicalPosition, PhysicalSize};
 use crate::event::{
-    DeviceEvent, ElementState, Event, Ime, InnerSizeWriter, MouseButton, MouseScrollDelta,
-    RawKeyEvent, Touch, TouchPhase, WindowEvent,
+    DeviceEvent, ElementState, Event, Ime, InnerSizeWriter, Modifiers, MouseButton,
+    MouseScrollDelta, RawKeyEvent, ScrollSource, Touch, TouchPhase, WindowEvent,
 };
 use crate::event_loop::ActiveEventLoop as RootAEL;
-use crate::keyboard::ModifiersState;
-use crate::platform_impl::common::xkb::{self, XkbState};
+use crate::platform_impl::common::xkb::{self, KeymapSnapshot, XkbState};
 use crate::platform_impl::platform::common::xkb::Context;
 use crate::platform_impl::platform::x11::ime::{ImeEvent, ImeEventReceiver, ImeRequest};
 use crate::platform_impl::platform::x11::ActiveEventLoop;
@@ -35,9 +36,10 @@ use crate::platform_impl::platform::ActiveEventLoop as PlatformActiveEventLoop;
 use crate::platform_impl::x11::atoms::*;
 use crate::platform_impl::x11::util::cookie::GenericEventCookie;
 use crate::platform_impl::x11::{
-    mkdid, mkwid, util, CookieResultExt, Device, DeviceId, DeviceInfo, Dnd, DndState, ImeReceiver,
-    ScrollOrientation, UnownedWindow, WindowId,
+    mkdid, mkwid, util, CookieResultExt, Device, DeviceId, DeviceInfo, Dnd, DndState,
+    EventLoopState, ImeReceiver, ScrollOrientation, TouchShape, UnownedWindow, WindowId,
 };
+use crate::window::{KeyRepeat, KeyRepeatRate, WindowBounds};
 
 /// The maximum amount of X modifiers to replay.
 pub const MAX_MOD_REPLAY_LEN: usize = 32;
@@ -62,11 +64,17 @@ pub struct EventProcessor {
     //
     // Used to detect key repeats.
     pub held_key_press: Option<u32>,
+    // The timer emulating the custom key repeat rate of the window.
+    pub key_repeat_token: Option<RegistrationToken>,
+    pub loop_handle: LoopHandle<'static, EventLoopState>,
     pub first_touch: Option<u64>,
+    // The touch sequences owned by the client, the rest could be accepted by the grabbing
+    // clients, like the gesture recognizers, and are cancelled then.
+    pub owned_touches: HashSet<u64>,
     // Currently focused window belonging to this process
     pub active_window: Option<xproto::Window>,
     /// Latest modifiers we've sent for the user to trigger change in event.
-    pub modifiers: Cell<ModifiersState>,
+    pub modifiers: Cell<Modifiers>,
     pub xfiltered_modifiers: VecDeque<c_ulong>,
     pub xmodmap: util::ModifierKeymap,
     pub is_composing: bool,
@@ -146,6 +154,22 @@ impl EventProcessor {
     {
         let event_type = xev.get_type();
 
+        // The keys grabbed for the global shortcuts are reported on the root window.
+        if event_type == xlib::KeyPress || event_type == xlib::KeyRelease {
+            let wt = Self::window_target(&self.target);
+            let xkey: &XKeyEvent = xev.as_ref();
+            if xkey.window as xproto::Window == wt.root {
+                wt.xconn.set_timestamp(xkey.time as xproto::Timestamp);
+                let state = if event_type == xlib::KeyPress {
+                    ElementState::Pressed
+                } else {
+                    ElementState::Released
+                };
+                wt.global_shortcut_input(xkey.keycode as _, xkey.state as _, state);
+                return;
+            }
+        }
+
         if self.filter_event(xev) {
             if event_type == xlib::KeyPress || event_type == xlib::KeyRelease {
                 let xev: &XKeyEvent = xev.as_ref();
@@ -253,6 +277,10 @@ impl EventProcessor {
                         let xev: &XIDeviceEvent = unsafe { xev.as_event() };
                         self.xinput2_touch(xev, phase, &mut callback);
                     },
+                    xinput2::XI_TouchOwnership => {
+                        let xev: &XITouchOwnershipEvent = unsafe { xev.as_event() };
+                        self.owned_touches.insert(xev.touchid as u64);
+                    },
                     xinput2::XI_RawButtonPress | xinput2::XI_RawButtonRelease => {
                         let state = match evtype {
                             xinput2::XI_RawButtonPress => ElementState::Pressed,
@@ -282,6 +310,10 @@ impl EventProcessor {
                         let xev: &XIHierarchyEvent = unsafe { xev.as_event() };
                         self.xinput2_hierarchy_changed(xev, &mut callback);
                     },
+                    xinput2::XI_PropertyEvent => {
+                        let xev: &XIPropertyEvent = unsafe { xev.as_event() };
+                        self.xinput2_property_changed(xev);
+                    },
                     _ => {},
                 }
             },
@@ -338,7 +370,24 @@ impl EventProcessor {
         let mut devices = self.devices.borrow_mut();
         if let Some(info) = DeviceInfo::get(&window_target.xconn, device as _) {
             for info in info.iter() {
-                devices.insert(DeviceId(info.deviceid as _), Device::new(info));
+                let mut device = Device::new(info);
+                let properties = Device::physical_device(info)
+                    .then(|| window_target.xconn.device_properties(info.deviceid as _).ok())
+                    .flatten();
+                if let Some(properties) = properties {
+                    device.scroll_source = properties.scroll_source;
+                    device.scroll_inverted = properties.scroll_inverted;
+                }
+                if Device::physical_device(info) {
+                    device.touch_shape = TouchShape::new(&window_target.xconn, info);
+                }
+
+                let description = Device::describe(&window_target.xconn, info, properties.as_ref());
+                window_target
+                    .devices
+                    .borrow_mut()
+                    .insert(DeviceId(info.deviceid as _), description);
+                devices.insert(DeviceId(info.deviceid as _), device);
             }
         }
     }
@@ -766,7 +815,7 @@ impl EventProcessor {
             // XResizeWindow requests, making Xorg, the winit client, and the WM
             // consume 100% of CPU.
             if let Some(adjusted_size) = shared_state_lock.dpi_adjusted {
-                if new_inner_size == adjusted_size || !util::wm_name_is_one_of(&["Xfwm4"]) {
+                if new_inner_size == adjusted_size || !wt.xconn.wm_name_is_one_of(&["Xfwm4"]) {
                     // When this finally happens, the event will not be synthetic.
                     shared_state_lock.dpi_adjusted = None;
                 } else {
@@ -784,6 +833,36 @@ impl EventProcessor {
             let _ = window.set_cursor_hittest(true);
         }
 
+        // Track the bounds the window is restored to once it's unmaximized.
+        let pending_normal_bounds = {
+            let mut shared_state_lock = window.shared_state_lock();
+            if !window.is_normal_state(&shared_state_lock) {
+                None
+            } else if mem::take(&mut shared_state_lock.pending_normal_bounds) {
+                shared_state_lock.normal_bounds
+            } else {
+                let outer_size = match shared_state_lock.frame_extents.as_ref() {
+                    Some(extents) => {
+                        extents.inner_size_to_outer(new_inner_size.0, new_inner_size.1)
+                    },
+                    None => new_inner_size,
+                };
+                shared_state_lock.normal_bounds = Some(WindowBounds {
+                    position: Some(new_outer_position.into()),
+                    inner_size: new_inner_size.into(),
+                    outer_size: outer_size.into(),
+                });
+                None
+            }
+        };
+
+        if let Some(bounds) = pending_normal_bounds {
+            if let Some(position) = bounds.position {
+                window.set_position_physical(position.x, position.y);
+            }
+            window.request_inner_size_physical(bounds.inner_size.width, bounds.inner_size.height);
+        }
+
         if resized {
             callback(&self.target, Event::WindowEvent {
                 window_id,
@@ -862,6 +941,26 @@ impl EventProcessor {
         {
             self.process_dpi_change(&mut callback);
         }
+
+        let xwindow = xev.window as xproto::Window;
+        if atom == atoms[_NET_WM_STATE] {
+            let state = self.with_window(xwindow, |window| window.update_net_wm_state()).flatten();
+            if let Some(state) = state {
+                callback(&self.target, Event::WindowEvent {
+                    window_id: mkwid(xwindow),
+                    event: WindowEvent::StateChanged(state),
+                });
+            }
+        } else if atom == atoms[_NET_WM_ALLOWED_ACTIONS] {
+            let capabilities =
+                self.with_window(xwindow, |window| window.update_allowed_actions()).flatten();
+            if let Some(capabilities) = capabilities {
+                callback(&self.target, Event::WindowEvent {
+                    window_id: mkwid(xwindow),
+                    event: WindowEvent::WmCapabilitiesChanged(capabilities),
+                });
+            }
+        }
     }
 
     fn visibility_notify<F>(&self, xev: &XVisibilityEvent, mut callback: F)
@@ -947,6 +1046,25 @@ impl EventProcessor {
             false
         };
 
+        // The server repeats are dropped when the window repeats the keys on its own, since the
+        // server repeat rate is global.
+        let key_repeat = self
+            .with_window(window, |window| window.shared_state_lock().key_repeat)
+            .unwrap_or_default();
+        if key_repeats && key_repeat != KeyRepeat::System {
+            if repeat {
+                return;
+            }
+
+            match key_repeat {
+                KeyRepeat::Custom(rate) if state == ElementState::Pressed => {
+                    self.start_key_repeat(rate)
+                },
+                _ if self.held_key_press.is_none() => self.stop_key_repeat(),
+                _ => (),
+            }
+        }
+
         // NOTE: When the modifier was captured by the XFilterEvents the modifiers for the modifier
         // itself are out of sync due to XkbState being delivered before XKeyEvent, since it's
         // being replayed by the XIM, thus we should replay ourselves.
@@ -974,6 +1092,14 @@ impl EventProcessor {
 
             if let Some(mut key_processor) = self.xkb_context.key_context() {
                 let event = key_processor.process_key_event(keycode, state, repeat);
+
+                // The compose sequence must end before the composed text is delivered.
+                if let Some(compose) = self.xkb_context.take_compose() {
+                    let event =
+                        Event::WindowEvent { window_id, event: WindowEvent::Compose(compose) };
+                    callback(&self.target, event);
+                }
+
                 let event = Event::WindowEvent {
                     window_id,
                     event: WindowEvent::KeyboardInput { device_id, event, is_synthetic: false },
@@ -1036,10 +1162,9 @@ impl EventProcessor {
 
         let mask = self.xkb_mod_mask_from_core(state);
         xkb_state.update_modifiers(mask, 0, 0, 0, 0, Self::core_keyboard_group(state));
-        let mods: ModifiersState = xkb_state.modifiers().into();
+        let mods = xkb_state.modifiers().into();
 
-        let event =
-            Event::WindowEvent { window_id, event: WindowEvent::ModifiersChanged(mods.into()) };
+        let event = Event::WindowEvent { window_id, event: WindowEvent::ModifiersChanged(mods) };
 
         callback(&self.target, event);
     }
@@ -1060,6 +1185,19 @@ impl EventProcessor {
             return;
         }
 
+        // Presses outside of the grabbing popup dismiss it instead of being delivered.
+        if state == ElementState::Pressed
+            && self
+                .with_window(event.event as xproto::Window, |window| {
+                    window.dismiss_popup_on_press(event.event_x, event.event_y)
+                })
+                .unwrap_or(false)
+        {
+            let event = Event::WindowEvent { window_id, event: WindowEvent::PopupDismissed };
+            callback(&self.target, event);
+            return;
+        }
+
         let event = match event.detail as u32 {
             xlib::Button1 => {
                 WindowEvent::MouseInput { device_id, state, button: MouseButton::Left }
@@ -1086,6 +1224,11 @@ impl EventProcessor {
                     _ => unreachable!(),
                 },
                 phase: TouchPhase::Moved,
+                source: match event.detail {
+                    6 | 7 => ScrollSource::WheelTilt,
+                    _ => ScrollSource::Wheel,
+                },
+                inverted: false,
             },
             8 => WindowEvent::MouseInput { device_id, state, button: MouseButton::Back },
 
@@ -1150,6 +1293,7 @@ impl EventProcessor {
             let event = if let Some(&mut (_, ref mut info)) =
                 physical_device.scroll_axes.iter_mut().find(|&&mut (axis, _)| axis == i as _)
             {
+                // High-resolution wheels and touchpads move by fractions of the increment.
                 let delta = (x - info.position) / info.increment;
                 info.position = x;
                 // X11 vertical scroll coordinates are opposite to winit's
@@ -1159,8 +1303,18 @@ impl EventProcessor {
                     },
                     ScrollOrientation::Vertical => MouseScrollDelta::LineDelta(0.0, -delta as f32),
                 };
+                let source = match (physical_device.scroll_source, info.orientation) {
+                    (ScrollSource::Wheel, ScrollOrientation::Horizontal) => ScrollSource::WheelTilt,
+                    (source, _) => source,
+                };
 
-                WindowEvent::MouseWheel { device_id, delta, phase: TouchPhase::Moved }
+                WindowEvent::MouseWheel {
+                    device_id,
+                    delta,
+                    phase: TouchPhase::Moved,
+                    source,
+                    inverted: physical_device.scroll_inverted,
+                }
             } else {
                 WindowEvent::AxisMotion { device_id, axis: i as u32, value: unsafe { *value } }
             };
@@ -1275,6 +1429,8 @@ impl EventProcessor {
         let event = Event::WindowEvent { window_id, event: WindowEvent::Focused(true) };
         callback(&self.target, event);
 
+        self.update_keyboard_grab(window, &mut callback);
+
         // Issue key press events for all pressed keys
         Self::handle_pressed_keys(
             &self.target,
@@ -1316,6 +1472,14 @@ impl EventProcessor {
             return;
         }
 
+        // Grabbing the keyboard to inhibit the shortcuts moves the focus to the grab window.
+        if xev.mode == xinput2::XINotifyGrab
+            && self.with_window(window, |window| window.shared_state_lock().keyboard_grabbed)
+                == Some(true)
+        {
+            return;
+        }
+
         if let Some(ime) = wt.ime.as_ref() {
             ime.borrow_mut().unfocus(xev.event).expect("Failed to unfocus input context");
         }
@@ -1344,13 +1508,90 @@ impl EventProcessor {
             // Clear this so detecting key repeats is consistently handled when the
             // window regains focus.
             self.held_key_press = None;
+            self.stop_key_repeat();
 
             if let Some(window) = self.with_window(window, Arc::clone) {
                 window.shared_state_lock().has_focus = false;
             }
 
             let event = Event::WindowEvent { window_id, event: WindowEvent::Focused(false) };
-            callback(&self.target, event)
+            callback(&self.target, event);
+
+            self.update_keyboard_grab(window, &mut callback);
+        }
+    }
+
+    /// Update the keyboard grab of the window used to inhibit the shortcuts.
+    pub(super) fn update_keyboard_grab<F>(&self, window: xproto::Window, mut callback: F)
+    where
+        F: FnMut(&RootAEL, Event),
+    {
+        if let Some(inhibited) =
+            self.with_window(window, |window| window.update_keyboard_grab()).flatten()
+        {
+            let event = Event::WindowEvent {
+                window_id: mkwid(window),
+                event: WindowEvent::KeyboardShortcutsInhibited(inhibited),
+            };
+            callback(&self.target, event);
+        }
+    }
+
+    /// Start repeating the held key at the custom rate.
+    fn start_key_repeat(&mut self, rate: KeyRepeatRate) {
+        self.stop_key_repeat();
+
+        let interval = rate.interval;
+        self.key_repeat_token = self
+            .loop_handle
+            .insert_source(Timer::from_duration(rate.delay), move |_, _, state| {
+                state.key_repeat = true;
+                TimeoutAction::ToDuration(interval)
+            })
+            .ok();
+    }
+
+    fn stop_key_repeat(&mut self) {
+        if let Some(token) = self.key_repeat_token.take() {
+            self.loop_handle.remove(token);
+        }
+    }
+
+    /// Issue the repeat of the held key emulating the custom key repeat rate.
+    pub(super) fn repeat_key<F>(&mut self, mut callback: F)
+    where
+        F: FnMut(&RootAEL, Event),
+    {
+        let (window, keycode) = match (self.active_window, self.held_key_press) {
+            (Some(window), Some(keycode)) => (window, keycode),
+            _ => return self.stop_key_repeat(),
+        };
+
+        // The policy could've changed while the key is held.
+        let custom = self
+            .with_window(window, |window| {
+                matches!(window.shared_state_lock().key_repeat, KeyRepeat::Custom(_))
+            })
+            .unwrap_or(false);
+        if !custom {
+            return self.stop_key_repeat();
+        }
+
+        if self.is_composing {
+            return;
+        }
+
+        if let Some(mut key_processor) = self.xkb_context.key_context() {
+            let event = key_processor.process_key_event(keycode, ElementState::Pressed, true);
+            let event = Event::WindowEvent {
+                window_id: mkwid(window),
+                event: WindowEvent::KeyboardInput {
+                    device_id: mkdid(util::VIRTUAL_CORE_KEYBOARD),
+                    event,
+                    is_synthetic: false,
+                },
+            };
+            callback(&self.target, event);
         }
     }
 
@@ -1363,10 +1604,17 @@ impl EventProcessor {
         // Set the timestamp.
         wt.xconn.set_timestamp(xev.time as xproto::Timestamp);
 
+        let id = xev.detail as u64;
+        // The touch sequence ending before the client became its owner was accepted by
+        // another client.
+        let phase = match phase {
+            TouchPhase::Ended if !self.owned_touches.remove(&id) => TouchPhase::Cancelled,
+            phase => phase,
+        };
+
         let window = xev.event as xproto::Window;
         if self.window_exists(window) {
             let window_id = mkwid(window);
-            let id = xev.detail as u64;
             let location = PhysicalPosition::new(xev.event_x, xev.event_y);
 
             // Mouse cursor position changes when touch events are received.
@@ -1382,6 +1630,14 @@ impl EventProcessor {
                 callback(&self.target, event);
             }
 
+            let (contact_size, orientation) = self
+                .devices
+                .borrow()
+                .get(&DeviceId(xev.sourceid as xinput::DeviceId))
+                .and_then(|device| device.touch_shape)
+                .map(|shape| shape.read(&xev.valuators))
+                .unwrap_or_default();
+
             let event = Event::WindowEvent {
                 window_id,
                 event: WindowEvent::Touch(Touch {
@@ -1389,6 +1645,8 @@ impl EventProcessor {
                     phase,
                     location,
                     force: None, // TODO
+                    contact_size,
+                    orientation,
                     id,
                 }),
             };
@@ -1519,10 +1777,38 @@ impl EventProcessor {
                 });
                 let mut devices = self.devices.borrow_mut();
                 devices.remove(&DeviceId(info.deviceid as xinput::DeviceId));
+                wt.devices.borrow_mut().remove(&DeviceId(info.deviceid as xinput::DeviceId));
             }
         }
     }
 
+    fn xinput2_property_changed(&mut self, xev: &XIPropertyEvent) {
+        let wt = Self::window_target(&self.target);
+
+        // Set the timestamp.
+        wt.xconn.set_timestamp(xev.time as xproto::Timestamp);
+
+        // The scrolling settings could be changed while the device is in use.
+        let atoms = wt.xconn.atoms();
+        let property = xev.property as xproto::Atom;
+        if property != atoms[LibinputNaturalScrollingEnabled]
+            && property != atoms[LibinputScrollMethodEnabled]
+        {
+            return;
+        }
+
+        let mut devices = self.devices.borrow_mut();
+        let device = match devices.get_mut(&DeviceId(xev.deviceid as xinput::DeviceId)) {
+            Some(device) => device,
+            None => return,
+        };
+
+        if let Ok(properties) = wt.xconn.device_properties(xev.deviceid as _) {
+            device.scroll_source = properties.scroll_source;
+            device.scroll_inverted = properties.scroll_inverted;
+        }
+    }
+
     fn xkb_event<F>(&mut self, xev: &XkbAnyEvent, mut callback: F)
     where
         F: FnMut(&RootAEL, Event),
@@ -1547,6 +1833,7 @@ impl EventProcessor {
                     let xcb = wt.xconn.xcb_connection().get_raw_xcb_connection();
                     self.xkb_context.set_keymap_from_x11(xcb);
                     self.xmodmap.reload_from_x_connection(&wt.xconn);
+                    self.update_keymap(true, &mut callback);
 
                     let window_id = match self.active_window.map(super::mkwid) {
                         Some(window_id) => window_id,
@@ -1563,6 +1850,7 @@ impl EventProcessor {
                 let xcb = wt.xconn.xcb_connection().get_raw_xcb_connection();
                 self.xkb_context.set_keymap_from_x11(xcb);
                 self.xmodmap.reload_from_x_connection(&wt.xconn);
+                self.update_keymap(true, &mut callback);
                 let window_id = match self.active_window.map(super::mkwid) {
                     Some(window_id) => window_id,
                     None => return,
@@ -1589,13 +1877,12 @@ impl EventProcessor {
                         xev.locked_group as u32,
                     );
 
-                    let window_id = match self.active_window.map(super::mkwid) {
-                        Some(window_id) => window_id,
-                        None => return,
-                    };
-
                     let mods = state.modifiers().into();
-                    self.send_modifiers(window_id, mods, true, &mut callback);
+                    if let Some(window_id) = self.active_window.map(super::mkwid) {
+                        self.send_modifiers(window_id, mods, true, &mut callback);
+                    }
+
+                    self.update_keymap(false, &mut callback);
                 }
             },
             _ => {},
@@ -1623,13 +1910,33 @@ impl EventProcessor {
 
             // NOTE: we use active window since generally sub windows don't have keyboard input,
             // and winit assumes that unfocused window doesn't have modifiers.
-            let window_id = match self.active_window.map(super::mkwid) {
-                Some(window_id) => window_id,
-                None => return,
-            };
-
             let mods = state.modifiers();
-            self.send_modifiers(window_id, mods.into(), force, &mut callback);
+            if let Some(window_id) = self.active_window.map(super::mkwid) {
+                self.send_modifiers(window_id, mods.into(), force, &mut callback);
+            }
+
+            self.update_keymap(false, &mut callback);
+        }
+    }
+
+    /// Update the keymap exposed to the user, notifying the active window once the keymap or its
+    /// active layout changes.
+    fn update_keymap<F>(&self, keymap_changed: bool, callback: &mut F)
+    where
+        F: FnMut(&RootAEL, Event),
+    {
+        let wt = Self::window_target(&self.target);
+        let keymap = self.xkb_context.keymap_snapshot();
+        let layout = keymap.as_ref().map(KeymapSnapshot::active_layout);
+        let old_keymap = wt.keymap.replace(keymap);
+        let layout_changed = old_keymap.as_ref().map(KeymapSnapshot::active_layout) != layout;
+        if !keymap_changed && !layout_changed {
+            return;
+        }
+
+        if let Some(window_id) = self.active_window.map(super::mkwid) {
+            let event = Event::WindowEvent { window_id, event: WindowEvent::KeyboardLayoutChanged };
+            callback(&self.target, event);
         }
     }
 
@@ -1661,7 +1968,8 @@ impl EventProcessor {
         }
 
         let mods = xkb_state.modifiers();
-        self.send_modifiers(window_id, mods.into(), true, &mut callback)
+        self.send_modifiers(window_id, mods.into(), true, &mut callback);
+        self.update_keymap(false, &mut callback);
     }
 
     pub(crate) fn update_mods_from_core_event<F>(
@@ -1746,7 +2054,7 @@ impl EventProcessor {
     fn send_modifiers<F: FnMut(&RootAEL, Event)>(
         &self,
         window_id: crate::window::WindowId,
-        modifiers: ModifiersState,
+        modifiers: Modifiers,
         force: bool,
         callback: &mut F,
     ) {
@@ -1755,7 +2063,7 @@ impl EventProcessor {
         if self.modifiers.replace(modifiers) != modifiers || force {
             let event = Event::WindowEvent {
                 window_id,
-                event: WindowEvent::ModifiersChanged(self.modifiers.get().into()),
+                event: WindowEvent::ModifiersChanged(self.modifiers.get()),
             };
             callback(&self.target, event);
         }
@@ -1,5 +1,5 @@
 use std::cell::{Cell, RefCell};
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::ffi::CStr;
 use std::marker::PhantomData;
 use std::mem::MaybeUninit;
@@ -18,6 +18,7 @@ use libc::{setlocale, LC_CTYPE};
 use tracing::warn;
 use x11rb::connection::RequestConnection;
 use x11rb::errors::{ConnectError, ConnectionError, IdsExhausted, ReplyError};
+use x11rb::protocol::screensaver::{self, ConnectionExt as _};
 use x11rb::protocol::xinput::{self, ConnectionExt as _};
 use x11rb::protocol::xkb;
 use x11rb::protocol::xproto::{self, ConnectionExt as _};
@@ -25,11 +26,25 @@ use x11rb::x11_utils::X11Error as LogicalError;
 use x11rb::xcb_ffi::ReplyOrIdError;
 
 use crate::application::ApplicationHandler;
-use crate::error::{EventLoopError, OsError as RootOsError};
-use crate::event::{Event, StartCause, WindowEvent};
-use crate::event_loop::{ActiveEventLoop as RootAEL, ControlFlow, DeviceEvents};
+use crate::dpi::PhysicalSize;
+use crate::error::{EventLoopError, ExternalError, NotSupportedError, OsError as RootOsError};
+use crate::event::{
+    DeviceAxis, DeviceInfo as RootDeviceInfo, DeviceKind, ElementState, Event, ScrollSource,
+    StartCause, WindowEvent,
+};
+use crate::event_loop::{
+    ActiveEventLoop as RootAEL, ControlFlow, DeviceEvents, GlobalShortcutId, IdleTimeoutId,
+};
+use crate::keyboard::{KeyCode, ModifiersState, PhysicalKey};
 use crate::platform::pump_events::PumpStatus;
-use crate::platform_impl::common::xkb::Context;
+use crate::platform_impl::common::executor::{self, LocalExecutor};
+#[cfg(target_os = "linux")]
+use crate::platform_impl::common::gamepad::{GamepadId, Gamepads};
+use crate::platform_impl::common::modal::ModalBlocker;
+use crate::platform_impl::common::user_sources::UserSources;
+use crate::platform_impl::common::xkb::{
+    physicalkey_to_scancode, Context, KeymapSnapshot, ResetDeadKeys,
+};
 use crate::platform_impl::platform::{min_timeout, WindowId};
 use crate::platform_impl::{
     ActiveEventLoop as PlatformActiveEventLoop, OsError, PlatformCustomCursor,
@@ -136,8 +151,37 @@ pub struct ActiveEventLoop {
     windows: RefCell<HashMap<WindowId, Weak<UnownedWindow>>>,
     redraw_sender: WakeSender<WindowId>,
     activation_sender: WakeSender<ActivationToken>,
+    keyboard_grab_sender: WakeSender<WindowId>,
     event_loop_proxy: EventLoopProxy,
     device_events: Cell<DeviceEvents>,
+    modal_blocker: ModalBlocker,
+    user_sources: UserSources<EventLoopState>,
+    executor: LocalExecutor,
+    reset_dead_keys: ResetDeadKeys,
+    /// The keymap exposed to the user, updated by the event processor.
+    keymap: RefCell<Option<KeymapSnapshot>>,
+    /// The input devices exposed to the user, updated by the event processor.
+    devices: RefCell<BTreeMap<DeviceId, RootDeviceInfo>>,
+    #[cfg(target_os = "linux")]
+    gamepads: Option<Gamepads<EventLoopState>>,
+    global_shortcuts: RefCell<HashMap<GlobalShortcutId, GlobalShortcut>>,
+}
+
+/// The modifiers matched by the global shortcuts.
+const GLOBAL_SHORTCUT_MODS: u16 =
+    (ffi::ShiftMask | ffi::ControlMask | ffi::Mod1Mask | ffi::Mod4Mask) as u16;
+
+/// The lock modifiers the global shortcuts are grabbed with, so they're ignored when matching.
+///
+/// NOTE: Num Lock is bound to `Mod2` by virtually every keymap.
+const GLOBAL_SHORTCUT_LOCK_MODS: [u16; 4] =
+    [0, ffi::LockMask as u16, ffi::Mod2Mask as u16, (ffi::LockMask | ffi::Mod2Mask) as u16];
+
+/// The global shortcut grabbed on the root window.
+struct GlobalShortcut {
+    keycode: xproto::Keycode,
+    modifiers: u16,
+    pressed: bool,
 }
 
 pub struct EventLoop {
@@ -146,6 +190,7 @@ pub struct EventLoop {
     event_processor: EventProcessor,
     redraw_receiver: PeekableReceiver<WindowId>,
     activation_receiver: PeekableReceiver<ActivationToken>,
+    keyboard_grab_receiver: PeekableReceiver<WindowId>,
 
     /// The current state of the event loop.
     state: EventLoopState,
@@ -153,16 +198,19 @@ pub struct EventLoop {
 
 type ActivationToken = (WindowId, crate::event_loop::AsyncRequestSerial);
 
-struct EventLoopState {
+pub(super) struct EventLoopState {
     /// The latest readiness state for the x11 file descriptor
     x11_readiness: Readiness,
 
     /// User requested a wake up.
     proxy_wake_up: bool,
+
+    /// The held key must be repeated.
+    key_repeat: bool,
 }
 
 impl EventLoop {
-    pub(crate) fn new(xconn: Arc<XConnection>) -> EventLoop {
+    pub(crate) fn new(xconn: Arc<XConnection>, gamepad_mappings: Option<&str>) -> EventLoop {
         let root = xconn.default_root().root;
         let atoms = xconn.atoms();
 
@@ -265,6 +313,7 @@ impl EventLoop {
 
         // Create a channel for sending activation tokens.
         let (activation_token_sender, activation_token_channel) = mpsc::channel();
+        let (keyboard_grab_sender, keyboard_grab_channel) = mpsc::channel();
 
         // Create a channel for sending user events.
         let (user_waker, user_waker_source) =
@@ -278,8 +327,12 @@ impl EventLoop {
             .expect("Failed to register the event loop waker source");
         let event_loop_proxy = EventLoopProxy::new(user_waker);
 
-        let xkb_context =
-            Context::from_x11_xkb(xconn.xcb_connection().get_raw_xcb_connection()).unwrap();
+        let reset_dead_keys = ResetDeadKeys::default();
+        let xkb_context = Context::from_x11_xkb(
+            xconn.xcb_connection().get_raw_xcb_connection(),
+            reset_dead_keys.clone(),
+        )
+        .unwrap();
 
         let mut xmodmap = util::ModifierKeymap::new();
         xmodmap.reload_from_x_connection(&xconn);
@@ -303,8 +356,21 @@ impl EventLoop {
                 sender: activation_token_sender, // not used again so no clone
                 waker: waker.clone(),
             },
+            keyboard_grab_sender: WakeSender {
+                sender: keyboard_grab_sender, // not used again so no clone
+                waker: waker.clone(),
+            },
             event_loop_proxy,
             device_events: Default::default(),
+            modal_blocker: Default::default(),
+            user_sources: UserSources::new(event_loop.handle()),
+            executor: LocalExecutor::new(&event_loop.handle()),
+            reset_dead_keys,
+            keymap: RefCell::new(xkb_context.keymap_snapshot()),
+            devices: Default::default(),
+            #[cfg(target_os = "linux")]
+            gamepads: gamepad_mappings.map(|mappings| Gamepads::new(event_loop.handle(), mappings)),
+            global_shortcuts: Default::default(),
         };
 
         // Set initial device event filter.
@@ -327,13 +393,16 @@ impl EventLoop {
             xkb_context,
             num_touch: 0,
             held_key_press: None,
+            key_repeat_token: None,
+            loop_handle: event_loop.handle(),
             first_touch: None,
+            owned_touches: HashSet::new(),
             active_window: None,
             modifiers: Default::default(),
             is_composing: false,
         };
 
-        // Register for device hotplug events
+        // Register for device hotplug and device property events
         // (The request buffer is flushed during `init_device`)
         let xconn = &EventProcessor::window_target(&event_processor.target).xconn;
 
@@ -341,7 +410,8 @@ impl EventLoop {
             .select_xinput_events(
                 root,
                 ALL_DEVICES,
-                x11rb::protocol::xinput::XIEventMask::HIERARCHY,
+                x11rb::protocol::xinput::XIEventMask::HIERARCHY
+                    | x11rb::protocol::xinput::XIEventMask::PROPERTY,
             )
             .expect_then_ignore_error("Failed to register for XInput2 device hotplug events");
 
@@ -362,7 +432,12 @@ impl EventLoop {
             event_processor,
             redraw_receiver: PeekableReceiver::from_recv(redraw_channel),
             activation_receiver: PeekableReceiver::from_recv(activation_token_channel),
-            state: EventLoopState { x11_readiness: Readiness::EMPTY, proxy_wake_up: false },
+            keyboard_grab_receiver: PeekableReceiver::from_recv(keyboard_grab_channel),
+            state: EventLoopState {
+                x11_readiness: Readiness::EMPTY,
+                proxy_wake_up: false,
+                key_repeat: false,
+            },
         }
     }
 
@@ -436,7 +511,14 @@ impl EventLoop {
     fn has_pending(&mut self) -> bool {
         self.event_processor.poll()
             || self.state.proxy_wake_up
+            || self.state.key_repeat
             || self.redraw_receiver.has_incoming()
+            || {
+                let window_target = EventProcessor::window_target(&self.event_processor.target);
+                window_target.user_sources.has_pending()
+                    || window_target.has_pending_gamepad_events()
+                    || window_target.executor.has_pending()
+            }
     }
 
     fn poll_events_with_timeout<A: ApplicationHandler>(
@@ -516,6 +598,35 @@ impl EventLoop {
         // Process all pending events
         self.drain_events(app);
 
+        // Issue the emulated key repeats.
+        if mem::take(&mut self.state.key_repeat) {
+            self.event_processor.repeat_key(|target: &RootAEL, event: Event| {
+                if let Event::WindowEvent { window_id, event } = event {
+                    app.window_event(target, window_id, event);
+                }
+            });
+        }
+
+        // Dispatch the user file descriptors and timers.
+        let user_sources =
+            &EventProcessor::window_target(&self.event_processor.target).user_sources;
+        while let Some(event) = user_sources.pop() {
+            event.deliver(app, &self.event_processor.target);
+        }
+
+        // Deliver the gamepad events.
+        #[cfg(target_os = "linux")]
+        if let Some(gamepads) =
+            &EventProcessor::window_target(&self.event_processor.target).gamepads
+        {
+            while let Some((device_id, event)) = gamepads.pop() {
+                app.device_event(&self.event_processor.target, device_id, event);
+            }
+        }
+
+        // Poll the futures spawned by the user.
+        EventProcessor::window_target(&self.event_processor.target).executor.run();
+
         // Empty activation tokens.
         while let Ok((window_id, serial)) = self.activation_receiver.try_recv() {
             let token = self.event_processor.with_window(window_id.0 as xproto::Window, |window| {
@@ -525,19 +636,32 @@ impl EventLoop {
             match token {
                 Some(Ok(token)) => {
                     let window_id = crate::window::WindowId(window_id);
-                    let event = WindowEvent::ActivationTokenDone {
-                        serial,
-                        token: crate::window::ActivationToken::_new(token),
-                    };
+                    let token = crate::window::ActivationToken::_new(token);
+                    executor::complete_activation_token(serial, Ok(token.clone()));
+                    let event = WindowEvent::ActivationTokenDone { serial, token };
                     app.window_event(&self.event_processor.target, window_id, event);
                 },
                 Some(Err(e)) => {
                     tracing::error!("Failed to get activation token: {}", e);
+                    let error = os_error!(OsError::XError(Arc::new(e)));
+                    executor::complete_activation_token(serial, Err(ExternalError::Os(error)));
                 },
                 None => {},
             }
         }
 
+        // Apply the keyboard shortcuts inhibition changes.
+        while let Ok(window_id) = self.keyboard_grab_receiver.try_recv() {
+            self.event_processor.update_keyboard_grab(
+                window_id.0 as xproto::Window,
+                |target: &RootAEL, event: Event| {
+                    if let Event::WindowEvent { window_id, event } = event {
+                        app.window_event(target, window_id, event);
+                    }
+                },
+            );
+        }
+
         // Empty the user event buffer
         if mem::take(&mut self.state.proxy_wake_up) {
             app.proxy_wake_up(&self.event_processor.target);
@@ -581,7 +705,11 @@ impl EventLoop {
                 } else {
                     match event {
                         Event::WindowEvent { window_id, event } => {
-                            app.window_event(window_target, window_id, event)
+                            let modal_blocker =
+                                &EventProcessor::window_target(window_target).modal_blocker;
+                            if !modal_blocker.should_filter(window_id.0, &event) {
+                                app.window_event(window_target, window_id, event)
+                            }
                         },
                         Event::DeviceEvent { device_id, event } => {
                             app.device_event(window_target, device_id, event)
@@ -624,11 +752,204 @@ impl AsRawFd for EventLoop {
     }
 }
 
+impl Drop for EventLoop {
+    fn drop(&mut self) {
+        let xconn = &EventProcessor::window_target(&self.event_processor.target).xconn;
+        crate::platform_impl::release_x11_backend(xconn);
+    }
+}
+
 impl ActiveEventLoop {
     pub fn create_proxy(&self) -> EventLoopProxy {
         self.event_loop_proxy.clone()
     }
 
+    /// The file descriptors and timers registered by the user.
+    #[inline]
+    pub(super) fn user_sources(&self) -> &UserSources<EventLoopState> {
+        &self.user_sources
+    }
+
+    /// Register the idle timeout, polling the idle time reported by the `MIT-SCREEN-SAVER`
+    /// extension.
+    pub(super) fn add_idle_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<IdleTimeoutId, NotSupportedError> {
+        self.xconn
+            .xcb_connection()
+            .extension_information(screensaver::X11_EXTENSION_NAME)
+            .ok()
+            .flatten()
+            .ok_or_else(NotSupportedError::new)?;
+
+        let xconn = self.xconn.clone();
+        let root = self.root;
+        Ok(self.user_sources.poll_idle_timeout(timeout, move || {
+            let info = xconn
+                .xcb_connection()
+                .screensaver_query_info(root)
+                .ok()?
+                .reply()
+                .map_err(|err| warn!("Failed to query the idle time: {err}"))
+                .ok()?;
+            Some(Duration::from_millis(info.ms_since_user_input.into()))
+        }))
+    }
+
+    pub(super) fn remove_idle_timeout(&self, timeout_id: IdleTimeoutId) {
+        self.user_sources.remove_idle_timeout(timeout_id)
+    }
+
+    pub(super) fn keymap(&self) -> Option<KeymapSnapshot> {
+        self.keymap.borrow().clone()
+    }
+
+    pub fn devices(&self) -> Vec<RootDeviceInfo> {
+        let devices = self.devices.borrow().values().cloned().collect();
+        #[cfg(target_os = "linux")]
+        let devices = match &self.gamepads {
+            Some(gamepads) => [devices, gamepads.devices()].concat(),
+            None => devices,
+        };
+        devices
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(super) fn rumble_gamepad(
+        &self,
+        gamepad_id: GamepadId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<(), ExternalError> {
+        match &self.gamepads {
+            Some(gamepads) => gamepads.rumble(gamepad_id, strong, weak, duration),
+            None => Err(ExternalError::NotSupported(NotSupportedError::new())),
+        }
+    }
+
+    pub(super) fn register_global_shortcut(
+        &self,
+        modifiers: ModifiersState,
+        key: KeyCode,
+    ) -> Result<GlobalShortcutId, ExternalError> {
+        // NOTE: The X11 keycodes are offset by 8 from the evdev scancodes.
+        let keycode = physicalkey_to_scancode(PhysicalKey::Code(key))
+            .and_then(|scancode| xproto::Keycode::try_from(scancode + 8).ok())
+            .ok_or_else(|| ExternalError::NotSupported(NotSupportedError::new()))?;
+
+        let mut mods = 0;
+        for (state, mask) in [
+            (ModifiersState::SHIFT, xproto::ModMask::SHIFT),
+            (ModifiersState::CONTROL, xproto::ModMask::CONTROL),
+            (ModifiersState::ALT, xproto::ModMask::M1),
+            (ModifiersState::SUPER, xproto::ModMask::M4),
+        ] {
+            if modifiers.contains(state) {
+                mods |= u16::from(mask);
+            }
+        }
+
+        for (index, lock_mods) in GLOBAL_SHORTCUT_LOCK_MODS.into_iter().enumerate() {
+            let result = self
+                .xconn
+                .xcb_connection()
+                .grab_key(
+                    false,
+                    self.root,
+                    (mods | lock_mods).into(),
+                    keycode,
+                    xproto::GrabMode::ASYNC,
+                    xproto::GrabMode::ASYNC,
+                )
+                .map_err(ReplyError::from)
+                .and_then(|cookie| cookie.check());
+
+            // The key is likely grabbed by another client already.
+            if let Err(err) = result {
+                for lock_mods in &GLOBAL_SHORTCUT_LOCK_MODS[..index] {
+                    self.ungrab_global_shortcut(keycode, mods | lock_mods);
+                }
+                self.xconn.flush_requests().expect("Failed to call `ungrab_key`");
+                return Err(ExternalError::Os(os_error!(OsError::XError(Arc::new(err.into())))));
+            }
+        }
+
+        let shortcut_id = self.user_sources.add_global_shortcut();
+        let shortcut = GlobalShortcut { keycode, modifiers: mods, pressed: false };
+        self.global_shortcuts.borrow_mut().insert(shortcut_id, shortcut);
+        Ok(shortcut_id)
+    }
+
+    pub(super) fn unregister_global_shortcut(&self, shortcut_id: GlobalShortcutId) {
+        if let Some(shortcut) = self.global_shortcuts.borrow_mut().remove(&shortcut_id) {
+            for lock_mods in GLOBAL_SHORTCUT_LOCK_MODS {
+                self.ungrab_global_shortcut(shortcut.keycode, shortcut.modifiers | lock_mods);
+            }
+            self.xconn.flush_requests().expect("Failed to call `ungrab_key`");
+        }
+
+        self.user_sources.remove_global_shortcut(shortcut_id);
+    }
+
+    fn ungrab_global_shortcut(&self, keycode: xproto::Keycode, modifiers: u16) {
+        self.xconn
+            .xcb_connection()
+            .ungrab_key(keycode, self.root, modifiers.into())
+            .expect_then_ignore_error("Failed to call `ungrab_key`");
+    }
+
+    /// Report the press or the release of the key grabbed for the global shortcuts.
+    pub(super) fn global_shortcut_input(
+        &self,
+        keycode: xproto::Keycode,
+        modifiers: u16,
+        state: ElementState,
+    ) {
+        let modifiers = modifiers & GLOBAL_SHORTCUT_MODS;
+        for (shortcut_id, shortcut) in self.global_shortcuts.borrow_mut().iter_mut() {
+            if shortcut.keycode != keycode {
+                continue;
+            }
+
+            // NOTE: The release is reported even if the modifiers were released first, and
+            // the repeated presses are ignored.
+            let pressed = match state {
+                ElementState::Pressed => shortcut.pressed || shortcut.modifiers == modifiers,
+                ElementState::Released => false,
+            };
+            if pressed != shortcut.pressed {
+                shortcut.pressed = pressed;
+                self.user_sources.push_global_shortcut(*shortcut_id, state);
+            }
+        }
+    }
+
+    /// Flush the pending requests before going to sleep and check whether there's work which
+    /// won't make the connection readable, like the events already read into the Xlib queue.
+    pub(super) fn prepare_to_wait(&self) -> bool {
+        // NOTE: `XPending` flushes the output buffer.
+        let queued = unsafe { (self.xconn.xlib.XPending)(self.xconn.display) } != 0;
+        queued
+            || self.user_sources.has_pending()
+            || self.has_pending_gamepad_events()
+            || self.executor.has_pending()
+    }
+
+    fn has_pending_gamepad_events(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        return self.gamepads.as_ref().is_some_and(Gamepads::has_pending);
+        #[cfg(not(target_os = "linux"))]
+        false
+    }
+
+    /// The executor running the futures spawned by the user.
+    #[inline]
+    pub(super) fn executor(&self) -> &LocalExecutor {
+        &self.executor
+    }
+
     /// Returns the `XConnection` of this events loop.
     #[inline]
     pub(crate) fn x_connection(&self) -> &Arc<XConnection> {
@@ -802,6 +1123,9 @@ impl Drop for Window {
         let window = self.deref();
         let xconn = &window.xconn;
 
+        // Release the screen saver suspend held by the window.
+        window.set_idle_inhibit(false);
+
         if let Ok(c) = xconn.xcb_connection().destroy_window(window.id().0 as xproto::Window) {
             c.ignore_error();
         }
@@ -984,6 +1308,10 @@ fn mkdid(w: xinput::DeviceId) -> crate::event::DeviceId {
 pub struct Device {
     _name: String,
     scroll_axes: Vec<(i32, ScrollAxis)>,
+    scroll_source: ScrollSource,
+    // Whether natural scrolling is enabled in the driver.
+    scroll_inverted: bool,
+    touch_shape: Option<TouchShape>,
     // For master devices, this is the paired device (pointer <-> keyboard).
     // For slave devices, this is the master.
     attachment: c_int,
@@ -1002,6 +1330,78 @@ enum ScrollOrientation {
     Horizontal,
 }
 
+/// The valuators describing the contact area of the touch points.
+#[derive(Debug, Copy, Clone)]
+struct TouchShape {
+    major: i32,
+    minor: Option<i32>,
+    // The valuator along with its maximum, which is a quarter turn clockwise.
+    orientation: Option<(i32, f64)>,
+    // The size of the device unit in pixels.
+    scale: f64,
+}
+
+impl TouchShape {
+    fn new(xconn: &XConnection, info: &ffi::XIDeviceInfo) -> Option<Self> {
+        let atoms = xconn.atoms();
+        let mut major = None;
+        let mut minor = None;
+        let mut orientation = None;
+        let mut width = None;
+        for &class_ptr in Device::classes(info) {
+            if unsafe { (*class_ptr)._type } != ffi::XIValuatorClass {
+                continue;
+            }
+
+            let info = unsafe { &*(class_ptr as *const ffi::XIValuatorClassInfo) };
+            let label = info.label as xproto::Atom;
+            if info.number == 0 {
+                width = Some(info.max - info.min);
+            } else if label == atoms[AbsMtTouchMajor] {
+                major = Some(info.number);
+            } else if label == atoms[AbsMtTouchMinor] {
+                minor = Some(info.number);
+            } else if label == atoms[AbsMtOrientation] && info.max > 0.0 {
+                orientation = Some((info.number, info.max));
+            }
+        }
+
+        // NOTE: The touchscreen is assumed to span the whole screen.
+        let width = width.filter(|width| *width > 0.0)?;
+        let scale = xconn.default_root().width_in_pixels as f64 / width;
+        Some(Self { major: major?, minor, orientation, scale })
+    }
+
+    /// The contact size and orientation from the valuators of the touch event.
+    fn read(&self, valuators: &ffi::XIValuatorState) -> (Option<PhysicalSize<f64>>, Option<f64>) {
+        let mask = unsafe { slice::from_raw_parts(valuators.mask, valuators.mask_len as usize) };
+        let mut value = valuators.values;
+        let mut major = None;
+        let mut minor = None;
+        let mut orientation = None;
+        for i in 0..valuators.mask_len * 8 {
+            if !ffi::XIMaskIsSet(mask, i) {
+                continue;
+            }
+
+            let x = unsafe { *value };
+            if i == self.major {
+                major = Some(x * self.scale);
+            } else if Some(i) == self.minor {
+                minor = Some(x * self.scale);
+            } else if let Some((_, max)) = self.orientation.filter(|(axis, _)| *axis == i) {
+                orientation = Some(x / max * 90.0);
+            }
+
+            value = unsafe { value.offset(1) };
+        }
+
+        // The contact area is round when the minor axis isn't reported.
+        let contact_size = major.map(|major| PhysicalSize::new(major, minor.unwrap_or(major)));
+        (contact_size, orientation)
+    }
+}
+
 impl Device {
     fn new(info: &ffi::XIDeviceInfo) -> Self {
         let name = unsafe { CStr::from_ptr(info.name).to_string_lossy() };
@@ -1026,8 +1426,14 @@ impl Device {
             }
         }
 
-        let mut device =
-            Device { _name: name.into_owned(), scroll_axes, attachment: info.attachment };
+        let mut device = Device {
+            _name: name.into_owned(),
+            scroll_axes,
+            scroll_source: ScrollSource::Unknown,
+            scroll_inverted: false,
+            touch_shape: None,
+            attachment: info.attachment,
+        };
         device.reset_scroll_position(info);
         device
     }
@@ -1048,6 +1454,68 @@ impl Device {
         }
     }
 
+    /// Describe the device for the user.
+    fn describe(
+        xconn: &XConnection,
+        info: &ffi::XIDeviceInfo,
+        properties: Option<&util::DeviceProperties>,
+    ) -> RootDeviceInfo {
+        let name = unsafe { CStr::from_ptr(info.name).to_string_lossy() }.into_owned();
+
+        let mut axes = Vec::new();
+        let mut touch_mode = None;
+        for &class_ptr in Device::classes(info) {
+            match unsafe { (*class_ptr)._type } {
+                ffi::XIValuatorClass => {
+                    let info = unsafe { &*(class_ptr as *const ffi::XIValuatorClassInfo) };
+                    let name = (info.label != 0)
+                        .then(|| xconn.get_atom_name(info.label as xproto::Atom).ok())
+                        .flatten();
+                    axes.push(DeviceAxis {
+                        id: info.number as _,
+                        name,
+                        min: info.min,
+                        max: info.max,
+                        resolution: info.resolution as _,
+                    });
+                },
+                ffi::XITouchClass => {
+                    let info = unsafe { &*(class_ptr as *const ffi::XITouchClassInfo) };
+                    touch_mode = Some(info.mode);
+                },
+                _ => (),
+            }
+        }
+
+        // Floating devices don't tell whether they're keyboards, only pointers have axes.
+        let keyboard = info._use == ffi::XIMasterKeyboard
+            || info._use == ffi::XISlaveKeyboard
+            || (info._use == ffi::XIFloatingSlave && axes.is_empty());
+        let kind = if keyboard {
+            DeviceKind::Keyboard
+        } else if touch_mode == Some(ffi::XIDirectTouch) {
+            DeviceKind::Touchscreen
+        } else if touch_mode == Some(ffi::XIDependentTouch)
+            || properties.is_some_and(|properties| properties.touchpad)
+        {
+            DeviceKind::Touchpad
+        } else if axes.iter().any(|axis| axis.name.as_deref() == Some("Abs Pressure")) {
+            DeviceKind::Tablet
+        } else {
+            DeviceKind::Pointer
+        };
+
+        let product_id = properties.and_then(|properties| properties.product_id);
+        RootDeviceInfo {
+            id: mkdid(info.deviceid as _),
+            name,
+            kind,
+            vendor_id: product_id.map(|(vendor, _)| vendor),
+            product_id: product_id.map(|(_, product)| product),
+            axes,
+        }
+    }
+
     #[inline]
     fn physical_device(info: &ffi::XIDeviceInfo) -> bool {
         info._use == ffi::XISlaveKeyboard
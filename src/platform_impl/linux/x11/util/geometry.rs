@@ -110,7 +110,7 @@ impl XConnection {
         let atoms = self.atoms();
         let extents_atom = atoms[_NET_FRAME_EXTENTS];
 
-        if !hint_is_supported(extents_atom) {
+        if !self.hint_is_supported(extents_atom) {
             return None;
         }
 
@@ -139,7 +139,7 @@ impl XConnection {
         let atoms = self.atoms();
         let client_list_atom = atoms[_NET_CLIENT_LIST];
 
-        if !hint_is_supported(client_list_atom) {
+        if !self.hint_is_supported(client_list_atom) {
             return None;
         }
 
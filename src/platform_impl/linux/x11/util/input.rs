@@ -4,6 +4,7 @@ use x11rb::protocol::xinput::{self, ConnectionExt as _};
 use x11rb::protocol::xkb;
 
 use super::*;
+use crate::event::ScrollSource;
 
 pub const VIRTUAL_CORE_POINTER: u16 = 2;
 pub const VIRTUAL_CORE_KEYBOARD: u16 = 3;
@@ -13,6 +14,17 @@ pub const VIRTUAL_CORE_KEYBOARD: u16 = 3;
 // To test if `lookup_utf8` works correctly, set this to 1.
 const TEXT_BUFFER_SIZE: usize = 1024;
 
+/// The properties of an input device set by its driver.
+#[derive(Debug, Clone, Copy)]
+pub struct DeviceProperties {
+    pub touchpad: bool,
+    pub scroll_source: ScrollSource,
+    /// Whether natural scrolling is enabled.
+    pub scroll_inverted: bool,
+    /// The vendor and product IDs.
+    pub product_id: Option<(u16, u16)>,
+}
+
 impl XConnection {
     pub fn select_xinput_events(
         &self,
@@ -57,6 +69,64 @@ impl XConnection {
             .map_err(Into::into)
     }
 
+    /// Query the properties of the device set by its driver.
+    pub fn device_properties(&self, device_id: u16) -> Result<DeviceProperties, X11Error> {
+        let atoms = self.atoms();
+        let properties =
+            self.xcb_connection().xinput_xi_list_properties(device_id)?.reply()?.properties;
+        let get_property = |property| -> Result<Option<xinput::XIGetPropertyItems>, X11Error> {
+            if !properties.contains(&property) {
+                return Ok(None);
+            }
+
+            let reply = self
+                .xcb_connection()
+                .xinput_xi_get_property(
+                    device_id,
+                    false,
+                    property,
+                    xproto::AtomEnum::ANY.into(),
+                    0,
+                    2,
+                )?
+                .reply()?;
+            Ok(Some(reply.items))
+        };
+        let get_flags = |property| -> Result<Vec<u8>, X11Error> {
+            match get_property(property)? {
+                Some(xinput::XIGetPropertyItems::Data8(items)) => Ok(items),
+                _ => Ok(Vec::new()),
+            }
+        };
+
+        // Only touchpads have tapping, the scroll methods are two-finger, edge and button.
+        let touchpad = properties.contains(&atoms[LibinputTappingEnabled])
+            || properties.contains(&atoms[SynapticsTapAction]);
+        let scroll_source = if touchpad {
+            ScrollSource::Finger
+        } else if get_flags(atoms[LibinputScrollMethodEnabled])?.get(2) == Some(&1) {
+            ScrollSource::Continuous
+        } else {
+            ScrollSource::Wheel
+        };
+        let scroll_inverted =
+            get_flags(atoms[LibinputNaturalScrollingEnabled])?.first() == Some(&1);
+
+        let product_id = match get_property(atoms[DeviceProductId])? {
+            Some(xinput::XIGetPropertyItems::Data32(ids)) if ids.len() == 2 => {
+                Some((ids[0] as u16, ids[1] as u16))
+            },
+            _ => None,
+        };
+
+        Ok(DeviceProperties { touchpad, scroll_source, scroll_inverted, product_id })
+    }
+
+    pub fn get_atom_name(&self, atom: xproto::Atom) -> Result<String, X11Error> {
+        let reply = self.xcb_connection().get_atom_name(atom)?.reply()?;
+        Ok(String::from_utf8_lossy(&reply.name).into_owned())
+    }
+
     fn lookup_utf8_inner(
         &self,
         ic: ffi::XIC,
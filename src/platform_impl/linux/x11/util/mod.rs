@@ -15,6 +15,7 @@ mod input;
 pub mod keys;
 pub(crate) mod memory;
 mod mouse;
+mod popup;
 mod randr;
 mod window_property;
 mod wm;
@@ -27,6 +28,7 @@ pub use self::geometry::*;
 pub use self::hint::*;
 pub use self::input::*;
 pub use self::mouse::*;
+pub use self::popup::*;
 pub use self::window_property::*;
 pub use self::wm::*;
 pub use self::xmodmap::ModifierKeymap;
@@ -0,0 +1,242 @@
+use std::cmp;
+
+use super::*;
+use crate::window::{PopupAnchor, PopupConstraintAdjustment};
+
+/// Rectangle in the root window coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PopupRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl PopupRect {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let x = cmp::max(self.x, other.x);
+        let y = cmp::max(self.y, other.y);
+        let width = cmp::min(self.x + self.width, other.x + other.width) - x;
+        let height = cmp::min(self.y + self.height, other.y + other.height) - y;
+        (width > 0 && height > 0).then_some(Self { x, y, width, height })
+    }
+}
+
+/// The rules to place the popup, mirroring the `xdg_positioner` semantics.
+#[derive(Debug, Clone, Copy)]
+pub struct PopupPositioner {
+    pub anchor_rect: PopupRect,
+    pub width: i32,
+    pub height: i32,
+    pub anchor: PopupAnchor,
+    pub gravity: PopupAnchor,
+    pub offset: (i32, i32),
+    pub constraint_adjustment: PopupConstraintAdjustment,
+}
+
+impl PopupPositioner {
+    /// Place the popup, keeping it inside of the `bounds` when the adjustments allow it.
+    pub fn place(&self, bounds: Option<&PopupRect>) -> PopupRect {
+        let (anchor_x, anchor_y) = anchor_directions(self.anchor);
+        let (gravity_x, gravity_y) = anchor_directions(self.gravity);
+        let (bounds, adjustment) = match bounds {
+            Some(bounds) => (*bounds, self.constraint_adjustment),
+            None => (self.anchor_rect, PopupConstraintAdjustment::empty()),
+        };
+
+        let (x, width) = constrain_axis(
+            |flip| {
+                let flip = if flip { -1 } else { 1 };
+                axis_position(
+                    self.anchor_rect.x,
+                    self.anchor_rect.width,
+                    anchor_x * flip,
+                    gravity_x * flip,
+                    self.width,
+                ) + self.offset.0
+            },
+            self.width,
+            (bounds.x, bounds.x + bounds.width),
+            adjustment.contains(PopupConstraintAdjustment::FLIP_X),
+            adjustment.contains(PopupConstraintAdjustment::SLIDE_X),
+            adjustment.contains(PopupConstraintAdjustment::RESIZE_X),
+        );
+
+        let (y, height) = constrain_axis(
+            |flip| {
+                let flip = if flip { -1 } else { 1 };
+                axis_position(
+                    self.anchor_rect.y,
+                    self.anchor_rect.height,
+                    anchor_y * flip,
+                    gravity_y * flip,
+                    self.height,
+                ) + self.offset.1
+            },
+            self.height,
+            (bounds.y, bounds.y + bounds.height),
+            adjustment.contains(PopupConstraintAdjustment::FLIP_Y),
+            adjustment.contains(PopupConstraintAdjustment::SLIDE_Y),
+            adjustment.contains(PopupConstraintAdjustment::RESIZE_Y),
+        );
+
+        PopupRect { x, y, width, height }
+    }
+}
+
+/// Split the anchor into the horizontal and vertical directions, where `-1` is towards the
+/// left/top edge and `1` is towards the right/bottom one.
+fn anchor_directions(anchor: PopupAnchor) -> (i32, i32) {
+    match anchor {
+        PopupAnchor::Center => (0, 0),
+        PopupAnchor::Top => (0, -1),
+        PopupAnchor::Bottom => (0, 1),
+        PopupAnchor::Left => (-1, 0),
+        PopupAnchor::Right => (1, 0),
+        PopupAnchor::TopLeft => (-1, -1),
+        PopupAnchor::BottomLeft => (-1, 1),
+        PopupAnchor::TopRight => (1, -1),
+        PopupAnchor::BottomRight => (1, 1),
+    }
+}
+
+/// Position of the popup start on a single axis.
+fn axis_position(rect_start: i32, rect_len: i32, anchor: i32, gravity: i32, len: i32) -> i32 {
+    let anchor_point = match anchor {
+        -1 => rect_start,
+        1 => rect_start + rect_len,
+        _ => rect_start + rect_len / 2,
+    };
+
+    match gravity {
+        -1 => anchor_point - len,
+        1 => anchor_point,
+        _ => anchor_point - len / 2,
+    }
+}
+
+/// Apply the flip, slide, and resize adjustments in that order on a single axis.
+fn constrain_axis(
+    position: impl Fn(bool) -> i32,
+    len: i32,
+    (min, max): (i32, i32),
+    flip: bool,
+    slide: bool,
+    resize: bool,
+) -> (i32, i32) {
+    let fits = |start: i32, len: i32| start >= min && start + len <= max;
+
+    let mut start = position(false);
+    if fits(start, len) {
+        return (start, len);
+    }
+
+    if flip {
+        let flipped = position(true);
+        if fits(flipped, len) {
+            return (flipped, len);
+        }
+    }
+
+    if slide {
+        // Prefer keeping the start edge visible when the popup is larger than the bounds.
+        start = cmp::max(cmp::min(start, max - len), min);
+        if fits(start, len) {
+            return (start, len);
+        }
+    }
+
+    if resize {
+        let end = cmp::min(start + len, max);
+        start = cmp::max(start, min);
+        if end > start {
+            return (start, end - start);
+        }
+    }
+
+    (start, len)
+}
+
+impl XConnection {
+    /// Get the work area of the current desktop, if the window manager provides it.
+    pub fn get_work_area(&self, root: xproto::Window) -> Option<PopupRect> {
+        let atoms = self.atoms();
+        let workarea_atom = atoms[_NET_WORKAREA];
+
+        if !self.hint_is_supported(workarea_atom) {
+            return None;
+        }
+
+        let workarea: Vec<u32> = self
+            .get_property(root, workarea_atom, xproto::Atom::from(xproto::AtomEnum::CARDINAL))
+            .ok()?;
+
+        // The property holds the area of every desktop, pick the current one.
+        let desktop: usize = self
+            .get_property::<u32>(
+                root,
+                atoms[_NET_CURRENT_DESKTOP],
+                xproto::Atom::from(xproto::AtomEnum::CARDINAL),
+            )
+            .ok()
+            .and_then(|desktop| desktop.first().copied())
+            .unwrap_or(0) as usize;
+
+        let area = workarea.get(desktop * 4..desktop * 4 + 4)?;
+        Some(PopupRect::new(area[0] as i32, area[1] as i32, area[2] as i32, area[3] as i32))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn popup_positioner(anchor: PopupAnchor, gravity: PopupAnchor) -> PopupPositioner {
+        PopupPositioner {
+            anchor_rect: PopupRect::new(100, 100, 20, 10),
+            width: 50,
+            height: 40,
+            anchor,
+            gravity,
+            offset: (0, 0),
+            constraint_adjustment: PopupConstraintAdjustment::all(),
+        }
+    }
+
+    #[test]
+    fn unconstrained_popup() {
+        let bounds = PopupRect::new(0, 0, 1000, 1000);
+        let positioner = popup_positioner(PopupAnchor::BottomLeft, PopupAnchor::BottomRight);
+        assert_eq!(positioner.place(Some(&bounds)), PopupRect::new(100, 110, 50, 40));
+
+        let positioner = popup_positioner(PopupAnchor::Center, PopupAnchor::Center);
+        assert_eq!(positioner.place(Some(&bounds)), PopupRect::new(85, 85, 50, 40));
+    }
+
+    #[test]
+    fn flip_popup() {
+        let bounds = PopupRect::new(0, 0, 1000, 140);
+        let positioner = popup_positioner(PopupAnchor::BottomLeft, PopupAnchor::BottomRight);
+        assert_eq!(positioner.place(Some(&bounds)), PopupRect::new(100, 60, 50, 40));
+    }
+
+    #[test]
+    fn slide_popup() {
+        let bounds = PopupRect::new(0, 0, 130, 1000);
+        let mut positioner = popup_positioner(PopupAnchor::BottomLeft, PopupAnchor::BottomRight);
+        positioner.constraint_adjustment = PopupConstraintAdjustment::SLIDE_X;
+        assert_eq!(positioner.place(Some(&bounds)), PopupRect::new(80, 110, 50, 40));
+    }
+
+    #[test]
+    fn resize_popup() {
+        let bounds = PopupRect::new(0, 0, 1000, 130);
+        let mut positioner = popup_positioner(PopupAnchor::BottomLeft, PopupAnchor::BottomRight);
+        positioner.constraint_adjustment = PopupConstraintAdjustment::RESIZE_Y;
+        assert_eq!(positioner.place(Some(&bounds)), PopupRect::new(100, 110, 50, 20));
+    }
+}
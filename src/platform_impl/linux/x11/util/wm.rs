@@ -1,5 +1,3 @@
-use std::sync::Mutex;
-
 use super::*;
 
 // https://specifications.freedesktop.org/wm-spec/latest/ar01s04.html#idm46075117309248
@@ -13,26 +11,23 @@ pub const MOVERESIZE_BOTTOMLEFT: isize = 6;
 pub const MOVERESIZE_LEFT: isize = 7;
 pub const MOVERESIZE_MOVE: isize = 8;
 
-// This info is global to the window manager.
-static SUPPORTED_HINTS: Mutex<Vec<xproto::Atom>> = Mutex::new(Vec::new());
-static WM_NAME: Mutex<Option<String>> = Mutex::new(None);
-
-pub fn hint_is_supported(hint: xproto::Atom) -> bool {
-    (*SUPPORTED_HINTS.lock().unwrap()).contains(&hint)
-}
+impl XConnection {
+    pub fn hint_is_supported(&self, hint: xproto::Atom) -> bool {
+        self.supported_hints.lock().unwrap().contains(&hint)
+    }
 
-pub fn wm_name_is_one_of(names: &[&str]) -> bool {
-    if let Some(ref name) = *WM_NAME.lock().unwrap() {
-        names.contains(&name.as_str())
-    } else {
-        false
+    pub fn wm_name_is_one_of(&self, names: &[&str]) -> bool {
+        if let Some(ref name) = *self.wm_name.lock().unwrap() {
+            names.contains(&name.as_str())
+        } else {
+            false
+        }
     }
-}
 
-impl XConnection {
+    // NOTE: This info is global to the window manager of the connection.
     pub fn update_cached_wm_info(&self, root: xproto::Window) {
-        *SUPPORTED_HINTS.lock().unwrap() = self.get_supported_hints(root);
-        *WM_NAME.lock().unwrap() = self.get_wm_name(root);
+        *self.supported_hints.lock().unwrap() = self.get_supported_hints(root);
+        *self.wm_name.lock().unwrap() = self.get_wm_name(root);
     }
 
     fn get_supported_hints(&self, root: xproto::Window) -> Vec<xproto::Atom> {
@@ -4,14 +4,18 @@ use std::num::NonZeroU32;
 use std::os::raw::*;
 use std::path::Path;
 use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
 use std::{cmp, env};
 
 use tracing::{debug, info, warn};
 use x11rb::connection::{Connection, RequestConnection};
 use x11rb::properties::{WmHints, WmSizeHints, WmSizeHintsSpecification};
+use x11rb::protocol::screensaver::{self, ConnectionExt as _};
 use x11rb::protocol::shape::SK;
 use x11rb::protocol::sync::{ConnectionExt as _, Int64};
 use x11rb::protocol::xfixes::{ConnectionExt, RegionWrapper};
+use x11rb::protocol::xinput::ConnectionExt as _;
+use x11rb::protocol::xkb::{BoolCtrl, ConnectionExt as _, ID as XkbId};
 use x11rb::protocol::xproto::{self, ConnectionExt as _, Rectangle};
 use x11rb::protocol::{randr, xinput};
 
@@ -25,6 +29,8 @@ use crate::error::{ExternalError, NotSupportedError, OsError as RootOsError};
 use crate::event::{Event, InnerSizeWriter, WindowEvent};
 use crate::event_loop::AsyncRequestSerial;
 use crate::platform::x11::WindowType;
+use crate::platform_impl::common::modal::ModalGuard;
+use crate::platform_impl::common::xkb::{self, ResetDeadKeys};
 use crate::platform_impl::x11::atoms::*;
 use crate::platform_impl::x11::{
     xinput_fp1616_to_float, MonitorHandle as X11MonitorHandle, WakeSender, X11Error,
@@ -34,8 +40,9 @@ use crate::platform_impl::{
     PlatformIcon, VideoModeHandle as PlatformVideoModeHandle,
 };
 use crate::window::{
-    CursorGrabMode, ImePurpose, ResizeDirection, Theme, UserAttentionType, WindowAttributes,
-    WindowButtons, WindowLevel,
+    CursorGrabMode, ImePurpose, KeyRepeat, KeyRepeatRate, PopupAttributes, ResizeDirection, Theme,
+    UserAttentionType, WindowAttributes, WindowBounds, WindowButtons, WindowLevel, WindowState,
+    WmCapabilities,
 };
 
 #[derive(Debug)]
@@ -65,6 +72,24 @@ pub struct SharedState {
     pub has_focus: bool,
     // Use `Option` to not apply hittest logic when it was never requested.
     pub cursor_hittest: Option<bool>,
+    // Whether the popup holds the pointer grab.
+    pub popup_grabbed: bool,
+    // The last known `_NET_WM_STATE`.
+    pub net_wm_state: Vec<xproto::Atom>,
+    // The bounds from the last `ConfigureNotify` in the normal state.
+    pub normal_bounds: Option<WindowBounds>,
+    // Whether the window holds the screen saver suspended.
+    pub idle_inhibited: bool,
+    // Whether the keyboard shortcuts inhibition was requested.
+    pub keyboard_shortcuts_inhibited: bool,
+    // Whether the window holds the keyboard grab inhibiting the shortcuts.
+    pub keyboard_grabbed: bool,
+    // The key repeat policy of the window.
+    pub key_repeat: KeyRepeat,
+    // The last known `_NET_WM_ALLOWED_ACTIONS`.
+    pub allowed_actions: Option<Vec<xproto::Atom>>,
+    // Whether the `normal_bounds` must be applied once the window is back in the normal state.
+    pub pending_normal_bounds: bool,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -103,6 +128,15 @@ impl SharedState {
             base_size: None,
             has_focus: false,
             cursor_hittest: None,
+            popup_grabbed: false,
+            net_wm_state: Vec::new(),
+            allowed_actions: None,
+            idle_inhibited: false,
+            keyboard_shortcuts_inhibited: false,
+            keyboard_grabbed: false,
+            key_repeat: KeyRepeat::System,
+            normal_bounds: None,
+            pending_normal_bounds: false,
         })
     }
 }
@@ -127,6 +161,10 @@ pub struct UnownedWindow {
     pub shared_state: Mutex<SharedState>,
     redraw_sender: WakeSender<WindowId>,
     activation_sender: WakeSender<super::ActivationToken>,
+    keyboard_grab_sender: WakeSender<WindowId>,
+    reset_dead_keys: ResetDeadKeys,
+    // Keeps the parent blocked while the modal window exists.
+    _modal_guard: Option<ModalGuard>,
 }
 
 macro_rules! leap {
@@ -185,10 +223,10 @@ impl UnownedWindow {
         let min_inner_size: Option<(u32, u32)> =
             window_attrs.min_inner_size.map(|size| size.to_physical::<u32>(scale_factor).into());
 
-        let position =
+        let mut position =
             window_attrs.position.map(|position| position.to_physical::<i32>(scale_factor));
 
-        let dimensions = {
+        let mut dimensions = {
             // x11 only applies constraints when the window is actively resized
             // by the user, so we have to manually apply the initial constraints
             let mut dimensions: (u32, u32) = window_attrs
@@ -209,6 +247,22 @@ impl UnownedWindow {
             dimensions
         };
 
+        // Popups are placed by us relative to their parent, since there's no window manager
+        // involved for override-redirect windows.
+        if let Some(popup) = window_attrs.popup.as_ref() {
+            let parent = event_loop
+                .windows
+                .borrow()
+                .get(&popup.parent.0)
+                .and_then(|parent| parent.upgrade())
+                .ok_or_else(|| os_error!(OsError::Misc("the parent of the popup doesn't exist")))?;
+
+            let rect = place_popup(xconn, root, &parent, popup, dimensions);
+            debug!("Calculated popup geometry: {:?}", rect);
+            position = Some(PhysicalPosition::new(rect.x, rect.y));
+            dimensions = (rect.width as u32, rect.height as u32);
+        }
+
         let screen_id = match window_attrs.platform_specific.x11.screen_id {
             Some(id) => id,
             None => xconn.default_screen_index() as c_int,
@@ -271,7 +325,8 @@ impl UnownedWindow {
 
             aux = aux.event_mask(event_mask).border_pixel(0);
 
-            if window_attrs.platform_specific.x11.override_redirect {
+            if window_attrs.platform_specific.x11.override_redirect || window_attrs.popup.is_some()
+            {
                 aux = aux.override_redirect(true as u32);
             }
 
@@ -349,6 +404,9 @@ impl UnownedWindow {
             shared_state: SharedState::new(guessed_monitor, &window_attrs),
             redraw_sender: event_loop.redraw_sender.clone(),
             activation_sender: event_loop.activation_sender.clone(),
+            keyboard_grab_sender: event_loop.keyboard_grab_sender.clone(),
+            reset_dead_keys: event_loop.reset_dead_keys.clone(),
+            _modal_guard: None,
         };
 
         // Title must be set before mapping. Some tiling window managers (i.e. i3) use the window
@@ -410,12 +468,28 @@ impl UnownedWindow {
                 .ignore_error();
             }
 
+            // Keep the dialog above its parent.
+            if let Some(parent) = window_attrs.transient_for {
+                leap!(xconn.change_property(
+                    window.xwindow,
+                    xproto::Atom::from(xproto::AtomEnum::WM_TRANSIENT_FOR),
+                    xproto::Atom::from(xproto::AtomEnum::WINDOW),
+                    xproto::PropMode::REPLACE,
+                    &[parent.0 .0 as xproto::Window],
+                ))
+                .ignore_error();
+            }
+
             if let Some(flusher) = leap!(window.set_pid()) {
                 flusher.ignore_error()
             }
 
-            leap!(window.set_window_types(window_attrs.platform_specific.x11.x11_window_types))
-                .ignore_error();
+            let window_types = match window_attrs.popup.as_ref() {
+                Some(popup) if popup.grab => vec![WindowType::PopupMenu],
+                Some(_) => vec![WindowType::Tooltip],
+                None => window_attrs.platform_specific.x11.x11_window_types,
+            };
+            leap!(window.set_window_types(window_types)).ignore_error();
 
             // Set size hints.
             let mut min_inner_size =
@@ -424,7 +498,7 @@ impl UnownedWindow {
                 window_attrs.max_inner_size.map(|size| size.to_physical::<u32>(scale_factor));
 
             if !window_attrs.resizable {
-                if util::wm_name_is_one_of(&["Xfwm4"]) {
+                if xconn.wm_name_is_one_of(&["Xfwm4"]) {
                     warn!("To avoid a WM bug, disabling resizing has no effect on Xfwm4");
                 } else {
                     max_inner_size = Some(dimensions.into());
@@ -518,6 +592,10 @@ impl UnownedWindow {
                     &xproto::ConfigureWindowAux::new().stack_mode(xproto::StackMode::ABOVE)
                 ))
                 .ignore_error();
+
+                if window_attrs.popup.as_ref().is_some_and(|popup| popup.grab) {
+                    window.grab_popup();
+                }
             }
 
             // Attempt to make keyboard input repeat detectable
@@ -543,7 +621,8 @@ impl UnownedWindow {
                 | xinput::XIEventMask::FOCUS_OUT
                 | xinput::XIEventMask::TOUCH_BEGIN
                 | xinput::XIEventMask::TOUCH_UPDATE
-                | xinput::XIEventMask::TOUCH_END;
+                | xinput::XIEventMask::TOUCH_END
+                | xinput::XIEventMask::TOUCH_OWNERSHIP;
             leap!(xconn.select_xinput_events(window.xwindow, super::ALL_MASTER_DEVICES, mask))
                 .ignore_error();
 
@@ -573,6 +652,11 @@ impl UnownedWindow {
             }
 
             leap!(window.set_window_level_inner(window_attrs.window_level)).ignore_error();
+
+            if let Some(parent) = window_attrs.transient_for.filter(|_| window_attrs.modal) {
+                leap!(window.toggle_atom(_NET_WM_STATE_MODAL, true)).ignore_error();
+                window._modal_guard = Some(event_loop.modal_blocker.block(parent.0));
+            }
         }
 
         window.set_cursor(window_attrs.cursor);
@@ -588,6 +672,60 @@ impl UnownedWindow {
         Ok(window)
     }
 
+    /// Grab the pointer for the popup, so presses outside of it could dismiss it.
+    fn grab_popup(&mut self) {
+        let mask = xinput::XIEventMask::BUTTON_PRESS
+            | xinput::XIEventMask::BUTTON_RELEASE
+            | xinput::XIEventMask::MOTION
+            | xinput::XIEventMask::ENTER
+            | xinput::XIEventMask::LEAVE;
+        let result = self
+            .xconn
+            .xcb_connection()
+            .xinput_xi_grab_device(
+                self.xwindow,
+                x11rb::CURRENT_TIME,
+                x11rb::NONE,
+                util::VIRTUAL_CORE_POINTER,
+                xproto::GrabMode::ASYNC,
+                xproto::GrabMode::ASYNC,
+                xinput::GrabOwner::NO_OWNER,
+                &[mask.into()],
+            )
+            .ok()
+            .and_then(|cookie| cookie.reply().ok());
+
+        match result.map(|reply| reply.status) {
+            Some(xproto::GrabStatus::SUCCESS) => {
+                self.shared_state.get_mut().unwrap().popup_grabbed = true
+            },
+            status => warn!("Failed to grab the pointer for the popup: {:?}", status),
+        }
+    }
+
+    /// Release the popup grab when the press at the given position is outside of the popup.
+    ///
+    /// Returns `true` when the popup was dismissed.
+    pub(crate) fn dismiss_popup_on_press(&self, x: f64, y: f64) -> bool {
+        let mut shared_state = self.shared_state_lock();
+        if !shared_state.popup_grabbed {
+            return false;
+        }
+
+        let (width, height) = self.inner_size_physical();
+        if x >= 0. && y >= 0. && x < width as f64 && y < height as f64 {
+            return false;
+        }
+
+        shared_state.popup_grabbed = false;
+        self.xconn
+            .xcb_connection()
+            .xinput_xi_ungrab_device(x11rb::CURRENT_TIME, util::VIRTUAL_CORE_POINTER)
+            .expect_then_ignore_error("Failed to call `xinput_xi_ungrab_device`");
+
+        true
+    }
+
     /// Embed this window into a parent window.
     pub(super) fn embed_window(&self) -> Result<(), RootOsError> {
         let atoms = self.xconn.atoms();
@@ -1004,6 +1142,138 @@ impl UnownedWindow {
         }
     }
 
+    /// Refresh the cached `_NET_WM_STATE` once the window manager changes it, returning the new
+    /// state if it has changed.
+    pub(crate) fn update_net_wm_state(&self) -> Option<WindowState> {
+        let net_wm_state = self
+            .xconn
+            .get_property(
+                self.xwindow,
+                self.xconn.atoms()[_NET_WM_STATE],
+                xproto::Atom::from(xproto::AtomEnum::ATOM),
+            )
+            .unwrap_or_default();
+
+        let mut shared_state = self.shared_state_lock();
+        let old_state = self.state_from(&shared_state);
+        shared_state.net_wm_state = net_wm_state;
+        let new_state = self.state_from(&shared_state);
+        (old_state != new_state).then_some(new_state)
+    }
+
+    fn state_from(&self, shared_state: &SharedState) -> WindowState {
+        let atoms = self.xconn.atoms();
+        let mut state = WindowState::empty();
+        for atom in &shared_state.net_wm_state {
+            if *atom == atoms[_NET_WM_STATE_MAXIMIZED_HORZ] {
+                state |= WindowState::TILED_LEFT | WindowState::TILED_RIGHT;
+            } else if *atom == atoms[_NET_WM_STATE_MAXIMIZED_VERT] {
+                state |= WindowState::TILED_TOP | WindowState::TILED_BOTTOM;
+            } else if *atom == atoms[_NET_WM_STATE_HIDDEN] {
+                state |= WindowState::SUSPENDED;
+            }
+        }
+        state
+    }
+
+    #[inline]
+    pub fn state(&self) -> WindowState {
+        self.state_from(&self.shared_state_lock())
+    }
+
+    /// Refresh the cached `_NET_WM_ALLOWED_ACTIONS` once the window manager changes it, returning
+    /// the new capabilities if they have changed.
+    pub(crate) fn update_allowed_actions(&self) -> Option<WmCapabilities> {
+        let allowed_actions = self
+            .xconn
+            .get_property(
+                self.xwindow,
+                self.xconn.atoms()[_NET_WM_ALLOWED_ACTIONS],
+                xproto::Atom::from(xproto::AtomEnum::ATOM),
+            )
+            .unwrap_or_default();
+
+        let old_capabilities = self.wm_capabilities();
+        self.shared_state_lock().allowed_actions = Some(allowed_actions);
+        let new_capabilities = self.wm_capabilities();
+        new_capabilities.filter(|_| old_capabilities != new_capabilities)
+    }
+
+    #[inline]
+    pub fn wm_capabilities(&self) -> Option<WmCapabilities> {
+        let atoms = self.xconn.atoms();
+        if !self.xconn.hint_is_supported(atoms[_NET_WM_ALLOWED_ACTIONS]) {
+            return None;
+        }
+
+        let shared_state = self.shared_state_lock();
+        let allowed_actions = shared_state.allowed_actions.as_ref()?;
+        let mut capabilities = WmCapabilities::empty();
+        capabilities.set(
+            WmCapabilities::MAXIMIZE,
+            allowed_actions.contains(&atoms[_NET_WM_ACTION_MAXIMIZE_HORZ])
+                && allowed_actions.contains(&atoms[_NET_WM_ACTION_MAXIMIZE_VERT]),
+        );
+        capabilities.set(
+            WmCapabilities::FULLSCREEN,
+            allowed_actions.contains(&atoms[_NET_WM_ACTION_FULLSCREEN]),
+        );
+        capabilities.set(
+            WmCapabilities::MINIMIZE,
+            allowed_actions.contains(&atoms[_NET_WM_ACTION_MINIMIZE]),
+        );
+        Some(capabilities)
+    }
+
+    /// Whether the window is neither maximized nor fullscreen according to the cached
+    /// `_NET_WM_STATE`.
+    pub(crate) fn is_normal_state(&self, shared_state: &SharedState) -> bool {
+        let atoms = self.xconn.atoms();
+        let non_normal = [
+            atoms[_NET_WM_STATE_MAXIMIZED_HORZ],
+            atoms[_NET_WM_STATE_MAXIMIZED_VERT],
+            atoms[_NET_WM_STATE_FULLSCREEN],
+        ];
+        !shared_state.net_wm_state.iter().any(|atom| non_normal.contains(atom))
+    }
+
+    #[inline]
+    pub fn normal_bounds(&self) -> Option<WindowBounds> {
+        self.shared_state_lock().normal_bounds
+    }
+
+    #[inline]
+    pub fn set_normal_bounds(&self, position: Option<Position>, inner_size: Size) {
+        let scale_factor = self.scale_factor();
+        let position = position.map(|position| position.to_physical::<i32>(scale_factor).into());
+        let (width, height) = inner_size.to_physical::<u32>(scale_factor).into();
+
+        let mut shared_state = self.shared_state_lock();
+        if !self.is_normal_state(&shared_state) {
+            // NOTE: The bounds are applied on the first `ConfigureNotify` in the normal state.
+            let outer_size = match shared_state.frame_extents.as_ref() {
+                Some(extents) => extents.inner_size_to_outer(width, height),
+                None => (width, height),
+            };
+            let position = position
+                .map(Into::into)
+                .or_else(|| shared_state.normal_bounds.and_then(|bounds| bounds.position));
+            shared_state.normal_bounds = Some(WindowBounds {
+                position,
+                inner_size: (width, height).into(),
+                outer_size: outer_size.into(),
+            });
+            shared_state.pending_normal_bounds = true;
+            return;
+        }
+        drop(shared_state);
+
+        if let Some((x, y)) = position {
+            self.set_position_physical(x, y);
+        }
+        self.request_inner_size_physical(width, height);
+    }
+
     fn set_maximized_inner(&self, maximized: bool) -> Result<VoidCookie<'_>, X11Error> {
         let atoms = self.xconn.atoms();
         let horz_atom = atoms[_NET_WM_STATE_MAXIMIZED_HORZ];
@@ -1055,6 +1325,110 @@ impl UnownedWindow {
     #[inline]
     pub fn set_blur(&self, _blur: bool) {}
 
+    #[inline]
+    pub fn set_idle_inhibit(&self, inhibit: bool) {
+        let mut shared_state = self.shared_state_lock();
+        if shared_state.idle_inhibited == inhibit {
+            return;
+        }
+
+        if self
+            .xconn
+            .xcb_connection()
+            .extension_information(screensaver::X11_EXTENSION_NAME)
+            .ok()
+            .flatten()
+            .is_none()
+        {
+            warn!("MIT-SCREEN-SAVER extension unavailable, unable to inhibit idle");
+            return;
+        }
+
+        // NOTE: The suspend requests are counted by the server, thus every window holds its own.
+        self.xconn
+            .xcb_connection()
+            .screensaver_suspend(inhibit.into())
+            .expect_then_ignore_error("Failed to suspend the screen saver");
+        self.xconn.flush_requests().expect("Failed to suspend the screen saver");
+        shared_state.idle_inhibited = inhibit;
+    }
+
+    #[inline]
+    pub fn set_keyboard_shortcuts_inhibited(&self, inhibited: bool) {
+        self.shared_state_lock().keyboard_shortcuts_inhibited = inhibited;
+        // The grab is updated from the event loop, so the change could be reported.
+        self.keyboard_grab_sender.send(self.id());
+    }
+
+    #[inline]
+    pub fn set_key_repeat(&self, repeat: KeyRepeat) {
+        self.shared_state_lock().key_repeat = repeat;
+    }
+
+    pub fn key_repeat_rate(&self) -> Option<KeyRepeatRate> {
+        match self.shared_state_lock().key_repeat {
+            KeyRepeat::System => (),
+            KeyRepeat::Disabled => return None,
+            KeyRepeat::Custom(rate) => return Some(rate),
+        }
+
+        let controls = self
+            .xconn
+            .xcb_connection()
+            .xkb_get_controls(XkbId::USE_CORE_KBD.into())
+            .ok()?
+            .reply()
+            .ok()?;
+        if !controls.enabled_controls.contains(BoolCtrl::REPEAT_KEYS) {
+            return None;
+        }
+
+        Some(KeyRepeatRate {
+            delay: Duration::from_millis(controls.repeat_delay.into()),
+            interval: Duration::from_millis(controls.repeat_interval.into()),
+        })
+    }
+
+    /// Grab or release the keyboard depending on the focus and the requested inhibition.
+    ///
+    /// Returns the new state when it has changed.
+    pub(crate) fn update_keyboard_grab(&self) -> Option<bool> {
+        let mut shared_state = self.shared_state_lock();
+        let grab = shared_state.keyboard_shortcuts_inhibited && shared_state.has_focus;
+        if grab == shared_state.keyboard_grabbed {
+            return None;
+        }
+
+        if grab {
+            let status = self
+                .xconn
+                .xcb_connection()
+                .grab_keyboard(
+                    true,
+                    self.xwindow,
+                    x11rb::CURRENT_TIME,
+                    xproto::GrabMode::ASYNC,
+                    xproto::GrabMode::ASYNC,
+                )
+                .ok()
+                .and_then(|cookie| cookie.reply().ok())
+                .map(|reply| reply.status);
+            if status != Some(xproto::GrabStatus::SUCCESS) {
+                warn!("Failed to grab the keyboard to inhibit the shortcuts: {:?}", status);
+                return None;
+            }
+        } else {
+            self.xconn
+                .xcb_connection()
+                .ungrab_keyboard(x11rb::CURRENT_TIME)
+                .expect_then_ignore_error("Failed to call `ungrab_keyboard`");
+            self.xconn.flush_requests().expect("Failed to call `ungrab_keyboard`");
+        }
+
+        shared_state.keyboard_grabbed = grab;
+        Some(grab)
+    }
+
     fn set_decorations_inner(&self, decorations: bool) -> Result<VoidCookie<'_>, X11Error> {
         self.shared_state_lock().is_decorated = decorations;
         let mut hints = self.xconn.get_motif_hints(self.xwindow);
@@ -1233,7 +1607,7 @@ impl UnownedWindow {
     ) -> Result<VoidCookie<'_>, X11Error> {
         // There are a few WMs that set client area position rather than window position, so
         // we'll translate for consistency.
-        if util::wm_name_is_one_of(&["Enlightenment", "FVWM"]) {
+        if self.xconn.wm_name_is_one_of(&["Enlightenment", "FVWM"]) {
             let extents = self.shared_state_lock().frame_extents.clone();
             if let Some(extents) = extents {
                 x += cast_dimension_to_hint(extents.frame_extents.left);
@@ -1424,7 +1798,7 @@ impl UnownedWindow {
     }
 
     pub fn set_resizable(&self, resizable: bool) {
-        if util::wm_name_is_one_of(&["Xfwm4"]) {
+        if self.xconn.wm_name_is_one_of(&["Xfwm4"]) {
             // Making the window unresizable on Xfwm prevents further changes to `WM_NORMAL_HINTS`
             // from being detected. This makes it impossible for resizing to be
             // re-enabled, and also breaks DPI scaling. As such, we choose the lesser of
@@ -1828,6 +2202,11 @@ impl UnownedWindow {
         Ok(token)
     }
 
+    #[inline]
+    pub fn reset_dead_keys(&self) {
+        xkb::reset_dead_keys(&self.reset_dead_keys)
+    }
+
     #[inline]
     pub fn request_activation_token(&self) -> Result<AsyncRequestSerial, NotSupportedError> {
         let serial = AsyncRequestSerial::get();
@@ -1922,6 +2301,54 @@ impl UnownedWindow {
     }
 }
 
+/// Compute the geometry of the popup in the root window coordinates.
+fn place_popup(
+    xconn: &XConnection,
+    root: xproto::Window,
+    parent: &UnownedWindow,
+    popup: &PopupAttributes,
+    (width, height): (u32, u32),
+) -> util::PopupRect {
+    let scale_factor = parent.scale_factor();
+    let (parent_x, parent_y) = parent.inner_position_physical();
+    let anchor_position: PhysicalPosition<i32> =
+        popup.anchor_rect_position.to_physical(scale_factor);
+    let anchor_size: PhysicalSize<i32> = popup.anchor_rect_size.to_physical(scale_factor);
+    let offset: PhysicalPosition<i32> = popup.offset.to_physical(scale_factor);
+
+    let positioner = util::PopupPositioner {
+        anchor_rect: util::PopupRect::new(
+            parent_x + anchor_position.x,
+            parent_y + anchor_position.y,
+            anchor_size.width.max(1),
+            anchor_size.height.max(1),
+        ),
+        width: width as i32,
+        height: height as i32,
+        anchor: popup.anchor,
+        gravity: popup.gravity,
+        offset: (offset.x, offset.y),
+        constraint_adjustment: popup.constraint_adjustment,
+    };
+
+    // Constrain the popup to the monitor the anchor is on, excluding the panels and docks.
+    let (anchor_x, anchor_y) = (positioner.anchor_rect.x as i64, positioner.anchor_rect.y as i64);
+    let monitor = xconn.available_monitors().ok().and_then(|monitors| {
+        monitors.into_iter().find(|monitor| monitor.rect.contains_point(anchor_x, anchor_y))
+    });
+    let bounds = monitor.map(|monitor| {
+        let (position, size) = (monitor.position(), monitor.size());
+        let monitor =
+            util::PopupRect::new(position.x, position.y, size.width as i32, size.height as i32);
+        xconn
+            .get_work_area(root)
+            .and_then(|work_area| work_area.intersection(&monitor))
+            .unwrap_or(monitor)
+    });
+
+    positioner.place(bounds.as_ref())
+}
+
 /// Cast a dimension value into a hinted dimension for `WmSizeHints`, clamping if too large.
 fn cast_dimension_to_hint(val: u32) -> i32 {
     val.try_into().unwrap_or(i32::MAX)
@@ -55,6 +55,12 @@ pub struct XConnection {
     /// Atom for the XSettings screen.
     xsettings_screen: Option<xproto::Atom>,
 
+    /// The hints supported by the window manager.
+    pub(crate) supported_hints: Mutex<Vec<xproto::Atom>>,
+
+    /// The name of the window manager.
+    pub(crate) wm_name: Mutex<Option<String>>,
+
     pub latest_error: Mutex<Option<XError>>,
     pub cursor_cache: Mutex<HashMap<Option<CursorIcon>, ffi::Cursor>>,
 }
@@ -133,6 +139,8 @@ impl XConnection {
             atoms: Box::new(atoms),
             default_screen,
             timestamp: AtomicU32::new(0),
+            supported_hints: Default::default(),
+            wm_name: Default::default(),
             latest_error: Mutex::new(None),
             monitor_handles: Mutex::new(None),
             database: RwLock::new(database),
@@ -18,7 +18,7 @@ use super::{
 };
 use crate::application::ApplicationHandler;
 use crate::error::EventLoopError;
-use crate::event::{self, Ime, Modifiers, StartCause};
+use crate::event::{self, DeviceInfo, Ime, Modifiers, StartCause};
 use crate::event_loop::{self, ControlFlow, DeviceEvents};
 use crate::keyboard::{
     Key, KeyCode, KeyLocation, ModifiersKeys, ModifiersState, NamedKey, NativeKey, NativeKeyCode,
@@ -267,7 +267,7 @@ impl EventState {
         pressed_mods
             .set(ModifiersKeys::RSUPER, self.keyboard.contains(KeyboardModifierState::RSUPER));
 
-        Modifiers { state, pressed_mods }
+        Modifiers { state, pressed_mods, lock_state: Default::default() }
     }
 }
 
@@ -450,6 +450,8 @@ impl EventLoop {
                         device_id: event::DeviceId(DeviceId),
                         delta: event::MouseScrollDelta::LineDelta(x as f32, y as f32),
                         phase: event::TouchPhase::Moved,
+                        source: event::ScrollSource::Unknown,
+                        inverted: false,
                     },
                 );
             },
@@ -744,6 +746,11 @@ impl ActiveEventLoop {
         v
     }
 
+    #[inline]
+    pub fn devices(&self) -> Vec<DeviceInfo> {
+        Vec::new()
+    }
+
     #[inline]
     pub fn listen_device_events(&self, _allowed: DeviceEvents) {}
 
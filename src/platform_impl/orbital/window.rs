@@ -7,7 +7,7 @@ use super::{
 use crate::cursor::Cursor;
 use crate::dpi::{PhysicalPosition, PhysicalSize, Position, Size};
 use crate::platform_impl::Fullscreen;
-use crate::window::ImePurpose;
+use crate::window::{ImePurpose, KeyRepeat, KeyRepeatRate};
 use crate::{error, window};
 
 // These values match the values uses in the `window_new` function in orbital:
@@ -265,6 +265,20 @@ impl Window {
     #[inline]
     pub fn set_blur(&self, _blur: bool) {}
 
+    #[inline]
+    pub fn set_idle_inhibit(&self, _inhibit: bool) {}
+
+    #[inline]
+    pub fn set_keyboard_shortcuts_inhibited(&self, _inhibited: bool) {}
+
+    #[inline]
+    pub fn set_key_repeat(&self, _repeat: KeyRepeat) {}
+
+    #[inline]
+    pub fn key_repeat_rate(&self) -> Option<KeyRepeatRate> {
+        None
+    }
+
     #[inline]
     pub fn set_visible(&self, visible: bool) {
         let _ = self.set_flag(ORBITAL_FLAG_HIDDEN, !visible);
@@ -311,6 +325,24 @@ impl Window {
         self.get_flag(ORBITAL_FLAG_MAXIMIZED).unwrap_or(false)
     }
 
+    #[inline]
+    pub fn normal_bounds(&self) -> Option<window::WindowBounds> {
+        None
+    }
+
+    #[inline]
+    pub fn set_normal_bounds(&self, _position: Option<Position>, _inner_size: Size) {}
+
+    #[inline]
+    pub fn state(&self) -> window::WindowState {
+        window::WindowState::empty()
+    }
+
+    #[inline]
+    pub fn wm_capabilities(&self) -> Option<window::WmCapabilities> {
+        None
+    }
+
     #[inline]
     pub(crate) fn set_fullscreen(&self, _monitor: Option<Fullscreen>) {}
 
@@ -13,7 +13,8 @@ use super::window::WindowId;
 use super::{backend, runner, EventLoopProxy};
 use crate::error::NotSupportedError;
 use crate::event::{
-    DeviceId as RootDeviceId, ElementState, Event, KeyEvent, Touch, TouchPhase, WindowEvent,
+    DeviceId as RootDeviceId, DeviceInfo, ElementState, Event, KeyEvent, ScrollSource, Touch,
+    TouchPhase, WindowEvent,
 };
 use crate::event_loop::{ControlFlow, DeviceEvents};
 use crate::keyboard::ModifiersState;
@@ -312,6 +313,8 @@ impl ActiveEventLoop {
                                 device_id: RootDeviceId(DeviceId(device_id)),
                                 phase: TouchPhase::Moved,
                                 force: Some(force),
+                                contact_size: None,
+                                orientation: None,
                                 location,
                             }),
                         },
@@ -418,6 +421,8 @@ impl ActiveEventLoop {
                                 device_id: RootDeviceId(DeviceId(device_id)),
                                 phase: TouchPhase::Started,
                                 force: Some(force),
+                                contact_size: None,
+                                orientation: None,
                                 location,
                             }),
                         },
@@ -486,6 +491,8 @@ impl ActiveEventLoop {
                                 device_id: RootDeviceId(DeviceId(device_id)),
                                 phase: TouchPhase::Ended,
                                 force: Some(force),
+                                contact_size: None,
+                                orientation: None,
                                 location,
                             }),
                         },
@@ -513,6 +520,8 @@ impl ActiveEventLoop {
                         device_id: RootDeviceId(DeviceId(pointer_id)),
                         delta,
                         phase: TouchPhase::Moved,
+                        source: ScrollSource::Unknown,
+                        inverted: false,
                     },
                 },
             )));
@@ -527,6 +536,8 @@ impl ActiveEventLoop {
                     device_id: RootDeviceId(DeviceId(device_id)),
                     phase: TouchPhase::Cancelled,
                     force: Some(force),
+                    contact_size: None,
+                    orientation: None,
                     location,
                 }),
             });
@@ -615,6 +626,11 @@ impl ActiveEventLoop {
         Ok(rwh_06::RawDisplayHandle::Web(rwh_06::WebDisplayHandle::new()))
     }
 
+    #[inline]
+    pub fn devices(&self) -> Vec<DeviceInfo> {
+        Vec::new()
+    }
+
     pub fn listen_device_events(&self, allowed: DeviceEvents) {
         self.runner.listen_device_events(allowed)
     }
@@ -12,8 +12,9 @@ use crate::dpi::{PhysicalPosition, PhysicalSize, Position, Size};
 use crate::error::{ExternalError, NotSupportedError, OsError as RootOE};
 use crate::icon::Icon;
 use crate::window::{
-    Cursor, CursorGrabMode, ImePurpose, ResizeDirection, Theme, UserAttentionType,
-    WindowAttributes, WindowButtons, WindowId as RootWI, WindowLevel,
+    Cursor, CursorGrabMode, ImePurpose, KeyRepeat, KeyRepeatRate, ResizeDirection, Theme,
+    UserAttentionType, WindowAttributes, WindowBounds, WindowButtons, WindowId as RootWI,
+    WindowLevel, WindowState, WmCapabilities,
 };
 
 pub struct Window {
@@ -125,6 +126,16 @@ impl Inner {
 
     pub fn set_blur(&self, _blur: bool) {}
 
+    pub fn set_idle_inhibit(&self, _inhibit: bool) {}
+
+    pub fn set_keyboard_shortcuts_inhibited(&self, _inhibited: bool) {}
+
+    pub fn set_key_repeat(&self, _repeat: KeyRepeat) {}
+
+    pub fn key_repeat_rate(&self) -> Option<KeyRepeatRate> {
+        None
+    }
+
     pub fn set_visible(&self, _visible: bool) {
         // Intentionally a no-op
     }
@@ -309,6 +320,24 @@ impl Inner {
         false
     }
 
+    #[inline]
+    pub fn normal_bounds(&self) -> Option<WindowBounds> {
+        None
+    }
+
+    #[inline]
+    pub fn set_normal_bounds(&self, _position: Option<Position>, _inner_size: Size) {}
+
+    #[inline]
+    pub fn state(&self) -> WindowState {
+        WindowState::empty()
+    }
+
+    #[inline]
+    pub fn wm_capabilities(&self) -> Option<WmCapabilities> {
+        None
+    }
+
     #[inline]
     pub(crate) fn fullscreen(&self) -> Option<Fullscreen> {
         if self.canvas.is_fullscreen() {
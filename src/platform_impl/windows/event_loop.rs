@@ -61,7 +61,8 @@ use crate::application::ApplicationHandler;
 use crate::dpi::{PhysicalPosition, PhysicalSize};
 use crate::error::EventLoopError;
 use crate::event::{
-    DeviceEvent, Event, Force, Ime, InnerSizeWriter, RawKeyEvent, Touch, TouchPhase, WindowEvent,
+    DeviceEvent, DeviceInfo, Event, Force, Ime, InnerSizeWriter, RawKeyEvent, ScrollSource, Touch,
+    TouchPhase, WindowEvent,
 };
 use crate::event_loop::{ActiveEventLoop as RootAEL, ControlFlow, DeviceEvents};
 use crate::keyboard::ModifiersState;
@@ -518,6 +519,11 @@ impl ActiveEventLoop {
         Ok(rwh_06::RawDisplayHandle::Windows(rwh_06::WindowsDisplayHandle::new()))
     }
 
+    #[inline]
+    pub fn devices(&self) -> Vec<DeviceInfo> {
+        Vec::new()
+    }
+
     pub fn listen_device_events(&self, allowed: DeviceEvents) {
         raw_input::register_all_mice_and_keyboards_for_raw_input(self.thread_msg_target, allowed);
     }
@@ -1589,6 +1595,8 @@ unsafe fn public_window_callback_inner(
                     device_id: DEVICE_ID,
                     delta: LineDelta(0.0, value),
                     phase: TouchPhase::Moved,
+                    source: ScrollSource::Unknown,
+                    inverted: false,
                 },
             });
 
@@ -1609,6 +1617,8 @@ unsafe fn public_window_callback_inner(
                     device_id: DEVICE_ID,
                     delta: LineDelta(value, 0.0),
                     phase: TouchPhase::Moved,
+                    source: ScrollSource::Unknown,
+                    inverted: false,
                 },
             });
 
@@ -1823,6 +1833,8 @@ unsafe fn public_window_callback_inner(
                             },
                             location,
                             force: None, // WM_TOUCH doesn't support pressure information
+                            contact_size: None,
+                            orientation: None,
                             id: input.dwID as u64,
                             device_id: DEVICE_ID,
                         }),
@@ -1969,6 +1981,8 @@ unsafe fn public_window_callback_inner(
                             },
                             location,
                             force,
+                            contact_size: None,
+                            orientation: None,
                             id: pointer_info.pointerId as u64,
                             device_id: DEVICE_ID,
                         }),
@@ -68,8 +68,9 @@ use crate::platform_impl::platform::window_state::{
 };
 use crate::platform_impl::platform::{util, Fullscreen, SelectedCursor, WindowId};
 use crate::window::{
-    CursorGrabMode, ImePurpose, ResizeDirection, Theme, UserAttentionType, WindowAttributes,
-    WindowButtons, WindowLevel,
+    CursorGrabMode, ImePurpose, KeyRepeat, KeyRepeatRate, ResizeDirection, Theme,
+    UserAttentionType, WindowAttributes, WindowBounds, WindowButtons, WindowLevel, WindowState,
+    WmCapabilities,
 };
 
 /// The Win32 implementation of the main `Window` object.
@@ -130,6 +131,18 @@ impl Window {
 
     pub fn set_blur(&self, _blur: bool) {}
 
+    pub fn set_idle_inhibit(&self, _inhibit: bool) {}
+
+    pub fn set_keyboard_shortcuts_inhibited(&self, _inhibited: bool) {}
+
+    #[inline]
+    pub fn set_key_repeat(&self, _repeat: KeyRepeat) {}
+
+    #[inline]
+    pub fn key_repeat_rate(&self) -> Option<KeyRepeatRate> {
+        None
+    }
+
     #[inline]
     pub fn set_visible(&self, visible: bool) {
         let window = self.window;
@@ -692,6 +705,24 @@ impl Window {
         window_state.window_flags.contains(WindowFlags::MAXIMIZED)
     }
 
+    #[inline]
+    pub fn normal_bounds(&self) -> Option<WindowBounds> {
+        None
+    }
+
+    #[inline]
+    pub fn set_normal_bounds(&self, _position: Option<Position>, _inner_size: Size) {}
+
+    #[inline]
+    pub fn state(&self) -> WindowState {
+        WindowState::empty()
+    }
+
+    #[inline]
+    pub fn wm_capabilities(&self) -> Option<WmCapabilities> {
+        None
+    }
+
     #[inline]
     pub fn fullscreen(&self) -> Option<Fullscreen> {
         let window_state = self.window_state_lock();
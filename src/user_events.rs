@@ -0,0 +1,257 @@
+//! The queue of the user events sent through the [`EventLoopProxy`].
+//!
+//! The events are delivered by wrapping the application handler, which drains the queue once the
+//! proxy wakes up the event loop, thus the backends only have to implement the wake up.
+//!
+//! [`EventLoopProxy`]: crate::event_loop::EventLoopProxy
+
+use std::collections::VecDeque;
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+use crate::application::ApplicationHandler;
+use crate::event::{DeviceEvent, DeviceId, ElementState, StartCause, WindowEvent};
+use crate::event_loop::{
+    ActiveEventLoop, FdId, FdReadiness, GlobalShortcutId, IdleTimeoutId, SendError, TimerId,
+};
+use crate::window::WindowId;
+
+/// The default capacity of the user event queue.
+pub(crate) const DEFAULT_CAPACITY: usize = 1024;
+
+struct QueueState<T> {
+    events: VecDeque<T>,
+    closed: bool,
+}
+
+/// The receiving side of the queue owned by the event loop.
+///
+/// The queue is closed once it's dropped, so the proxies could tell that the loop has exited.
+pub(crate) struct UserEventQueue<T> {
+    state: Arc<Mutex<QueueState<T>>>,
+    capacity: usize,
+}
+
+impl<T> UserEventQueue<T> {
+    pub fn new(capacity: usize) -> Self {
+        let state = QueueState { events: VecDeque::new(), closed: false };
+        Self { state: Arc::new(Mutex::new(state)), capacity }
+    }
+
+    pub fn sender(&self) -> UserEventSender<T> {
+        UserEventSender { state: self.state.clone(), capacity: self.capacity }
+    }
+
+    /// Take the events queued so far.
+    ///
+    /// The events sent while the taken ones are handled are left for the next wake up, so the
+    /// handler sending the events can't starve the event loop.
+    pub fn take(&self) -> VecDeque<T> {
+        mem::take(&mut self.state.lock().unwrap().events)
+    }
+}
+
+impl<T> Drop for UserEventQueue<T> {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        state.events.clear();
+    }
+}
+
+/// The sending side of the queue, shared by the proxies.
+pub(crate) struct UserEventSender<T> {
+    state: Arc<Mutex<QueueState<T>>>,
+    capacity: usize,
+}
+
+impl<T> UserEventSender<T> {
+    /// Queue the event, the caller is responsible for waking up the event loop.
+    pub fn send(&self, event: T) -> Result<(), SendError<T>> {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return Err(SendError::Closed(event));
+        }
+
+        if state.events.len() >= self.capacity {
+            return Err(SendError::Full(event));
+        }
+
+        state.events.push_back(event);
+        Ok(())
+    }
+}
+
+impl<T> Clone for UserEventSender<T> {
+    fn clone(&self) -> Self {
+        Self { state: self.state.clone(), capacity: self.capacity }
+    }
+}
+
+/// The application handler delivering the user events to the wrapped one.
+pub(crate) struct UserEventHandler<T, A> {
+    app: A,
+    queue: Arc<UserEventQueue<T>>,
+}
+
+impl<T, A> UserEventHandler<T, A> {
+    pub fn new(app: A, queue: Arc<UserEventQueue<T>>) -> Self {
+        Self { app, queue }
+    }
+}
+
+impl<T: 'static, A: ApplicationHandler<T>> ApplicationHandler for UserEventHandler<T, A> {
+    #[inline]
+    fn new_events(&mut self, event_loop: &ActiveEventLoop, cause: StartCause) {
+        self.app.new_events(event_loop, cause);
+    }
+
+    #[inline]
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        self.app.resumed(event_loop);
+    }
+
+    #[inline]
+    fn can_create_surfaces(&mut self, event_loop: &ActiveEventLoop) {
+        self.app.can_create_surfaces(event_loop);
+    }
+
+    fn proxy_wake_up(&mut self, event_loop: &ActiveEventLoop) {
+        for event in self.queue.take() {
+            self.app.user_event(event_loop, event);
+        }
+
+        self.app.proxy_wake_up(event_loop);
+    }
+
+    #[inline]
+    fn window_event(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        window_id: WindowId,
+        event: WindowEvent,
+    ) {
+        self.app.window_event(event_loop, window_id, event);
+    }
+
+    #[inline]
+    fn device_event(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        device_id: DeviceId,
+        event: DeviceEvent,
+    ) {
+        self.app.device_event(event_loop, device_id, event);
+    }
+
+    #[inline]
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        self.app.about_to_wait(event_loop);
+    }
+
+    #[inline]
+    fn suspended(&mut self, event_loop: &ActiveEventLoop) {
+        self.app.suspended(event_loop);
+    }
+
+    #[inline]
+    fn destroy_surfaces(&mut self, event_loop: &ActiveEventLoop) {
+        self.app.destroy_surfaces(event_loop);
+    }
+
+    #[inline]
+    fn exiting(&mut self, event_loop: &ActiveEventLoop) {
+        self.app.exiting(event_loop);
+    }
+
+    #[inline]
+    fn memory_warning(&mut self, event_loop: &ActiveEventLoop) {
+        self.app.memory_warning(event_loop);
+    }
+
+    #[inline]
+    fn fd_ready(&mut self, event_loop: &ActiveEventLoop, fd_id: FdId, readiness: FdReadiness) {
+        self.app.fd_ready(event_loop, fd_id, readiness);
+    }
+
+    #[inline]
+    fn timer_expired(&mut self, event_loop: &ActiveEventLoop, timer_id: TimerId) {
+        self.app.timer_expired(event_loop, timer_id);
+    }
+
+    #[inline]
+    fn idle_changed(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        timeout_id: IdleTimeoutId,
+        idle: bool,
+    ) {
+        self.app.idle_changed(event_loop, timeout_id, idle);
+    }
+
+    #[inline]
+    fn global_shortcut(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        shortcut_id: GlobalShortcutId,
+        state: ElementState,
+    ) {
+        self.app.global_shortcut(event_loop, shortcut_id, state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_ordered() {
+        let queue = UserEventQueue::new(DEFAULT_CAPACITY);
+        let first = queue.sender();
+        let second = first.clone();
+
+        first.send(1).unwrap();
+        second.send(2).unwrap();
+        first.send(3).unwrap();
+
+        assert_eq!(queue.take(), [1, 2, 3]);
+        assert!(queue.take().is_empty());
+    }
+
+    #[test]
+    fn events_sent_during_delivery_are_deferred() {
+        let queue = UserEventQueue::new(DEFAULT_CAPACITY);
+        let sender = queue.sender();
+        sender.send(1).unwrap();
+
+        for event in queue.take() {
+            sender.send(event + 1).unwrap();
+        }
+
+        assert_eq!(queue.take(), [2]);
+    }
+
+    #[test]
+    fn full_queue() {
+        let queue = UserEventQueue::new(2);
+        let sender = queue.sender();
+
+        sender.send(1).unwrap();
+        sender.send(2).unwrap();
+        assert_eq!(sender.send(3), Err(SendError::Full(3)));
+
+        assert_eq!(queue.take(), [1, 2]);
+        sender.send(3).unwrap();
+        assert_eq!(queue.take(), [3]);
+    }
+
+    #[test]
+    fn closed_queue() {
+        let queue = UserEventQueue::new(DEFAULT_CAPACITY);
+        let sender = queue.sender();
+        sender.send(1).unwrap();
+
+        drop(queue);
+        assert_eq!(sender.send(2), Err(SendError::Closed(2)));
+    }
+}
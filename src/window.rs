@@ -1,5 +1,6 @@
 //! The [`Window`] struct and associated types.
 use std::fmt;
+use std::time::Duration;
 
 #[doc(inline)]
 pub use cursor_icon::{CursorIcon, ParseError as CursorIconParseError};
@@ -11,6 +12,8 @@ use crate::dpi::{PhysicalPosition, PhysicalSize, Position, Size};
 use crate::error::{ExternalError, NotSupportedError};
 pub use crate::icon::{BadIcon, Icon};
 use crate::monitor::{MonitorHandle, VideoModeHandle};
+#[cfg(feature = "serde")]
+pub use crate::placement::{MonitorIdentity, WindowPlacement};
 use crate::platform_impl::{self, PlatformSpecificWindowAttributes};
 
 /// Represents a window.
@@ -123,6 +126,11 @@ pub struct WindowAttributes {
     #[cfg(feature = "rwh_06")]
     pub(crate) parent_window: Option<SendSyncRawWindowHandle>,
     pub fullscreen: Option<Fullscreen>,
+    pub popup: Option<PopupAttributes>,
+    pub transient_for: Option<WindowId>,
+    pub modal: bool,
+    #[cfg(feature = "serde")]
+    pub(crate) placement: Option<WindowPlacement>,
     // Platform-specific configuration.
     #[allow(dead_code)]
     pub(crate) platform_specific: PlatformSpecificWindowAttributes,
@@ -153,6 +161,11 @@ impl Default for WindowAttributes {
             cursor: Cursor::default(),
             #[cfg(feature = "rwh_06")]
             parent_window: None,
+            popup: None,
+            transient_for: None,
+            modal: false,
+            #[cfg(feature = "serde")]
+            placement: None,
             active: true,
             platform_specific: Default::default(),
         }
@@ -459,7 +472,9 @@ impl WindowAttributes {
     ///   to the client area of its parent window. For more information, see
     ///   <https://docs.microsoft.com/en-us/windows/win32/winmsg/window-features#child-windows>
     /// - **X11**: A child window is confined to the client area of its parent window.
-    /// - **Android / iOS / Wayland / Web:** Unsupported.
+    /// - **Wayland:** A child window is a `wl_subsurface` of its parent, which must be a window of
+    ///   the same event loop. See `WindowExtWayland` for the stacking order and commit mode.
+    /// - **Android / iOS / Web:** Unsupported.
     #[cfg(feature = "rwh_06")]
     #[inline]
     pub unsafe fn with_parent_window(
@@ -469,6 +484,221 @@ impl WindowAttributes {
         self.parent_window = parent_window.map(SendSyncRawWindowHandle);
         self
     }
+
+    /// Sets the window the dialog is transient for.
+    ///
+    /// The window manager keeps the dialog above its parent, and usually places it on top of the
+    /// parent and doesn't show it in the taskbar.
+    ///
+    /// The default is `None`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Sets `WM_TRANSIENT_FOR`.
+    /// - **Wayland:** Sets the parent of the `xdg_toplevel`.
+    /// - **Windows / macOS / iOS / Android / Web / Orbital:** Unsupported.
+    #[inline]
+    pub fn with_transient_for(mut self, parent: Option<WindowId>) -> Self {
+        self.transient_for = parent;
+        self
+    }
+
+    /// Sets whether the dialog is modal for the window it's transient for.
+    ///
+    /// While the modal dialog exists, its parent doesn't receive the keyboard, pointer, touch,
+    /// and IME input. Has no effect without [`WindowAttributes::with_transient_for`].
+    ///
+    /// The default is `false`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Sets `_NET_WM_STATE_MODAL`.
+    /// - **Wayland:** Uses `xdg_dialog_v1` when the compositor supports it, the input is filtered
+    ///   regardless.
+    /// - **Windows / macOS / iOS / Android / Web / Orbital:** Unsupported.
+    #[inline]
+    pub fn with_modal(mut self, modal: bool) -> Self {
+        self.modal = modal;
+        self
+    }
+
+    /// Build the window as a popup attached to another window.
+    ///
+    /// Popups are short-lived surfaces, like menus, combo box lists, or tooltips, which are
+    /// positioned relative to their parent with the rules described by [`PopupAttributes`]. The
+    /// size of the popup is taken from [`WindowAttributes::inner_size`]. When the popup is
+    /// dismissed by the system, [`WindowEvent::PopupDismissed`] is sent and the popup should be
+    /// dropped.
+    ///
+    /// The default is `None`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Created as an `xdg_popup`, the compositor does the constraint solving.
+    /// - **X11:** Created as an override-redirect window, positioned against the work area of the
+    ///   monitor it is placed on.
+    /// - **Windows / macOS / iOS / Android / Web / Orbital:** Unsupported, a regular window is
+    ///   created.
+    ///
+    /// [`WindowEvent::PopupDismissed`]: crate::event::WindowEvent::PopupDismissed
+    #[inline]
+    pub fn with_popup(mut self, popup: PopupAttributes) -> Self {
+        self.popup = Some(popup);
+        self
+    }
+
+    /// Restore the window placement saved with [`Window::placement`].
+    ///
+    /// The placement overrides [`WindowAttributes::inner_size`],
+    /// [`WindowAttributes::position`], [`WindowAttributes::maximized`], and
+    /// [`WindowAttributes::fullscreen`] once the window is created. See [`WindowPlacement`] for
+    /// how it's matched against the currently available monitors.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn with_placement(mut self, placement: WindowPlacement) -> Self {
+        self.placement = Some(placement);
+        self
+    }
+}
+
+/// The placement rules of a popup window.
+///
+/// The popup is placed in three steps: a point on the anchor rectangle is selected with
+/// [`anchor`], the popup is then placed on the side of that point described by [`gravity`] and
+/// moved by [`offset`]. If the result doesn't fit on the screen, the popup is adjusted according
+/// to [`constraint_adjustment`].
+///
+/// See [`WindowAttributes::with_popup`].
+///
+/// [`anchor`]: Self::anchor
+/// [`gravity`]: Self::gravity
+/// [`offset`]: Self::offset
+/// [`constraint_adjustment`]: Self::constraint_adjustment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PopupAttributes {
+    /// The window the popup is attached to.
+    pub parent: WindowId,
+    /// The origin of the anchor rectangle, relative to the parent's inner position.
+    pub anchor_rect_position: Position,
+    /// The size of the anchor rectangle.
+    pub anchor_rect_size: Size,
+    /// The point on the anchor rectangle the popup is attached to.
+    ///
+    /// The default is [`PopupAnchor::Center`].
+    pub anchor: PopupAnchor,
+    /// The direction in which the popup extends from the anchor point.
+    ///
+    /// The default is [`PopupAnchor::Center`].
+    pub gravity: PopupAnchor,
+    /// The offset of the popup from the anchor point.
+    ///
+    /// The default is `(0, 0)`.
+    pub offset: Position,
+    /// How the popup is adjusted when it doesn't fit on the screen.
+    ///
+    /// The default is [`PopupConstraintAdjustment::empty()`].
+    pub constraint_adjustment: PopupConstraintAdjustment,
+    /// Whether the popup grabs the input.
+    ///
+    /// A grabbing popup is dismissed when the user clicks outside of the popup windows of the
+    /// client. The grab must be requested in response to a user input, like a button press.
+    ///
+    /// The default is `false`, which is the right choice for tooltips.
+    pub grab: bool,
+}
+
+impl PopupAttributes {
+    /// Create popup attributes for the given `parent` with the given anchor rectangle.
+    pub fn new(
+        parent: WindowId,
+        anchor_rect_position: impl Into<Position>,
+        anchor_rect_size: impl Into<Size>,
+    ) -> Self {
+        Self {
+            parent,
+            anchor_rect_position: anchor_rect_position.into(),
+            anchor_rect_size: anchor_rect_size.into(),
+            anchor: PopupAnchor::Center,
+            gravity: PopupAnchor::Center,
+            offset: PhysicalPosition::new(0, 0).into(),
+            constraint_adjustment: PopupConstraintAdjustment::empty(),
+            grab: false,
+        }
+    }
+
+    /// Sets the point on the anchor rectangle the popup is attached to.
+    #[inline]
+    pub fn with_anchor(mut self, anchor: PopupAnchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Sets the direction in which the popup extends from the anchor point.
+    #[inline]
+    pub fn with_gravity(mut self, gravity: PopupAnchor) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    /// Sets the offset of the popup from the anchor point.
+    #[inline]
+    pub fn with_offset(mut self, offset: impl Into<Position>) -> Self {
+        self.offset = offset.into();
+        self
+    }
+
+    /// Sets how the popup is adjusted when it doesn't fit on the screen.
+    #[inline]
+    pub fn with_constraint_adjustment(mut self, adjustment: PopupConstraintAdjustment) -> Self {
+        self.constraint_adjustment = adjustment;
+        self
+    }
+
+    /// Sets whether the popup grabs the input.
+    #[inline]
+    pub fn with_grab(mut self, grab: bool) -> Self {
+        self.grab = grab;
+        self
+    }
+}
+
+/// An edge or a corner of a rectangle, used to place a popup.
+///
+/// When used as [`PopupAttributes::anchor`] it selects a point on the anchor rectangle, when
+/// used as [`PopupAttributes::gravity`] it selects the direction the popup extends to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PopupAnchor {
+    #[default]
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    BottomLeft,
+    TopRight,
+    BottomRight,
+}
+
+bitflags::bitflags! {
+    /// The ways a popup may be adjusted when it would be placed outside of the screen.
+    ///
+    /// When several adjustments are allowed, they're attempted in the order flip, slide, resize.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct PopupConstraintAdjustment: u32 {
+        /// Move the popup horizontally until it fits.
+        const SLIDE_X = 1 << 0;
+        /// Move the popup vertically until it fits.
+        const SLIDE_Y = 1 << 1;
+        /// Mirror the anchor and gravity horizontally.
+        const FLIP_X = 1 << 2;
+        /// Mirror the anchor and gravity vertically.
+        const FLIP_Y = 1 << 3;
+        /// Shrink the popup horizontally until it fits.
+        const RESIZE_X = 1 << 4;
+        /// Shrink the popup vertically until it fits.
+        const RESIZE_Y = 1 << 5;
+    }
 }
 
 /// Base Window functions.
@@ -952,6 +1182,83 @@ impl Window {
         self.window.maybe_queue_on_main(move |w| w.set_blur(blur))
     }
 
+    /// Prevent the screen from blanking and the system from going idle.
+    ///
+    /// The inhibition is released once it's disabled or the window is dropped.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Only works with the `zwp_idle_inhibit_manager_v1` protocol, and only while
+    ///   the window is visible.
+    /// - **X11:** Suspends the screen saver with the `MIT-SCREEN-SAVER` extension regardless of the
+    ///   window visibility.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    #[inline]
+    pub fn set_idle_inhibit(&self, inhibit: bool) {
+        let _span = tracing::debug_span!("winit::Window::set_idle_inhibit", inhibit).entered();
+        self.window.maybe_queue_on_main(move |w| w.set_idle_inhibit(inhibit))
+    }
+
+    /// Request the system keyboard shortcuts, like <kbd>Alt</kbd>+<kbd>Tab</kbd>, to be delivered
+    /// to the window instead of being handled by the compositor or the window manager while the
+    /// window is focused.
+    ///
+    /// This is meant for the remote desktop and virtual machine clients forwarding the input.
+    /// Whether the request is granted is reported with
+    /// [`WindowEvent::KeyboardShortcutsInhibited`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Requires the `zwp_keyboard_shortcuts_inhibit_manager_v1` protocol. The
+    ///   compositor may ask the user for the permission and always keeps some shortcuts.
+    /// - **X11:** The keyboard is grabbed while the window is focused.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    ///
+    /// [`WindowEvent::KeyboardShortcutsInhibited`]: crate::event::WindowEvent::KeyboardShortcutsInhibited
+    #[inline]
+    pub fn set_keyboard_shortcuts_inhibited(&self, inhibited: bool) {
+        let _span =
+            tracing::debug_span!("winit::Window::set_keyboard_shortcuts_inhibited", inhibited)
+                .entered();
+        self.window.maybe_queue_on_main(move |w| w.set_keyboard_shortcuts_inhibited(inhibited))
+    }
+
+    /// Sets how the held keys are repeated while the window is focused.
+    ///
+    /// The repeated presses are reported with [`KeyEvent::repeat`] set to `true`. The custom rate
+    /// with the zero interval is treated as [`KeyRepeat::Disabled`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The custom rate is emulated, since the server repeat rate is global.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    ///
+    /// [`KeyEvent::repeat`]: crate::event::KeyEvent::repeat
+    #[inline]
+    pub fn set_key_repeat(&self, repeat: KeyRepeat) {
+        let _span =
+            tracing::debug_span!("winit::Window::set_key_repeat", repeat = ?repeat).entered();
+        let repeat = match repeat {
+            KeyRepeat::Custom(rate) if rate.interval.is_zero() => KeyRepeat::Disabled,
+            repeat => repeat,
+        };
+        self.window.maybe_queue_on_main(move |w| w.set_key_repeat(repeat))
+    }
+
+    /// Gets the key repeat rate in effect for the window.
+    ///
+    /// Returns `None` when the keys aren't repeated.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** The system rate is the one of the last keyboard which focused the window.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, returns `None`.
+    #[inline]
+    pub fn key_repeat_rate(&self) -> Option<KeyRepeatRate> {
+        let _span = tracing::debug_span!("winit::Window::key_repeat_rate",).entered();
+        self.window.maybe_wait_on_main(|w| w.key_repeat_rate())
+    }
+
     /// Modifies the window's visibility.
     ///
     /// If `false`, this will hide the window. If `true`, this will show the window.
@@ -1092,6 +1399,42 @@ impl Window {
         self.window.maybe_wait_on_main(|w| w.is_maximized())
     }
 
+    /// Gets the bounds the window is restored to once it's neither maximized nor fullscreen.
+    ///
+    /// When the window is in the normal state, these are its current bounds. Returns `None` when
+    /// they aren't known yet.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** The position is always `None`. Tiled windows are not in the normal state.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    #[inline]
+    pub fn normal_bounds(&self) -> Option<WindowBounds> {
+        let _span = tracing::debug_span!("winit::Window::normal_bounds",).entered();
+        self.window.maybe_wait_on_main(|w| w.normal_bounds())
+    }
+
+    /// Sets the bounds the window is restored to once it's neither maximized nor fullscreen.
+    ///
+    /// The `position` is the outer position, like in [`Window::set_outer_position`], and `None`
+    /// leaves the position up to the system. When the window is in the normal state, the bounds
+    /// are applied right away.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** The `position` is ignored.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    #[inline]
+    pub fn set_normal_bounds(&self, position: Option<Position>, inner_size: Size) {
+        let _span = tracing::debug_span!(
+            "winit::Window::set_normal_bounds",
+            position = ?position,
+            inner_size = ?inner_size
+        )
+        .entered();
+        self.window.maybe_queue_on_main(move |w| w.set_normal_bounds(position, inner_size))
+    }
+
     /// Sets the window to fullscreen or back.
     ///
     /// ## Platform-specific
@@ -1159,6 +1502,41 @@ impl Window {
         self.window.maybe_queue_on_main(move |w| w.set_decorations(decorations))
     }
 
+    /// Gets the window's current states set by the window manager.
+    ///
+    /// Changes are reported with [`WindowEvent::StateChanged`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The tiled edges are derived from the maximized state along each axis, and the
+    ///   window is suspended when it's hidden.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, always empty.
+    ///
+    /// [`WindowEvent::StateChanged`]: crate::event::WindowEvent::StateChanged
+    #[inline]
+    pub fn state(&self) -> WindowState {
+        let _span = tracing::debug_span!("winit::Window::state",).entered();
+        self.window.maybe_wait_on_main(|w| w.state())
+    }
+
+    /// Gets the window management actions supported by the window manager.
+    ///
+    /// Returns `None` when they aren't known. Changes are reported with
+    /// [`WindowEvent::WmCapabilitiesChanged`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Derived from `_NET_WM_ALLOWED_ACTIONS`, [`WmCapabilities::WINDOW_MENU`] is never
+    ///   set.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported, always `None`.
+    ///
+    /// [`WindowEvent::WmCapabilitiesChanged`]: crate::event::WindowEvent::WmCapabilitiesChanged
+    #[inline]
+    pub fn wm_capabilities(&self) -> Option<WmCapabilities> {
+        let _span = tracing::debug_span!("winit::Window::wm_capabilities",).entered();
+        self.window.maybe_wait_on_main(|w| w.wm_capabilities())
+    }
+
     /// Gets the window's current decorations state.
     ///
     /// Returns `true` when windows are decorated (server-side or by Winit).
@@ -1626,6 +2004,19 @@ impl Window {
         let _span = tracing::debug_span!("winit::Window::primary_monitor",).entered();
         self.window.maybe_wait_on_main(|w| w.primary_monitor().map(|inner| MonitorHandle { inner }))
     }
+
+    /// Take the snapshot of the window placement, which could be restored with
+    /// [`WindowAttributes::with_placement`].
+    ///
+    /// The geometry of the maximized or fullscreen window is taken from [`Window::normal_bounds`],
+    /// so the window is restored to the size it had before it was maximized. When the normal
+    /// bounds are unsupported, the current geometry is stored instead.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn placement(&self) -> WindowPlacement {
+        let _span = tracing::debug_span!("winit::Window::placement",).entered();
+        WindowPlacement::from_window(self)
+    }
 }
 
 #[cfg(feature = "rwh_06")]
@@ -1748,6 +2139,19 @@ impl From<ResizeDirection> for CursorIcon {
     }
 }
 
+/// The bounds of the window in the normal state.
+///
+/// See [`Window::normal_bounds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowBounds {
+    /// The outer position of the window, see [`Window::outer_position`].
+    pub position: Option<PhysicalPosition<i32>>,
+    /// The inner size of the window, see [`Window::inner_size`].
+    pub inner_size: PhysicalSize<u32>,
+    /// The outer size of the window, see [`Window::outer_size`].
+    pub outer_size: PhysicalSize<u32>,
+}
+
 /// Fullscreen modes.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Fullscreen {
@@ -1757,6 +2161,64 @@ pub enum Fullscreen {
     Borderless(Option<MonitorHandle>),
 }
 
+/// The side drawing the window decorations.
+///
+/// See [`WindowEvent::DecorationModeChanged`].
+///
+/// [`WindowEvent::DecorationModeChanged`]: crate::event::WindowEvent::DecorationModeChanged
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DecorationMode {
+    /// The decorations are drawn by the application, or by winit on its behalf.
+    Client,
+
+    /// The decorations are drawn by the compositor or the window manager.
+    Server,
+}
+
+bitflags::bitflags! {
+    /// The states of the window set by the window manager.
+    ///
+    /// See [`Window::state`] and [`WindowEvent::StateChanged`].
+    ///
+    /// [`WindowEvent::StateChanged`]: crate::event::WindowEvent::StateChanged
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct WindowState: u32 {
+        /// The left edge of the window is adjacent to another window or to the screen edge.
+        const TILED_LEFT = 1 << 0;
+        /// The right edge of the window is adjacent to another window or to the screen edge.
+        const TILED_RIGHT = 1 << 1;
+        /// The top edge of the window is adjacent to another window or to the screen edge.
+        const TILED_TOP = 1 << 2;
+        /// The bottom edge of the window is adjacent to another window or to the screen edge.
+        const TILED_BOTTOM = 1 << 3;
+        /// The window isn't visible to the user, e.g. it's minimized or fully obscured, thus it
+        /// should stop animating.
+        const SUSPENDED = 1 << 4;
+    }
+}
+
+bitflags::bitflags! {
+    /// The window management actions supported by the window manager.
+    ///
+    /// The client side decorations should hide the buttons for the unsupported actions.
+    ///
+    /// See [`Window::wm_capabilities`] and [`WindowEvent::WmCapabilitiesChanged`].
+    ///
+    /// [`WindowEvent::WmCapabilitiesChanged`]: crate::event::WindowEvent::WmCapabilitiesChanged
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct WmCapabilities: u32 {
+        /// [`Window::show_window_menu`] is supported.
+        const WINDOW_MENU = 1 << 0;
+        /// [`Window::set_maximized`] is supported.
+        const MAXIMIZE = 1 << 1;
+        /// [`Window::set_fullscreen`] is supported.
+        const FULLSCREEN = 1 << 2;
+        /// [`Window::set_minimized`] is supported.
+        const MINIMIZE = 1 << 3;
+    }
+}
+
 /// The theme variant to use.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -1851,6 +2313,29 @@ impl Default for ImePurpose {
     }
 }
 
+/// The key repeat policy for use in [`Window::set_key_repeat`].
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum KeyRepeat {
+    /// Repeat the keys at the system rate (default).
+    #[default]
+    System,
+    /// Don't repeat the keys.
+    Disabled,
+    /// Repeat the keys at the custom rate.
+    Custom(KeyRepeatRate),
+}
+
+/// The rate at which the held key is repeated.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct KeyRepeatRate {
+    /// The delay between the key press and the first repeat.
+    pub delay: Duration,
+    /// The interval between the repeats.
+    ///
+    /// The keys aren't repeated when it's zero.
+    pub interval: Duration,
+}
+
 /// An opaque token used to activate the [`Window`].
 ///
 /// [`Window`]: crate::window::Window